@@ -0,0 +1,7 @@
+package com.swipepredictor
+
+/**
+ * Thrown by the native bridge when a predictor ID refers to a predictor that
+ * was removed, or was created before the manager was re-initialized.
+ */
+class StalePredictorException(message: String) : IllegalStateException(message)
@@ -5,6 +5,7 @@ import android.os.HandlerThread
 import android.view.Choreographer
 import com.facebook.react.bridge.*
 import com.facebook.react.modules.core.DeviceEventManagerModule
+import java.nio.DoubleBuffer
 import java.util.concurrent.ConcurrentHashMap
 import kotlin.math.roundToLong
 
@@ -25,15 +26,89 @@ class SwipePredictorModule(reactContext: ReactApplicationContext) :
             velocitySmoothingFactor: Double
         )
         
+        @JvmStatic
+        external fun nativeUpdateConfig(
+            decelerationRate: Double,
+            minVelocityThreshold: Double,
+            minGestureTimeMs: Double,
+            applyToExisting: Boolean
+        )
+        
+        @JvmStatic
+        external fun nativeCreateContext(
+            decelerationRate: Double,
+            minVelocityThreshold: Double,
+            minGestureTimeMs: Double
+        ): Int
+        
+        @JvmStatic
+        external fun nativeDestroyContext(contextId: Int)
+        
+        @JvmStatic
+        external fun nativeListContexts(): IntArray?
+        
+        @JvmStatic
+        external fun nativeUpdateContextConfig(
+            contextId: Int,
+            decelerationRate: Double,
+            minVelocityThreshold: Double,
+            minGestureTimeMs: Double,
+            applyToExisting: Boolean
+        )
+        
+        @JvmStatic
+        external fun nativeInitPredictorInContext(contextId: Int): Int
+        
+        // Physical pixels per point of the coordinates passed to and returned
+        // from predictors. Throws IllegalStateException while samples are buffered.
+        @JvmStatic
+        external fun nativeSetDisplayScale(pixelsPerPoint: Double)
+        
+        @JvmStatic
+        external fun nativeSetContextDisplayScale(contextId: Int, pixelsPerPoint: Double)
+        
         @JvmStatic
         external fun nativeInitPredictor(): Int
         
         @JvmStatic
         external fun nativeAddTouchPoint(predictorId: Int, x: Double, y: Double, timestamp: Double)
         
+        // Returns ADD_POINT_OK, or the native error code explaining why the
+        // sample was rejected. Never throws, regardless of strict mode.
+        @JvmStatic
+        external fun nativeAddTouchPointChecked(predictorId: Int, x: Double, y: Double, timestamp: Double): Int
+        
+        // In strict mode nativeAddTouchPoint throws InvalidTouchPointException
+        // or TimestampOutOfOrderException instead of dropping rejected samples
+        @JvmStatic
+        external fun nativeSetStrictMode(enabled: Boolean)
+        
+        @JvmStatic
+        external fun nativeGetRejectedSampleCount(predictorId: Int): Long
+        
+        const val ADD_POINT_OK = 0
+        const val ADD_POINT_INVALID_TIMESTAMP = 2
+        const val ADD_POINT_TIMESTAMP_OUT_OF_ORDER = 3
+        const val ADD_POINT_INVALID_COORDINATE = 4
+        const val ADD_POINT_STALE_HANDLE = 10
+        
         @JvmStatic
         external fun nativeGetPrediction(predictorId: Int): Prediction?
         
+        // Allocation-free variants: write [x, y, confidence] into the caller's
+        // storage and return one of the PREDICTION_* status codes
+        @JvmStatic
+        external fun nativeGetPredictionInto(predictorId: Int, out: DoubleArray): Int
+        
+        @JvmStatic
+        external fun nativeGetPredictionIntoBuffer(predictorId: Int, out: DoubleBuffer): Int
+        
+        const val PREDICTION_FIELDS = 3
+        const val PREDICTION_WRITTEN = 1
+        const val PREDICTION_UNAVAILABLE = 0
+        const val PREDICTION_INVALID_ARGUMENT = -1
+        const val PREDICTION_STALE_HANDLE = -2
+        
         @JvmStatic
         external fun nativeResetPredictor(predictorId: Int)
         
@@ -42,6 +117,9 @@ class SwipePredictorModule(reactContext: ReactApplicationContext) :
         
         @JvmStatic
         external fun nativeRemovePredictor(predictorId: Int)
+        
+        @JvmStatic
+        external fun nativeShutdown()
     }
     
     private val handlerThread = HandlerThread("SwipePredictorThread").apply { start() }
@@ -49,6 +127,8 @@ class SwipePredictorModule(reactContext: ReactApplicationContext) :
     private val activePredictors = ConcurrentHashMap<Int, PredictorInfo>()
     private var choreographer: Choreographer? = null
     private var isFrameCallbackScheduled = false
+    // Reused for every per-frame prediction; only touched on the background thread
+    private val predictionScratch = DoubleArray(PREDICTION_FIELDS)
     
     data class PredictorInfo(
         val predictorId: Int,
@@ -64,6 +144,26 @@ class SwipePredictorModule(reactContext: ReactApplicationContext) :
     
     override fun getName() = "SwipePredictorModule"
     
+    // React Native reports touches in dp, so predictors start at a scale of 1;
+    // JS passes displayScale to setDisplayScale when feeding raw pixels
+    override fun getConstants(): Map<String, Any> = mapOf(
+        "displayScale" to reactApplicationContext.resources.displayMetrics.density.toDouble()
+    )
+    
+    @ReactMethod
+    fun setDisplayScale(pixelsPerPoint: Double, promise: Promise) {
+        backgroundHandler.post {
+            try {
+                nativeSetDisplayScale(pixelsPerPoint)
+                promise.resolve(null)
+            } catch (e: IllegalArgumentException) {
+                promise.reject("INVALID_SCALE", e.message, e)
+            } catch (e: IllegalStateException) {
+                promise.reject("SCALE_CHANGE_FAILED", e.message, e)
+            }
+        }
+    }
+    
     @ReactMethod
     fun createPredictor(options: ReadableMap, promise: Promise) {
         backgroundHandler.post {
@@ -100,7 +200,43 @@ class SwipePredictorModule(reactContext: ReactApplicationContext) :
     @ReactMethod
     fun addTouchPoint(predictorId: Int, x: Double, y: Double, timestamp: Double) {
         backgroundHandler.post {
-            nativeAddTouchPoint(predictorId, x, y, timestamp)
+            try {
+                nativeAddTouchPoint(predictorId, x, y, timestamp)
+            } catch (e: StalePredictorException) {
+                activePredictors.remove(predictorId)
+            } catch (e: IllegalArgumentException) {
+                // InvalidTouchPointException in strict mode, or an argument the
+                // bridge rejects up front; the sample is dropped either way
+                val params = Arguments.createMap().apply {
+                    putInt("predictorId", predictorId)
+                    putString("reason", rejectionReason(e))
+                    putString("message", e.message)
+                }
+                sendEvent("onTouchPointRejected", params)
+            }
+        }
+    }
+    
+    private fun rejectionReason(e: IllegalArgumentException) = when (e) {
+        is TimestampOutOfOrderException -> "TIMESTAMP_OUT_OF_ORDER"
+        is InvalidTouchPointException -> "INVALID_TOUCH_POINT"
+        else -> "INVALID_ARGUMENT"
+    }
+    
+    @ReactMethod
+    fun setStrictMode(enabled: Boolean) {
+        nativeSetStrictMode(enabled)
+    }
+    
+    @ReactMethod
+    fun getRejectedSampleCount(predictorId: Int, promise: Promise) {
+        backgroundHandler.post {
+            try {
+                promise.resolve(nativeGetRejectedSampleCount(predictorId).toDouble())
+            } catch (e: StalePredictorException) {
+                activePredictors.remove(predictorId)
+                promise.reject("STALE_PREDICTOR", "Predictor $predictorId no longer exists", e)
+            }
         }
     }
     
@@ -121,6 +257,9 @@ class SwipePredictorModule(reactContext: ReactApplicationContext) :
                 } else {
                     promise.resolve(null)
                 }
+            } catch (e: StalePredictorException) {
+                activePredictors.remove(predictorId)
+                promise.reject("STALE_PREDICTOR", "Predictor $predictorId no longer exists", e)
             } catch (e: Exception) {
                 promise.reject("PREDICTION_ERROR", "Error getting prediction", e)
             }
@@ -130,7 +269,11 @@ class SwipePredictorModule(reactContext: ReactApplicationContext) :
     @ReactMethod
     fun resetPredictor(predictorId: Int) {
         backgroundHandler.post {
-            nativeResetPredictor(predictorId)
+            try {
+                nativeResetPredictor(predictorId)
+            } catch (e: StalePredictorException) {
+                activePredictors.remove(predictorId)
+            }
         }
     }
     
@@ -140,6 +283,9 @@ class SwipePredictorModule(reactContext: ReactApplicationContext) :
             try {
                 val isCancelled = nativeDetectCancellation(predictorId) == 1
                 promise.resolve(isCancelled)
+            } catch (e: StalePredictorException) {
+                activePredictors.remove(predictorId)
+                promise.reject("STALE_PREDICTOR", "Predictor $predictorId no longer exists", e)
             } catch (e: Exception) {
                 promise.reject("CANCELLATION_ERROR", "Error detecting cancellation", e)
             }
@@ -149,7 +295,11 @@ class SwipePredictorModule(reactContext: ReactApplicationContext) :
     @ReactMethod
     fun removePredictor(predictorId: Int) {
         backgroundHandler.post {
-            nativeRemovePredictor(predictorId)
+            try {
+                nativeRemovePredictor(predictorId)
+            } catch (e: StalePredictorException) {
+                // Already released natively; just drop our bookkeeping
+            }
             activePredictors.remove(predictorId)
             
             if (activePredictors.isEmpty()) {
@@ -197,15 +347,21 @@ class SwipePredictorModule(reactContext: ReactApplicationContext) :
     private fun checkAndSendPrediction(predictorId: Int) {
         backgroundHandler.post {
             try {
-                val prediction = nativeGetPrediction(predictorId)
+                val status = nativeGetPredictionInto(predictorId, predictionScratch)
                 val info = activePredictors[predictorId]
                 
-                if (prediction != null && info != null && prediction.confidence >= info.confidenceThreshold) {
+                if (status == PREDICTION_STALE_HANDLE) {
+                    activePredictors.remove(predictorId)
+                    return@post
+                }
+                
+                val confidence = predictionScratch[2]
+                if (status == PREDICTION_WRITTEN && info != null && confidence >= info.confidenceThreshold) {
                     val params = Arguments.createMap().apply {
                         putInt("predictorId", predictorId)
-                        putDouble("x", prediction.x)
-                        putDouble("y", prediction.y)
-                        putDouble("confidence", prediction.confidence)
+                        putDouble("x", predictionScratch[0])
+                        putDouble("y", predictionScratch[1])
+                        putDouble("confidence", confidence)
                     }
                     
                     sendEvent("onPrediction", params)
@@ -0,0 +1,14 @@
+package com.swipepredictor
+
+/**
+ * Thrown by `nativeAddTouchPoint` in strict mode when the predictor rejects a
+ * sample, e.g. for a NaN coordinate, an invalid timestamp or a gesture that
+ * has already ended.
+ */
+open class InvalidTouchPointException(message: String) : IllegalArgumentException(message)
+
+/**
+ * Thrown in strict mode when a sample's timestamp is earlier than the
+ * previous sample's.
+ */
+class TimestampOutOfOrderException(message: String) : InvalidTouchPointException(message)
@@ -0,0 +1,331 @@
+/*
+ * C API for the swipe_predictor Rust core.
+ *
+ * Intended for a shared C++ TurboModule / JSI host object that calls the
+ * predictor directly on both iOS and Android, bypassing the platform bridges.
+ * Every call is synchronous and callback-free, and is safe to make from any
+ * thread.
+ *
+ * Lifetime:
+ *   - A context owns the physics config that its new predictors are created with.
+ *   - A predictor handle shares ownership of its context, so it stays valid
+ *     after swipe_predictor_context_destroy(). Free each object exactly once,
+ *     e.g. from the host object's destructor.
+ *
+ * Units:
+ *   - Coordinates passed to and returned from handle functions (touch points,
+ *     predictions, snap points, dismissal distances) are in pixels, converted
+ *     with the context's display scale. It defaults to 1, i.e. pixels = points.
+ *   - Configuration thresholds (physics, cancellation, events, input filter)
+ *     are in density-independent points, so they behave the same on any screen.
+ *
+ * Keep in sync with src/rust/src/ffi.rs.
+ */
+#ifndef SWIPE_PREDICTOR_H
+#define SWIPE_PREDICTOR_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct SwipePredictorContext SwipePredictorContext;
+typedef struct SwipePredictorHandle SwipePredictorHandle;
+typedef struct SwipePredictorWriter SwipePredictorWriter;
+typedef struct SwipePredictorReader SwipePredictorReader;
+
+typedef struct SwipePredictorConfig {
+    double deceleration_rate;
+    double min_velocity_threshold;
+    double min_gesture_time_ms;
+} SwipePredictorConfig;
+
+/* status is SWIPE_PREDICTOR_OK when x, y and confidence hold a prediction */
+typedef struct SwipePredictorPrediction {
+    int32_t status;
+    double x;
+    double y;
+    double confidence;
+} SwipePredictorPrediction;
+
+/* Latest prediction from an SPSC reader; sequence changes on every publish */
+typedef struct SwipePredictorSnapshot {
+    uint64_t sequence;
+    int32_t status;
+    double x;
+    double y;
+    double confidence;
+} SwipePredictorSnapshot;
+
+/* Thresholds for swipe_predictor_events_enable(); snap_points holds
+ * snap_point_count x/y pairs and is copied. min_distance and snap_points are
+ * in pixels at the context's display scale. */
+typedef struct SwipePredictorEventConfig {
+    double min_distance;
+    double confidence_band;
+    int32_t direction_flip;
+    const double *snap_points;
+    size_t snap_point_count;
+} SwipePredictorEventConfig;
+
+/* changes is a mask of SWIPE_PREDICTOR_CHANGE_*; snap_index is -1 without snap points */
+typedef struct SwipePredictorEvent {
+    uint32_t changes;
+    int32_t snap_index;
+    double timestamp;
+    double x;
+    double y;
+    double confidence;
+} SwipePredictorEvent;
+
+#define SWIPE_PREDICTOR_CHANGE_FIRST (1u << 0)
+#define SWIPE_PREDICTOR_CHANGE_MOVED (1u << 1)
+#define SWIPE_PREDICTOR_CHANGE_CONFIDENCE_BAND (1u << 2)
+#define SWIPE_PREDICTOR_CHANGE_SNAP_TARGET (1u << 3)
+#define SWIPE_PREDICTOR_CHANGE_DIRECTION_FLIP (1u << 4)
+
+/* Input filter for swipe_predictor_set_input_filter(). max_acceleration is in
+ * px/s^2 and beta per px/s, at the context's display scale; a max_acceleration
+ * of 0 disables spike rejection, and the One Euro fields are ignored unless
+ * one_euro_enabled is non-zero. */
+typedef struct SwipePredictorInputFilterConfig {
+    double max_acceleration;
+    int32_t merge_duplicates;
+    int32_t one_euro_enabled;
+    double min_cutoff;
+    double beta;
+    double derivative_cutoff;
+} SwipePredictorInputFilterConfig;
+
+typedef struct SwipePredictorFilterStats {
+    uint64_t spikes_rejected;
+    uint64_t duplicates_merged;
+} SwipePredictorFilterStats;
+
+/* Result of swipe_predictor_context_load_config(). status is SWIPE_PREDICTOR_OK
+ * or an error code; line and column (1-based) locate a syntax error, and
+ * field and value name an out-of-range setting. Strings are NUL-terminated. */
+typedef struct SwipePredictorConfigError {
+    int32_t status;
+    uint32_t line;
+    uint32_t column;
+    double value;
+    char field[64];
+    char message[256];
+} SwipePredictorConfigError;
+
+/* Formats for swipe_predictor_context_load_config() */
+#define SWIPE_PREDICTOR_CONFIG_JSON 0
+#define SWIPE_PREDICTOR_CONFIG_TOML 1
+
+/* Result of swipe_predictor_predict_dismissal(); distances are along the
+ * gesture's dominant direction, measured from its first point */
+typedef struct SwipePredictorDismissal {
+    int32_t status;
+    int32_t will_dismiss;
+    double probability;
+    double current_distance;
+    double projected_distance;
+} SwipePredictorDismissal;
+
+/* Affine map for swipe_predictor_set_transform(), laid out like
+ * CGAffineTransform: x' = a*x + c*y + tx, y' = b*x + d*y + ty */
+typedef struct SwipePredictorTransform {
+    double a;
+    double b;
+    double c;
+    double d;
+    double tx;
+    double ty;
+} SwipePredictorTransform;
+
+/* Thresholds for swipe_predictor_set_cancellation_config(); stop_speed (px/s)
+ * and min_displacement (px) are at the context's display scale */
+typedef struct SwipePredictorCancellationConfig {
+    double reversal_angle_deg;
+    double stop_speed;
+    double slow_velocity_ratio;
+    double min_displacement;
+    uint32_t hysteresis_samples;
+} SwipePredictorCancellationConfig;
+
+/* Values returned by swipe_predictor_cancellation_reason() */
+#define SWIPE_PREDICTOR_CANCEL_NONE 0
+#define SWIPE_PREDICTOR_CANCEL_DIRECTION_REVERSAL 1
+#define SWIPE_PREDICTOR_CANCEL_STOPPED 2
+#define SWIPE_PREDICTOR_CANCEL_SLOWED_BELOW_THRESHOLD 3
+#define SWIPE_PREDICTOR_CANCEL_RETURNED_PAST_ORIGIN 4
+
+/* Status codes; positive values mirror PredictorError::code() */
+#define SWIPE_PREDICTOR_OK 0
+#define SWIPE_PREDICTOR_ERR_INTERNAL (-1)
+#define SWIPE_PREDICTOR_ERR_INSUFFICIENT_DATA 1
+#define SWIPE_PREDICTOR_ERR_INVALID_TIMESTAMP 2
+#define SWIPE_PREDICTOR_ERR_TIMESTAMP_OUT_OF_ORDER 3
+#define SWIPE_PREDICTOR_ERR_INVALID_COORDINATE 4
+#define SWIPE_PREDICTOR_ERR_GESTURE_TOO_SHORT 5
+#define SWIPE_PREDICTOR_ERR_VELOCITY_TOO_LOW 6
+#define SWIPE_PREDICTOR_ERR_INVALID_CONFIGURATION 7
+#define SWIPE_PREDICTOR_ERR_NUMERICAL 8
+#define SWIPE_PREDICTOR_ERR_INVALID_HANDLE 9
+#define SWIPE_PREDICTOR_ERR_STALE_HANDLE 10
+#define SWIPE_PREDICTOR_ERR_HANDLE_LIMIT_REACHED 11
+#define SWIPE_PREDICTOR_ERR_INVALID_STATE_TRANSITION 12
+#define SWIPE_PREDICTOR_ERR_GESTURE_CANCELLED 13
+
+/* Policies for swipe_predictor_set_duplicate_timestamp_policy() */
+#define SWIPE_PREDICTOR_DUPLICATES_MERGE 0
+#define SWIPE_PREDICTOR_DUPLICATES_AVERAGE 1
+#define SWIPE_PREDICTOR_DUPLICATES_INTERPOLATE 2
+#define SWIPE_PREDICTOR_DUPLICATES_REJECT 3
+#define SWIPE_PREDICTOR_DUPLICATES_KEEP 4
+
+/* Interpolations for swipe_predictor_set_resampling() */
+#define SWIPE_PREDICTOR_INTERPOLATION_LINEAR 0
+#define SWIPE_PREDICTOR_INTERPOLATION_CATMULL_ROM 1
+
+/* Values returned by swipe_predictor_state() */
+#define SWIPE_PREDICTOR_STATE_IDLE 0
+#define SWIPE_PREDICTOR_STATE_ACTIVE 1
+#define SWIPE_PREDICTOR_STATE_ENDED 2
+#define SWIPE_PREDICTOR_STATE_CANCELLED 3
+
+void swipe_predictor_init_panic_handler(void);
+
+/* Contexts. The create functions return NULL on invalid configuration. */
+SwipePredictorContext *swipe_predictor_context_create(double deceleration_rate,
+                                                      double min_velocity_threshold,
+                                                      double min_gesture_time_ms);
+SwipePredictorContext *swipe_predictor_context_create_default(void);
+SwipePredictorContext *swipe_predictor_context_create_with_config(SwipePredictorConfig config);
+SwipePredictorConfig swipe_predictor_context_get_config(SwipePredictorContext *ctx);
+/* Returns 1 on success, 0 on invalid configuration (nothing changes) */
+int32_t swipe_predictor_context_update_config(SwipePredictorContext *ctx,
+                                              double deceleration_rate,
+                                              double min_velocity_threshold,
+                                              double min_gesture_time_ms,
+                                              int32_t apply_to_existing);
+/* Physical pixels per point, e.g. UIScreen.scale or DisplayMetrics.density.
+ * Returns SWIPE_PREDICTOR_OK or SWIPE_PREDICTOR_ERR_INVALID_CONFIGURATION,
+ * including for a new scale while any handle has samples buffered. */
+int32_t swipe_predictor_context_set_display_scale(SwipePredictorContext *ctx, double pixels_per_point);
+double swipe_predictor_context_get_display_scale(SwipePredictorContext *ctx);
+/*
+ * Load a JSON or TOML config document, e.g.
+ *   preset = "ios_fast"            # ios, android, ios_fast or custom
+ *   buffer_size = 12
+ *   [physics]     deceleration_rate, min_velocity_threshold, min_gesture_time_ms
+ *   [estimator]   recency_exponent, release_stop_ms
+ *   [confidence]  speed_scale, duration_scale, deceleration_factor, deceleration_penalty
+ * Every key is optional and unknown keys are errors. On failure nothing
+ * changes. Existing predictors keep their buffer size. Only built with the
+ * crate's "config" feature.
+ */
+SwipePredictorConfigError swipe_predictor_context_load_config(SwipePredictorContext *ctx,
+                                                              const char *document,
+                                                              int32_t format,
+                                                              int32_t apply_to_existing);
+void swipe_predictor_context_destroy(SwipePredictorContext *ctx);
+
+/* Predictors. Returns NULL when the context is NULL or full. */
+SwipePredictorHandle *swipe_predictor_create_in_context(SwipePredictorContext *ctx);
+void swipe_predictor_destroy(SwipePredictorHandle *handle);
+
+/* Returns 1 if the point was accepted, 0 otherwise */
+int32_t swipe_predictor_add_point(SwipePredictorHandle *handle, double x, double y, double timestamp);
+/* Returns SWIPE_PREDICTOR_OK or the status code explaining the rejection */
+int32_t swipe_predictor_add_point_checked(SwipePredictorHandle *handle, double x, double y, double timestamp);
+uint64_t swipe_predictor_rejected_count(SwipePredictorHandle *handle);
+
+/*
+ * Filter samples before they enter the buffer; pass NULL to disable. Samples
+ * the filter drops still return SWIPE_PREDICTOR_OK from add_point_checked()
+ * and are counted in swipe_predictor_filter_stats() instead.
+ */
+int32_t swipe_predictor_set_input_filter(SwipePredictorHandle *handle,
+                                         const SwipePredictorInputFilterConfig *config);
+SwipePredictorFilterStats swipe_predictor_filter_stats(SwipePredictorHandle *handle);
+/*
+ * How a sample with the same timestamp as the previous one is buffered; takes
+ * a SWIPE_PREDICTOR_DUPLICATES_* value. The default, KEEP, buffers it as
+ * delivered unless the input filter merges it.
+ */
+int32_t swipe_predictor_set_duplicate_timestamp_policy(SwipePredictorHandle *handle, int32_t policy);
+/*
+ * Resample samples to a uniform rate_hz before velocity, straightness and
+ * deceleration analysis, so predictions don't depend on the input rate.
+ * Takes a SWIPE_PREDICTOR_INTERPOLATION_* value; a rate of 0 disables it.
+ */
+int32_t swipe_predictor_set_resampling(SwipePredictorHandle *handle, double rate_hz, int32_t interpolation);
+/*
+ * Screen-to-local transform (in points) for a rotated, scaled or scrolled
+ * container. Points are mapped into local space on input; predictions and
+ * dismissal distances are mapped back to screen space.
+ * Returns SWIPE_PREDICTOR_ERR_INVALID_CONFIGURATION if it isn't invertible.
+ */
+int32_t swipe_predictor_set_transform(SwipePredictorHandle *handle, SwipePredictorTransform transform);
+
+SwipePredictorPrediction swipe_predictor_predict(SwipePredictorHandle *handle);
+/* Returns 1 and writes the outputs when a prediction is available */
+int32_t swipe_predictor_get_prediction(SwipePredictorHandle *handle,
+                                       double *out_x,
+                                       double *out_y,
+                                       double *out_confidence);
+
+/*
+ * Explicit gesture lifecycle. Each returns SWIPE_PREDICTOR_OK or a status code,
+ * e.g. SWIPE_PREDICTOR_ERR_INVALID_STATE_TRANSITION for update() before begin().
+ * begin() discards the previous gesture, even an active one; after cancel()
+ * predictions fail with SWIPE_PREDICTOR_ERR_GESTURE_CANCELLED.
+ */
+int32_t swipe_predictor_begin(SwipePredictorHandle *handle, double x, double y, double timestamp);
+int32_t swipe_predictor_update(SwipePredictorHandle *handle, double x, double y, double timestamp);
+int32_t swipe_predictor_end(SwipePredictorHandle *handle, double release_time);
+int32_t swipe_predictor_cancel(SwipePredictorHandle *handle);
+/* Returns a SWIPE_PREDICTOR_STATE_* value, or -1 for an invalid handle */
+int32_t swipe_predictor_state(SwipePredictorHandle *handle);
+
+/*
+ * Prediction-changed events. Once enabled, every accepted sample queues an
+ * event only if the prediction crossed a threshold; poll instead of polling
+ * predictions. Pass NULL to disable. poll_event() returns 1 if it wrote an event.
+ */
+int32_t swipe_predictor_events_enable(SwipePredictorHandle *handle, const SwipePredictorEventConfig *config);
+int32_t swipe_predictor_poll_event(SwipePredictorHandle *handle, SwipePredictorEvent *out_event);
+
+int32_t swipe_predictor_reset(SwipePredictorHandle *handle);
+/* Returns 1 if the gesture appears to be cancelled */
+int32_t swipe_predictor_detect_cancellation(SwipePredictorHandle *handle);
+/* Will a swipe-to-dismiss end past threshold px from where it started, or return? */
+SwipePredictorDismissal swipe_predictor_predict_dismissal(SwipePredictorHandle *handle, double threshold);
+/* Returns a SWIPE_PREDICTOR_CANCEL_* value */
+int32_t swipe_predictor_cancellation_reason(SwipePredictorHandle *handle);
+int32_t swipe_predictor_set_cancellation_config(SwipePredictorHandle *handle,
+                                                SwipePredictorCancellationConfig config);
+
+/*
+ * Lock-free single-producer/single-consumer mode. The writer republishes the
+ * prediction after every accepted sample; the reader never blocks, so it can
+ * be polled from the UI thread (e.g. a Reanimated worklet). Use each half from
+ * one thread at a time. Coordinates on both halves are in pixels at
+ * pixels_per_point (1 for points), fixed for the pair's lifetime. Returns 1
+ * and writes both handles on success.
+ */
+int32_t swipe_predictor_spsc_create(SwipePredictorConfig config,
+                                    double pixels_per_point,
+                                    SwipePredictorWriter **out_writer,
+                                    SwipePredictorReader **out_reader);
+/* Returns SWIPE_PREDICTOR_OK or the status code explaining the rejection */
+int32_t swipe_predictor_writer_add_point(SwipePredictorWriter *writer, double x, double y, double timestamp);
+int32_t swipe_predictor_writer_reset(SwipePredictorWriter *writer);
+void swipe_predictor_writer_destroy(SwipePredictorWriter *writer);
+SwipePredictorSnapshot swipe_predictor_reader_latest(SwipePredictorReader *reader);
+void swipe_predictor_reader_destroy(SwipePredictorReader *reader);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SWIPE_PREDICTOR_H */
@@ -7,6 +7,10 @@ RCT_EXTERN_METHOD(createPredictor:(NSDictionary *)options
                   resolver:(RCTPromiseResolveBlock)resolve
                   rejecter:(RCTPromiseRejectBlock)reject)
 
+RCT_EXTERN_METHOD(setDisplayScale:(double)pixelsPerPoint
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject)
+
 RCT_EXTERN_METHOD(addTouchPoint:(NSInteger)predictorId
                   x:(double)x
                   y:(double)y
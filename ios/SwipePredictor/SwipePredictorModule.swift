@@ -1,5 +1,6 @@
 import Foundation
 import React
+import UIKit
 
 @objc(SwipePredictorModule)
 class SwipePredictorModule: RCTEventEmitter {
@@ -50,6 +51,34 @@ class SwipePredictorModule: RCTEventEmitter {
         return ["onPrediction", "onCancellation"]
     }
     
+    // Gesture handlers report points, so predictors start at a scale of 1;
+    // JS passes displayScale to setDisplayScale when feeding raw pixels
+    override func constantsToExport() -> [AnyHashable: Any]! {
+        return ["displayScale": UIScreen.main.scale]
+    }
+    
+    @objc
+    func setDisplayScale(_ pixelsPerPoint: Double,
+                         resolver resolve: @escaping RCTPromiseResolveBlock,
+                         rejecter reject: @escaping RCTPromiseRejectBlock) {
+        DispatchQueue.global(qos: .userInteractive).async { [weak self] in
+            self?.ensureContextInitialized()
+            
+            guard let context = self?.context else {
+                reject("NO_CONTEXT", "SwipePredictor context not initialized", nil)
+                return
+            }
+            
+            // Fails for an invalid scale or while a predictor has samples buffered
+            let status = swipe_predictor_context_set_display_scale(context, pixelsPerPoint)
+            if status == 0 {
+                resolve(nil)
+            } else {
+                reject("SCALE_CHANGE_FAILED", "Could not set display scale \(pixelsPerPoint) (status \(status))", nil)
+            }
+        }
+    }
+    
     @objc
     func createPredictor(_ options: [String: Any], 
                         resolver resolve: @escaping RCTPromiseResolveBlock,
@@ -251,6 +280,9 @@ func swipe_predictor_context_create_default() -> OpaquePointer?
 @_silgen_name("swipe_predictor_context_destroy")
 func swipe_predictor_context_destroy(_ ctx: OpaquePointer)
 
+@_silgen_name("swipe_predictor_context_set_display_scale")
+func swipe_predictor_context_set_display_scale(_ ctx: OpaquePointer, _ pixelsPerPoint: Double) -> Int32
+
 @_silgen_name("swipe_predictor_create_in_context")
 func swipe_predictor_create_in_context(_ ctx: OpaquePointer) -> OpaquePointer?
 
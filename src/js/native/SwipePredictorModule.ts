@@ -2,6 +2,19 @@ import { NativeModules, NativeEventEmitter, Platform } from 'react-native';
 import type { Prediction } from '../types';
 
 interface SwipePredictorModuleInterface {
+  /**
+   * Physical pixels per point of the main display (`UIScreen.scale` or
+   * `DisplayMetrics.density`). React Native touch coordinates are already in
+   * points, so pass this to `setDisplayScale` only when feeding raw pixels.
+   */
+  readonly displayScale: number;
+  
+  /**
+   * Set the pixels per point of coordinates passed to and returned from
+   * predictors. Rejects while any predictor has samples buffered.
+   */
+  setDisplayScale(pixelsPerPoint: number): Promise<void>;
+  
   createPredictor(options: {
     updateInterval: number;
     confidenceThreshold: number;
@@ -9,6 +22,20 @@ interface SwipePredictorModuleInterface {
   
   addTouchPoint(predictorId: number, x: number, y: number, timestamp: number): void;
   
+  /**
+   * Android only. When enabled, samples the predictor rejects (NaN
+   * coordinates, out-of-order timestamps, samples after the gesture ended)
+   * are reported through an `onTouchPointRejected` event instead of being
+   * dropped silently.
+   */
+  setStrictMode?(enabled: boolean): void;
+  
+  /**
+   * Android only. Samples the predictor has rejected since it was created,
+   * whether or not strict mode is on.
+   */
+  getRejectedSampleCount?(predictorId: number): Promise<number>;
+  
   getPrediction(predictorId: number): Promise<Prediction | null>;
   
   resetPredictor(predictorId: number): void;
@@ -18,6 +45,16 @@ interface SwipePredictorModuleInterface {
   removePredictor(predictorId: number): void;
 }
 
+/**
+ * Payload of the `onTouchPointRejected` event (Android). Samples the predictor
+ * itself rejects are only reported in strict mode.
+ */
+export interface TouchPointRejectedEvent {
+  predictorId: number;
+  reason: 'TIMESTAMP_OUT_OF_ORDER' | 'INVALID_TOUCH_POINT' | 'INVALID_ARGUMENT';
+  message: string;
+}
+
 const { SwipePredictorModule } = NativeModules;
 
 if (!SwipePredictorModule) {
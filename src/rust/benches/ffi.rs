@@ -0,0 +1,101 @@
+// criterion doesn't build for wasm32, where this bench is an empty binary
+#![cfg_attr(target_arch = "wasm32", no_main)]
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use swipe_predictor::*;
+
+/// Sample `i` of a steady horizontal swipe at 60Hz
+fn sample(i: usize) -> (f64, f64, f64) {
+    let t = i as f64 * 16.0;
+    (t * 1.5, 200.0, t)
+}
+
+/// A predictor handle, passed to other threads as an address since raw
+/// pointers aren't `Send`. Every FFI function used here locks the context.
+#[derive(Clone, Copy)]
+struct Handle(usize);
+
+impl Handle {
+    fn create(ctx: *mut SwipePredictorContext) -> Self {
+        let handle = swipe_predictor_create_in_context(ctx);
+        assert!(!handle.is_null());
+        Self(handle as usize)
+    }
+
+    fn ptr(self) -> *mut SwipePredictorHandle {
+        self.0 as *mut SwipePredictorHandle
+    }
+
+    /// One frame of the hot path: add a sample, then read the prediction
+    fn frame(self, i: usize) -> SwipePredictorPrediction {
+        let (x, y, t) = sample(i);
+        swipe_predictor_add_point_checked(self.ptr(), black_box(x), black_box(y), black_box(t));
+        swipe_predictor_predict(self.ptr())
+    }
+}
+
+/// Add a sample and predict through the C API while `contenders` other
+/// threads do the same with their own predictors in the same context
+fn bench_contended_frame(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ffi/add_point_and_predict");
+    for contenders in [0, 1, 3] {
+        group.bench_with_input(
+            BenchmarkId::new("contenders", contenders),
+            &contenders,
+            |b, &contenders| {
+                let ctx = swipe_predictor_context_create_default();
+                let stop = Arc::new(AtomicBool::new(false));
+                let workers: Vec<_> = (0..contenders)
+                    .map(|_| {
+                        let handle = Handle::create(ctx);
+                        let stop = Arc::clone(&stop);
+                        let worker = thread::spawn(move || {
+                            let mut i = 0;
+                            while !stop.load(Ordering::Relaxed) {
+                                black_box(handle.frame(i));
+                                i += 1;
+                            }
+                        });
+                        (handle, worker)
+                    })
+                    .collect();
+
+                let handle = Handle::create(ctx);
+                let mut i = 0;
+                b.iter(|| {
+                    i += 1;
+                    handle.frame(i)
+                });
+
+                stop.store(true, Ordering::Relaxed);
+                for (worker_handle, worker) in workers {
+                    worker.join().unwrap();
+                    swipe_predictor_destroy(worker_handle.ptr());
+                }
+                swipe_predictor_destroy(handle.ptr());
+                swipe_predictor_context_destroy(ctx);
+            },
+        );
+    }
+    group.finish();
+}
+
+/// The lock-free alternative: the writer publishes, the reader never blocks
+fn bench_spsc_reader(c: &mut Criterion) {
+    c.bench_function("ffi/reader_latest", |b| {
+        let (mut writer, reader) = spsc::split(PhysicsConfig::default()).unwrap();
+        for i in 0..10 {
+            let (x, y, t) = sample(i);
+            writer.add_touch_point(x, y, t).unwrap();
+        }
+        b.iter(|| black_box(&reader).latest());
+    });
+}
+
+criterion_group!(benches, bench_contended_frame, bench_spsc_reader);
+criterion_main!(benches);
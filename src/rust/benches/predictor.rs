@@ -0,0 +1,100 @@
+// criterion doesn't build for wasm32, where this bench is an empty binary
+#![cfg_attr(target_arch = "wasm32", no_main)]
+#![cfg(not(target_arch = "wasm32"))]
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use swipe_predictor::*;
+
+const BUFFER_SIZES: [usize; 4] = [5, 10, 25, 100];
+
+/// Sample `i` of a steady diagonal swipe at 60Hz
+fn sample(i: usize) -> (f64, f64, f64) {
+    let t = i as f64 * 16.0;
+    (t * 1.5, 200.0 + t * 0.4, t)
+}
+
+/// A predictor with a full buffer, so every new sample evicts the oldest
+fn full_predictor(buffer_size: usize) -> GesturePredictor {
+    let mut predictor =
+        GesturePredictor::with_buffer_size(PhysicsConfig::default(), buffer_size).unwrap();
+    for i in 0..buffer_size {
+        let (x, y, t) = sample(i);
+        predictor.add_touch_point(x, y, t).unwrap();
+    }
+    predictor
+}
+
+fn bench_add_touch_point(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add_touch_point");
+    for buffer_size in BUFFER_SIZES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(buffer_size),
+            &buffer_size,
+            |b, &size| {
+                let mut predictor = full_predictor(size);
+                let mut i = size;
+                b.iter(|| {
+                    let (x, y, t) = sample(i);
+                    i += 1;
+                    predictor
+                        .add_touch_point(black_box(x), black_box(y), black_box(t))
+                        .unwrap();
+                });
+            },
+        );
+    }
+
+    // The inline ring buffer, against the `VecDeque` above at the same size
+    group.bench_function("fixed/10", |b| {
+        let mut predictor = FixedGesturePredictor::<10>::fixed(PhysicsConfig::default()).unwrap();
+        let mut i = 0;
+        b.iter(|| {
+            let (x, y, t) = sample(i);
+            i += 1;
+            predictor
+                .add_touch_point(black_box(x), black_box(y), black_box(t))
+                .unwrap();
+        });
+    });
+    group.finish();
+}
+
+fn bench_predict(c: &mut Criterion) {
+    let mut group = c.benchmark_group("predict");
+    for buffer_size in BUFFER_SIZES {
+        let predictor = full_predictor(buffer_size);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(buffer_size),
+            &predictor,
+            |b, predictor| {
+                b.iter(|| black_box(predictor).predict().unwrap());
+            },
+        );
+    }
+    group.finish();
+}
+
+/// The analysis runs in `add_touch_point`, which latches the verdict, so this
+/// should stay flat across buffer sizes
+fn bench_detect_cancellation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("detect_cancellation");
+    for buffer_size in BUFFER_SIZES {
+        let predictor = full_predictor(buffer_size);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(buffer_size),
+            &predictor,
+            |b, predictor| {
+                b.iter(|| black_box(predictor).detect_cancellation());
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_add_touch_point,
+    bench_predict,
+    bench_detect_cancellation
+);
+criterion_main!(benches);
@@ -36,18 +36,19 @@ ar = "$ANDROID_NDK_HOME/toolchains/llvm/prebuilt/darwin-x86_64/bin/llvm-ar"
 linker = "$ANDROID_NDK_HOME/toolchains/llvm/prebuilt/darwin-x86_64/bin/i686-linux-android21-clang"
 EOF
 
-# Build for all Android architectures
+# Build for all Android architectures. The crate is rlib-only so `no_std`
+# dependents aren't forced to link a cdylib; the JNI library is requested here
 echo "Building for arm64-v8a..."
-cargo build --target aarch64-linux-android --release
+cargo rustc --lib --target aarch64-linux-android --release --crate-type cdylib
 
 echo "Building for armeabi-v7a..."
-cargo build --target armv7-linux-androideabi --release
+cargo rustc --lib --target armv7-linux-androideabi --release --crate-type cdylib
 
 echo "Building for x86_64..."
-cargo build --target x86_64-linux-android --release
+cargo rustc --lib --target x86_64-linux-android --release --crate-type cdylib
 
 echo "Building for x86..."
-cargo build --target i686-linux-android --release
+cargo rustc --lib --target i686-linux-android --release --crate-type cdylib
 
 # Create JNI directories
 mkdir -p ../../android/src/main/jniLibs/arm64-v8a
@@ -10,7 +10,7 @@ rustup target add aarch64-apple-ios
 
 # Build for arm64 only (all modern iOS devices)
 echo "Building for arm64..."
-cargo build --target aarch64-apple-ios --release
+cargo rustc --lib --target aarch64-apple-ios --release --crate-type staticlib
 
 # Create output directory
 echo "Creating output directory..."
@@ -0,0 +1,24 @@
+#!/bin/bash
+
+set -e
+
+echo "Building WebAssembly package for swipe_predictor..."
+
+# Add required target
+echo "Adding wasm target..."
+rustup target add wasm32-unknown-unknown
+
+# Only the JavaScript bindings; the C FFI isn't usable from the browser. The
+# crate is rlib-only, so the cdylib is requested here rather than in Cargo.toml
+echo "Building wasm module..."
+cargo rustc --lib --target wasm32-unknown-unknown --release --crate-type cdylib \
+  --no-default-features --features wasm
+
+# wasm-bindgen-cli must match the wasm-bindgen version in Cargo.lock
+echo "Generating JavaScript bindings..."
+wasm-bindgen --target web --out-dir ../../lib/wasm --out-name swipe_predictor \
+  target/wasm32-unknown-unknown/release/swipe_predictor.wasm
+
+echo "✅ Build complete!"
+echo "Output: lib/wasm/swipe_predictor.js, lib/wasm/swipe_predictor_bg.wasm"
+echo "Size: $(ls -lh ../../lib/wasm/swipe_predictor_bg.wasm | awk '{print $5}')"
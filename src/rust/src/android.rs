@@ -1,50 +1,225 @@
-use jni::JNIEnv;
-use jni::objects::{JClass, JObject};
-use jni::sys::{jdouble, jint, JavaVM, JNI_VERSION_1_6};
-use std::collections::HashMap;
-use std::sync::Mutex;
-
-use crate::ffi::{SwipePredictorContext, SwipePredictorHandle};
-
-// Wrapper for handle pointers to make them Send + Sync
-struct HandlePtr(*mut SwipePredictorHandle);
-unsafe impl Send for HandlePtr {}
-unsafe impl Sync for HandlePtr {}
-
-// Wrapper for context pointers to make them Send + Sync
-struct ContextPtr(*mut SwipePredictorContext);
-unsafe impl Send for ContextPtr {}
-unsafe impl Sync for ContextPtr {}
-
-// Global state for Android including handle mapping
-struct AndroidState {
-    context: Option<ContextPtr>,
-    handles: HashMap<i32, HandlePtr>,
-    next_id: i32,
-}
-
-impl AndroidState {
-    fn new() -> Self {
-        Self {
-            context: None,
-            handles: HashMap::new(),
-            next_id: 1, // Start from 1 so 0/-1 can indicate errors
+use jni::{JNIEnv, JavaVM, NativeMethod};
+use jni::objects::{GlobalRef, JByteBuffer, JClass, JDoubleArray, JMethodID, JObject};
+use jni::sys::{jboolean, jdouble, jint, jintArray, jlong, jvalue, JNI_ERR, JNI_FALSE, JNI_VERSION_1_6};
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
+
+use crate::error::PredictorError;
+use crate::ffi::SwipePredictorHandle;
+use crate::handle::HandleId;
+use crate::physics::PhysicsConfig;
+use crate::registry::BridgeRegistry;
+use crate::types::DisplayScale;
+
+/// Class declaring the native methods, registered explicitly in `JNI_OnLoad`.
+/// Override at build time with `SWIPE_PREDICTOR_JNI_CLASS` when the module
+/// lives in a different package; the bridge's exception classes are expected
+/// in that package too.
+const MODULE_CLASS: &str = match option_env!("SWIPE_PREDICTOR_JNI_CLASS") {
+    Some(class) => class,
+    None => "com/swipepredictor/SwipePredictorModule",
+};
+
+/// Class returned by `nativeGetPrediction`, overridable with `SWIPE_PREDICTOR_JNI_PREDICTION_CLASS`
+const PREDICTION_CLASS: &str = match option_env!("SWIPE_PREDICTOR_JNI_PREDICTION_CLASS") {
+    Some(class) => class,
+    None => "com/swipepredictor/Prediction",
+};
+
+/// Class and constructor for `Prediction`, resolved once while the app class
+/// loader is reachable. `FindClass` on other native threads only sees system
+/// classes, so the hot path must not look them up.
+///
+/// Generic only so tests can populate a cache without a JVM.
+struct JniCache<Class = GlobalRef, Ctor = JMethodID> {
+    prediction_class: Class,
+    prediction_ctor: Ctor,
+}
+
+impl JniCache {
+    fn resolve(env: &mut JNIEnv) -> Option<Self> {
+        let class = env.find_class(PREDICTION_CLASS).ok()?;
+        let prediction_ctor = env.get_method_id(&class, "<init>", "(DDD)V").ok()?;
+        let prediction_class = env.new_global_ref(&class).ok()?;
+        Some(Self {
+            prediction_class,
+            prediction_ctor,
+        })
+    }
+}
+
+static JNI_CACHE: OnceLock<JniCache> = OnceLock::new();
+
+/// Return the cached classes, running `init` only if nothing is cached yet.
+/// A failed lookup caches nothing, so a later call on a better thread retries.
+fn cached<Class, Ctor>(
+    cache: &OnceLock<JniCache<Class, Ctor>>,
+    init: impl FnOnce() -> Option<JniCache<Class, Ctor>>,
+) -> Option<&JniCache<Class, Ctor>> {
+    if let Some(value) = cache.get() {
+        return Some(value);
+    }
+    let value = init()?;
+    Some(cache.get_or_init(|| value))
+}
+
+/// Register every native method on `class_name` via `RegisterNatives`,
+/// so the Java class doesn't have to match the `Java_...` symbol names
+fn register_natives(env: &mut JNIEnv, class_name: &str) -> jni::errors::Result<()> {
+    fn method(name: &str, sig: &str, fn_ptr: *mut c_void) -> NativeMethod {
+        NativeMethod {
+            name: name.into(),
+            sig: sig.into(),
+            fn_ptr,
         }
     }
+    
+    let get_prediction_sig = format!("(I)L{};", PREDICTION_CLASS);
+    let methods = [
+        method("nativeInitManager", "(DDDD)V", Java_com_swipepredictor_SwipePredictorModule_nativeInitManager as *mut c_void),
+        method("nativeUpdateConfig", "(DDDZ)V", Java_com_swipepredictor_SwipePredictorModule_nativeUpdateConfig as *mut c_void),
+        method("nativeCreateContext", "(DDD)I", Java_com_swipepredictor_SwipePredictorModule_nativeCreateContext as *mut c_void),
+        method("nativeDestroyContext", "(I)V", Java_com_swipepredictor_SwipePredictorModule_nativeDestroyContext as *mut c_void),
+        method("nativeListContexts", "()[I", Java_com_swipepredictor_SwipePredictorModule_nativeListContexts as *mut c_void),
+        method("nativeUpdateContextConfig", "(IDDDZ)V", Java_com_swipepredictor_SwipePredictorModule_nativeUpdateContextConfig as *mut c_void),
+        method("nativeSetDisplayScale", "(D)V", Java_com_swipepredictor_SwipePredictorModule_nativeSetDisplayScale as *mut c_void),
+        method("nativeSetContextDisplayScale", "(ID)V", Java_com_swipepredictor_SwipePredictorModule_nativeSetContextDisplayScale as *mut c_void),
+        method("nativeInitPredictorInContext", "(I)I", Java_com_swipepredictor_SwipePredictorModule_nativeInitPredictorInContext as *mut c_void),
+        method("nativeInitPredictor", "()I", Java_com_swipepredictor_SwipePredictorModule_nativeInitPredictor as *mut c_void),
+        method("nativeAddTouchPoint", "(IDDD)V", Java_com_swipepredictor_SwipePredictorModule_nativeAddTouchPoint as *mut c_void),
+        method("nativeAddTouchPointChecked", "(IDDD)I", Java_com_swipepredictor_SwipePredictorModule_nativeAddTouchPointChecked as *mut c_void),
+        method("nativeSetStrictMode", "(Z)V", Java_com_swipepredictor_SwipePredictorModule_nativeSetStrictMode as *mut c_void),
+        method("nativeGetRejectedSampleCount", "(I)J", Java_com_swipepredictor_SwipePredictorModule_nativeGetRejectedSampleCount as *mut c_void),
+        method("nativeGetPrediction", &get_prediction_sig, Java_com_swipepredictor_SwipePredictorModule_nativeGetPrediction as *mut c_void),
+        method("nativeGetPredictionInto", "(I[D)I", Java_com_swipepredictor_SwipePredictorModule_nativeGetPredictionInto as *mut c_void),
+        method("nativeGetPredictionIntoBuffer", "(ILjava/nio/DoubleBuffer;)I", Java_com_swipepredictor_SwipePredictorModule_nativeGetPredictionIntoBuffer as *mut c_void),
+        method("nativeResetPredictor", "(I)V", Java_com_swipepredictor_SwipePredictorModule_nativeResetPredictor as *mut c_void),
+        method("nativeDetectCancellation", "(I)I", Java_com_swipepredictor_SwipePredictorModule_nativeDetectCancellation as *mut c_void),
+        method("nativeRemovePredictor", "(I)V", Java_com_swipepredictor_SwipePredictorModule_nativeRemovePredictor as *mut c_void),
+        method("nativeShutdown", "()V", Java_com_swipepredictor_SwipePredictorModule_nativeShutdown as *mut c_void),
+    ];
+    
+    let class = env.find_class(class_name)?;
+    env.register_native_methods(&class, &methods)
+}
+
+/// Number of doubles written by the `nativeGetPredictionInto*` entry points:
+/// `[x, y, confidence]`. New fields are only ever appended.
+const PREDICTION_FIELDS: usize = 3;
+
+// Status codes returned by the allocation-free prediction entry points,
+// which never throw so the per-frame path stays off the JVM heap
+const PREDICTION_WRITTEN: jint = 1;
+const PREDICTION_UNAVAILABLE: jint = 0;
+const PREDICTION_INVALID_ARGUMENT: jint = -1;
+const PREDICTION_STALE_HANDLE: jint = -2;
+
+/// Class `name` in the same package as `MODULE_CLASS`, so moving the module
+/// with `SWIPE_PREDICTOR_JNI_CLASS` moves its exceptions too
+fn module_package_class(name: &str) -> String {
+    match MODULE_CLASS.rsplit_once('/') {
+        Some((package, _)) => format!("{}/{}", package, name),
+        None => name.to_string(),
+    }
 }
 
-// Global state with handle mapping to avoid pointer truncation
-use std::sync::LazyLock;
-static GLOBAL_STATE: LazyLock<Mutex<AndroidState>> = LazyLock::new(|| {
-    Mutex::new(AndroidState::new())
+/// Java exception thrown when a predictor or context ID refers to one that was
+/// released or created before the last re-init
+static STALE_PREDICTOR_EXCEPTION: LazyLock<String> =
+    LazyLock::new(|| module_package_class("StalePredictorException"));
+
+/// Thrown in strict mode when `nativeAddTouchPoint` rejects a sample for any
+/// reason other than a stale ID, so the module only has one type to catch
+static INVALID_TOUCH_POINT_EXCEPTION: LazyLock<String> =
+    LazyLock::new(|| module_package_class("InvalidTouchPointException"));
+
+/// Subclass of `InvalidTouchPointException` for samples older than the previous one
+static TIMESTAMP_OUT_OF_ORDER_EXCEPTION: LazyLock<String> =
+    LazyLock::new(|| module_package_class("TimestampOutOfOrderException"));
+
+/// When set, `nativeAddTouchPoint` throws for rejected samples instead of
+/// dropping them silently. Off by default for backward compatibility.
+static STRICT_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Java exception class for a sample rejected by `add_point`
+fn add_point_exception_class(err: &PredictorError) -> &'static str {
+    match err {
+        PredictorError::TimestampOutOfOrder { .. } => &TIMESTAMP_OUT_OF_ORDER_EXCEPTION,
+        PredictorError::StaleHandle { .. } => &STALE_PREDICTOR_EXCEPTION,
+        _ => &INVALID_TOUCH_POINT_EXCEPTION,
+    }
+}
+
+/// Throw the Java exception matching a registry error.
+/// Stale IDs throw `StalePredictorException`; anything else `fallback_class`.
+fn throw_registry_error(env: &mut JNIEnv, what: &str, raw_id: jint, err: PredictorError, fallback_class: &str) {
+    let class = match err {
+        PredictorError::StaleHandle { .. } => &STALE_PREDICTOR_EXCEPTION,
+        _ => fallback_class,
+    };
+    let _ = env.throw_new(class, format!("{} ID {}: {}", what, raw_id, err));
+}
+
+/// Look up the handle for `predictor_id`.
+/// Throws `StalePredictorException` for stale IDs; unknown IDs return `None` without throwing.
+fn lookup_handle(
+    env: &mut JNIEnv,
+    state: &BridgeRegistry,
+    predictor_id: jint,
+) -> Option<*mut SwipePredictorHandle> {
+    let id = HandleId::from_raw(predictor_id)?;
+    match state.predictor(id) {
+        Ok(handle) => Some(handle),
+        Err(e @ PredictorError::StaleHandle { .. }) => {
+            throw_registry_error(env, "Predictor", predictor_id, e, &STALE_PREDICTOR_EXCEPTION);
+            None
+        }
+        Err(_) => None,
+    }
+}
+
+// Global registry of contexts and predictors, keyed by i32 IDs to avoid pointer truncation
+static GLOBAL_STATE: LazyLock<Mutex<BridgeRegistry>> = LazyLock::new(|| {
+    Mutex::new(BridgeRegistry::new())
 });
 
+fn lock_state<'a>(env: &mut JNIEnv) -> Option<std::sync::MutexGuard<'a, BridgeRegistry>> {
+    match GLOBAL_STATE.lock() {
+        Ok(guard) => Some(guard),
+        Err(_) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalStateException",
+                "Failed to acquire lock on global state"
+            );
+            None
+        }
+    }
+}
+
 /// Called when the native library is loaded by the JVM
 #[no_mangle]
-pub extern "system" fn JNI_OnLoad(_vm: JavaVM, _: *mut std::os::raw::c_void) -> jint {
+pub extern "system" fn JNI_OnLoad(vm: JavaVM, _: *mut c_void) -> jint {
     // Initialize the panic handler to prevent panics from crossing the FFI boundary
     crate::ffi::swipe_predictor_init_panic_handler();
     
+    let mut env = match vm.get_env() {
+        Ok(env) => env,
+        Err(_) => return JNI_ERR,
+    };
+    
+    // This runs on the thread calling System.loadLibrary, where the app class
+    // loader is reachable, so resolve classes now for use from any thread
+    if cached(&JNI_CACHE, || JniCache::resolve(&mut env)).is_none() {
+        let _ = env.exception_clear();
+    }
+    
+    // The exported Java_* symbols still resolve if registration fails,
+    // as long as the default class name is in use
+    if register_natives(&mut env, MODULE_CLASS).is_err() {
+        let _ = env.exception_clear();
+    }
+    
     // Return the JNI version we support
     JNI_VERSION_1_6
 }
@@ -83,84 +258,301 @@ pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeInitMa
         return;
     }
     
-    // Create a new context and store it globally
-    let ctx = crate::ffi::swipe_predictor_context_create(
+    let mut state = match lock_state(&mut env) {
+        Some(guard) => guard,
+        None => return,
+    };
+    
+    // Replace the default context; predictors in the old one become stale,
+    // contexts created with nativeCreateContext are untouched
+    let physics_config = PhysicsConfig {
         deceleration_rate,
         min_velocity_threshold,
         min_gesture_time_ms,
-    );
+    };
     
-    if ctx.is_null() {
+    if state.reset_default_context(physics_config).is_err() {
         let _ = env.throw_new(
             "java/lang/IllegalStateException",
             "Failed to initialize SwipePredictor. Parameters may be invalid."
         );
-        return;
     }
+}
+
+/// Update the physics config without invalidating existing predictor IDs
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeUpdateConfig(
+    mut env: JNIEnv,
+    class: JClass,
+    deceleration_rate: jdouble,
+    min_velocity_threshold: jdouble,
+    min_gesture_time_ms: jdouble,
+    apply_to_existing: jboolean,
+) {
+    let default_context = match lock_state(&mut env) {
+        Some(state) => state.default_context(),
+        None => return,
+    };
     
-    let mut state = match GLOBAL_STATE.lock() {
-        Ok(guard) => guard,
-        Err(_) => {
+    match default_context {
+        Some(id) => Java_com_swipepredictor_SwipePredictorModule_nativeUpdateContextConfig(
+            env,
+            class,
+            id.to_raw(),
+            deceleration_rate,
+            min_velocity_threshold,
+            min_gesture_time_ms,
+            apply_to_existing,
+        ),
+        None => {
             let _ = env.throw_new(
                 "java/lang/IllegalStateException",
-                "Failed to acquire lock on global state"
+                "SwipePredictor not initialized. Call nativeInitManager first."
             );
-            return;
         }
+    }
+}
+
+/// Create an independent context with its own physics config and return its ID
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeCreateContext(
+    mut env: JNIEnv,
+    _class: JClass,
+    deceleration_rate: jdouble,
+    min_velocity_threshold: jdouble,
+    min_gesture_time_ms: jdouble,
+) -> jint {
+    let physics_config = PhysicsConfig {
+        deceleration_rate,
+        min_velocity_threshold,
+        min_gesture_time_ms,
     };
     
-    // Clean up existing handles and context
-    if let Some(old_ctx) = state.context.take() {
-        // Destroy all existing handles first
-        for (_, handle_ptr) in state.handles.drain() {
-            crate::ffi::swipe_predictor_destroy(handle_ptr.0);
-        }
-        // Destroy old context
-        crate::ffi::swipe_predictor_context_destroy(old_ctx.0);
+    if let Err(e) = physics_config.validate() {
+        let _ = env.throw_new("java/lang/IllegalArgumentException", e.to_string());
+        return -1;
     }
     
-    // Set new context and reset state
-    state.context = Some(ContextPtr(ctx));
-    state.next_id = 1;
+    let mut state = match lock_state(&mut env) {
+        Some(guard) => guard,
+        None => return -1,
+    };
+    
+    match state.create_context(physics_config) {
+        Ok(id) => id.to_raw(),
+        Err(_) => -1,
+    }
 }
 
+/// Destroy a context and every predictor created in it
 #[no_mangle]
-pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeInitPredictor(
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeDestroyContext(
     mut env: JNIEnv,
     _class: JClass,
-) -> jint {
-    let mut state = match GLOBAL_STATE.lock() {
-        Ok(guard) => guard,
-        Err(_) => {
+    context_id: jint,
+) {
+    let id = match HandleId::from_raw(context_id) {
+        Some(id) => id,
+        None => return,
+    };
+    
+    let mut state = match lock_state(&mut env) {
+        Some(guard) => guard,
+        None => return,
+    };
+    
+    if let Err(e @ PredictorError::StaleHandle { .. }) = state.destroy_context(id) {
+        throw_registry_error(&mut env, "Context", context_id, e, &STALE_PREDICTOR_EXCEPTION);
+    }
+}
+
+/// List the IDs of all live contexts
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeListContexts(
+    mut env: JNIEnv,
+    _class: JClass,
+) -> jintArray {
+    let ids: Vec<jint> = match lock_state(&mut env) {
+        Some(state) => state.context_ids().into_iter().map(HandleId::to_raw).collect(),
+        None => return std::ptr::null_mut(),
+    };
+    
+    let array = match env.new_int_array(ids.len() as i32) {
+        Ok(array) => array,
+        Err(_) => return std::ptr::null_mut(),
+    };
+    
+    if env.set_int_array_region(&array, 0, &ids).is_err() {
+        return std::ptr::null_mut();
+    }
+    
+    array.into_raw()
+}
+
+/// Update the physics config of a specific context
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeUpdateContextConfig(
+    mut env: JNIEnv,
+    _class: JClass,
+    context_id: jint,
+    deceleration_rate: jdouble,
+    min_velocity_threshold: jdouble,
+    min_gesture_time_ms: jdouble,
+    apply_to_existing: jboolean,
+) {
+    let physics_config = PhysicsConfig {
+        deceleration_rate,
+        min_velocity_threshold,
+        min_gesture_time_ms,
+    };
+    
+    if let Err(e) = physics_config.validate() {
+        let _ = env.throw_new("java/lang/IllegalArgumentException", e.to_string());
+        return;
+    }
+    
+    let id = match HandleId::from_raw(context_id) {
+        Some(id) => id,
+        None => {
             let _ = env.throw_new(
-                "java/lang/IllegalStateException",
-                "Failed to acquire lock on global state"
+                "java/lang/IllegalArgumentException",
+                format!("Invalid context ID: {}", context_id)
             );
-            return -1;
+            return;
         }
     };
     
-    let ctx = match &state.context {
-        Some(ctx) => ctx.0,
+    let mut state = match lock_state(&mut env) {
+        Some(guard) => guard,
+        None => return,
+    };
+    
+    if let Err(e) = state.update_context_config(id, physics_config, apply_to_existing != JNI_FALSE) {
+        throw_registry_error(&mut env, "Context", context_id, e, "java/lang/IllegalStateException");
+    }
+}
+
+/// Set the display density of the default context, in physical pixels per point
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeSetDisplayScale(
+    mut env: JNIEnv,
+    class: JClass,
+    pixels_per_point: jdouble,
+) {
+    let default_context = match lock_state(&mut env) {
+        Some(state) => state.default_context(),
+        None => return,
+    };
+    
+    match default_context {
+        Some(id) => Java_com_swipepredictor_SwipePredictorModule_nativeSetContextDisplayScale(
+            env,
+            class,
+            id.to_raw(),
+            pixels_per_point,
+        ),
         None => {
             let _ = env.throw_new(
                 "java/lang/IllegalStateException",
                 "SwipePredictor not initialized. Call nativeInitManager first."
             );
+        }
+    }
+}
+
+/// Set the display density of a specific context, in physical pixels per point.
+/// Coordinates passed to and returned from its predictors are then in pixels.
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeSetContextDisplayScale(
+    mut env: JNIEnv,
+    _class: JClass,
+    context_id: jint,
+    pixels_per_point: jdouble,
+) {
+    if let Err(e) = DisplayScale::new(pixels_per_point) {
+        let _ = env.throw_new("java/lang/IllegalArgumentException", e.to_string());
+        return;
+    }
+    
+    let id = match HandleId::from_raw(context_id) {
+        Some(id) => id,
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid context ID: {}", context_id)
+            );
+            return;
+        }
+    };
+    
+    let mut state = match lock_state(&mut env) {
+        Some(guard) => guard,
+        None => return,
+    };
+    
+    // Fails while a predictor in the context has samples buffered
+    if let Err(e) = state.set_display_scale(id, pixels_per_point) {
+        throw_registry_error(&mut env, "Context", context_id, e, "java/lang/IllegalStateException");
+    }
+}
+
+/// Create a predictor in a specific context
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeInitPredictorInContext(
+    mut env: JNIEnv,
+    _class: JClass,
+    context_id: jint,
+) -> jint {
+    let id = match HandleId::from_raw(context_id) {
+        Some(id) => id,
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid context ID: {}", context_id)
+            );
             return -1;
         }
     };
     
-    let handle = crate::ffi::swipe_predictor_create_in_context(ctx);
-    if handle.is_null() {
-        return -1;
+    let mut state = match lock_state(&mut env) {
+        Some(guard) => guard,
+        None => return -1,
+    };
+    
+    match state.create_predictor(id) {
+        Ok(predictor_id) => predictor_id.to_raw(),
+        Err(e @ PredictorError::StaleHandle { .. }) => {
+            throw_registry_error(&mut env, "Context", context_id, e, &STALE_PREDICTOR_EXCEPTION);
+            -1
+        }
+        Err(_) => -1,
     }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeInitPredictor(
+    mut env: JNIEnv,
+    class: JClass,
+) -> jint {
+    let default_context = match lock_state(&mut env) {
+        Some(state) => state.default_context(),
+        None => return -1,
+    };
     
-    // Store handle in map and return ID
-    let id = state.next_id;
-    state.next_id = state.next_id.wrapping_add(1);
-    state.handles.insert(id, HandlePtr(handle));
-    id
+    match default_context {
+        Some(id) => Java_com_swipepredictor_SwipePredictorModule_nativeInitPredictorInContext(
+            env,
+            class,
+            id.to_raw(),
+        ),
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalStateException",
+                "SwipePredictor not initialized. Call nativeInitManager first."
+            );
+            -1
+        }
+    }
 }
 
 #[no_mangle]
@@ -193,9 +585,162 @@ pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeAddTou
         Err(_) => return,
     };
     
-    if let Some(handle_ptr) = state.handles.get(&predictor_id) {
-        // Don't throw on failure for backward compatibility
-        let _ = crate::ffi::swipe_predictor_add_point(handle_ptr.0, x as f64, y as f64, timestamp as f64);
+    if let Some(handle) = lookup_handle(&mut env, &state, predictor_id) {
+        // Rejected samples are only reported in strict mode, for backward compatibility
+        if let Some(Err(e)) = crate::ffi::add_point(handle, x, y, timestamp) {
+            if STRICT_MODE.load(Ordering::Relaxed) {
+                let _ = env.throw_new(
+                    add_point_exception_class(&e),
+                    format!("Predictor ID {} rejected touch point: {}", predictor_id, e),
+                );
+            }
+        }
+    }
+}
+
+/// Add a touch point and return 0, or the `PredictorError` code explaining
+/// why the sample was rejected. Never throws, regardless of strict mode.
+fn add_point_status(state: &BridgeRegistry, predictor_id: jint, x: f64, y: f64, timestamp: f64) -> jint {
+    let id = match HandleId::from_raw(predictor_id) {
+        Some(id) => id,
+        None => return PredictorError::InvalidHandle { index: 0 }.code(),
+    };
+    
+    match state.predictor(id) {
+        Ok(handle) => crate::ffi::swipe_predictor_add_point_checked(handle, x, y, timestamp),
+        Err(e) => e.code(),
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeAddTouchPointChecked(
+    _env: JNIEnv,
+    _class: JClass,
+    predictor_id: jint,
+    x: jdouble,
+    y: jdouble,
+    timestamp: jdouble,
+) -> jint {
+    match GLOBAL_STATE.lock() {
+        Ok(state) => add_point_status(&state, predictor_id, x, y, timestamp),
+        Err(_) => crate::ffi::SWIPE_PREDICTOR_ERR_INTERNAL,
+    }
+}
+
+/// Toggle strict mode for every predictor
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeSetStrictMode(
+    _env: JNIEnv,
+    _class: JClass,
+    enabled: jboolean,
+) {
+    STRICT_MODE.store(enabled != JNI_FALSE, Ordering::Relaxed);
+}
+
+/// Number of samples the predictor has rejected since it was created
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeGetRejectedSampleCount(
+    mut env: JNIEnv,
+    _class: JClass,
+    predictor_id: jint,
+) -> jlong {
+    let state = match lock_state(&mut env) {
+        Some(guard) => guard,
+        None => return 0,
+    };
+    
+    match lookup_handle(&mut env, &state, predictor_id) {
+        Some(handle) => crate::ffi::swipe_predictor_rejected_count(handle) as jlong,
+        None => 0,
+    }
+}
+
+/// Compute a prediction into `out` as `[x, y, confidence]`, returning a status code
+fn predict_into(state: &BridgeRegistry, predictor_id: jint, out: &mut [f64; PREDICTION_FIELDS]) -> jint {
+    let id = match HandleId::from_raw(predictor_id) {
+        Some(id) => id,
+        None => return PREDICTION_INVALID_ARGUMENT,
+    };
+    
+    let handle = match state.predictor(id) {
+        Ok(handle) => handle,
+        Err(PredictorError::StaleHandle { .. }) => return PREDICTION_STALE_HANDLE,
+        Err(_) => return PREDICTION_INVALID_ARGUMENT,
+    };
+    
+    let [x, y, confidence] = out;
+    match crate::ffi::swipe_predictor_get_prediction(handle, x, y, confidence) {
+        1 => PREDICTION_WRITTEN,
+        _ => PREDICTION_UNAVAILABLE,
+    }
+}
+
+/// Allocation-free alternative to `nativeGetPrediction` that writes
+/// `[x, y, confidence]` into a caller-owned `double[]` of at least 3 elements
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeGetPredictionInto(
+    env: JNIEnv,
+    _class: JClass,
+    predictor_id: jint,
+    out: JDoubleArray,
+) -> jint {
+    if out.is_null() {
+        return PREDICTION_INVALID_ARGUMENT;
+    }
+    
+    match env.get_array_length(&out) {
+        Ok(len) if len as usize >= PREDICTION_FIELDS => {}
+        _ => return PREDICTION_INVALID_ARGUMENT,
+    }
+    
+    let mut fields = [0.0; PREDICTION_FIELDS];
+    let status = match GLOBAL_STATE.lock() {
+        Ok(state) => predict_into(&state, predictor_id, &mut fields),
+        Err(_) => return PREDICTION_UNAVAILABLE,
+    };
+    
+    if status == PREDICTION_WRITTEN && env.set_double_array_region(&out, 0, &fields).is_err() {
+        return PREDICTION_INVALID_ARGUMENT;
+    }
+    status
+}
+
+/// Allocation-free alternative to `nativeGetPrediction` that writes
+/// `[x, y, confidence]` into a direct `DoubleBuffer` of at least 3 elements.
+/// The buffer must use native byte order (`ByteOrder.nativeOrder()`).
+#[no_mangle]
+pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeGetPredictionIntoBuffer(
+    env: JNIEnv,
+    _class: JClass,
+    predictor_id: jint,
+    buffer: JObject,
+) -> jint {
+    if buffer.is_null() {
+        return PREDICTION_INVALID_ARGUMENT;
+    }
+    
+    // GetDirectBufferAddress accepts any direct NIO buffer, not just ByteBuffer
+    let buffer = JByteBuffer::from(buffer);
+    let (address, capacity) = match (
+        env.get_direct_buffer_address(&buffer),
+        env.get_direct_buffer_capacity(&buffer),
+    ) {
+        (Ok(address), Ok(capacity)) => (address, capacity),
+        _ => return PREDICTION_INVALID_ARGUMENT,
+    };
+    
+    // Capacity is in elements of the buffer's type, i.e. doubles
+    if capacity < PREDICTION_FIELDS || address.align_offset(std::mem::align_of::<f64>()) != 0 {
+        return PREDICTION_INVALID_ARGUMENT;
+    }
+    
+    // SAFETY: The address is non-null, aligned and valid for `capacity` doubles,
+    // and the caller keeps the buffer alive for the duration of this call
+    let out = unsafe { &mut *(address as *mut [f64; PREDICTION_FIELDS]) };
+    
+    match GLOBAL_STATE.lock() {
+        Ok(state) => predict_into(&state, predictor_id, out),
+        Err(_) => PREDICTION_UNAVAILABLE,
     }
 }
 
@@ -218,40 +763,49 @@ pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeGetPre
         Err(_) => return JObject::null(),
     };
     
-    let handle_ptr = match state.handles.get(&predictor_id) {
+    let handle = match lookup_handle(&mut env, &state, predictor_id) {
         Some(h) => h,
         None => return JObject::null(),
     };
     
     let result = crate::ffi::swipe_predictor_get_prediction(
-        handle_ptr.0, 
+        handle, 
         &mut x, 
         &mut y, 
         &mut confidence
     );
     
     if result == 1 {
-        match env.find_class("com/swipepredictor/Prediction") {
-            Ok(prediction_class) => {
-                match env.new_object(
-                    prediction_class,
-                    "(DDD)V",
-                    &[x.into(), y.into(), confidence.into()],
-                ) {
-                    Ok(obj) => obj,
-                    Err(e) => {
-                        let _ = env.throw_new(
-                            "java/lang/RuntimeException", 
-                            &format!("Failed to create Prediction object: {:?}", e)
-                        );
-                        JObject::null()
-                    }
-                }
-            },
-            Err(e) => {
+        // Normally populated by JNI_OnLoad; resolving here is a fallback
+        // that only works on threads that can see the app class loader
+        let cache = match cached(&JNI_CACHE, || JniCache::resolve(&mut env)) {
+            Some(cache) => cache,
+            None => {
+                let _ = env.exception_clear();
                 let _ = env.throw_new(
                     "java/lang/ClassNotFoundException", 
-                    &format!("Prediction class not found: {:?}. Ensure com.swipepredictor.Prediction exists.", e)
+                    format!("Prediction class not found. Ensure {} exists.", PREDICTION_CLASS)
+                );
+                return JObject::null();
+            }
+        };
+        
+        let args = [jvalue { d: x }, jvalue { d: y }, jvalue { d: confidence }];
+        // SAFETY: The constructor ID was resolved from this class with signature (DDD)V
+        let obj = unsafe {
+            env.new_object_unchecked(
+                <&JClass>::from(cache.prediction_class.as_obj()),
+                cache.prediction_ctor,
+                &args,
+            )
+        };
+        
+        match obj {
+            Ok(obj) => obj,
+            Err(e) => {
+                let _ = env.throw_new(
+                    "java/lang/RuntimeException", 
+                    &format!("Failed to create Prediction object: {:?}", e)
                 );
                 JObject::null()
             }
@@ -261,9 +815,10 @@ pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeGetPre
     }
 }
 
+
 #[no_mangle]
 pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeResetPredictor(
-    _env: JNIEnv,
+    mut env: JNIEnv,
     _class: JClass,
     predictor_id: jint,
 ) {
@@ -272,14 +827,14 @@ pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeResetP
         Err(_) => return,
     };
     
-    if let Some(handle_ptr) = state.handles.get(&predictor_id) {
-        let _ = crate::ffi::swipe_predictor_reset(handle_ptr.0);
+    if let Some(handle) = lookup_handle(&mut env, &state, predictor_id) {
+        let _ = crate::ffi::swipe_predictor_reset(handle);
     }
 }
 
 #[no_mangle]
 pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeDetectCancellation(
-    _env: JNIEnv,
+    mut env: JNIEnv,
     _class: JClass,
     predictor_id: jint,
 ) -> jint {
@@ -288,15 +843,15 @@ pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeDetect
         Err(_) => return 0,
     };
     
-    match state.handles.get(&predictor_id) {
-        Some(handle_ptr) => crate::ffi::swipe_predictor_detect_cancellation(handle_ptr.0),
+    match lookup_handle(&mut env, &state, predictor_id) {
+        Some(handle) => crate::ffi::swipe_predictor_detect_cancellation(handle),
         None => 0,
     }
 }
 
 #[no_mangle]
 pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeRemovePredictor(
-    _env: JNIEnv,
+    mut env: JNIEnv,
     _class: JClass,
     predictor_id: jint,
 ) {
@@ -305,8 +860,13 @@ pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeRemove
         Err(_) => return,
     };
     
-    if let Some(handle_ptr) = state.handles.remove(&predictor_id) {
-        crate::ffi::swipe_predictor_destroy(handle_ptr.0);
+    let id = match HandleId::from_raw(predictor_id) {
+        Some(id) => id,
+        None => return,
+    };
+    
+    if let Err(e @ PredictorError::StaleHandle { .. }) = state.remove_predictor(id) {
+        throw_registry_error(&mut env, "Predictor", predictor_id, e, &STALE_PREDICTOR_EXCEPTION);
     }
 }
 
@@ -315,7 +875,7 @@ pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeShutdo
     _env: JNIEnv,
     _class: JClass,
 ) {
-    // Destroy all handles and the global context
+    // Destroy all contexts and their predictors
     let mut state = match GLOBAL_STATE.lock() {
         Ok(guard) => guard,
         Err(_) => {
@@ -325,18 +885,7 @@ pub extern "system" fn Java_com_swipepredictor_SwipePredictorModule_nativeShutdo
         }
     };
     
-    // Destroy all handles first
-    for (_, handle_ptr) in state.handles.drain() {
-        crate::ffi::swipe_predictor_destroy(handle_ptr.0);
-    }
-    
-    // Destroy context
-    if let Some(ctx) = state.context.take() {
-        crate::ffi::swipe_predictor_context_destroy(ctx.0);
-    }
-    
-    // Reset next_id
-    state.next_id = 1;
+    state.clear();
 }
 
 #[cfg(test)]
@@ -351,36 +900,126 @@ mod tests {
         // Test multiple init/shutdown cycles
         {
             let mut state = GLOBAL_STATE.lock().unwrap();
-            state.context = None;
-            state.handles.clear();
-            state.next_id = 1;
+            state.clear();
         }
         
         // Verify mutex is not poisoned
         assert!(GLOBAL_STATE.lock().is_ok());
     }
     
+    #[test]
+    fn test_jni_cache_resolves_once() {
+        // No JNI_OnLoad in tests: a failed resolve leaves the real cache empty
+        assert!(cached(&JNI_CACHE, || None).is_none());
+        assert!(JNI_CACHE.get().is_none());
+        
+        let cache = OnceLock::new();
+        let mut lookups = 0;
+        assert!(cached(&cache, || { lookups += 1; None }).is_none());
+        
+        let resolved = cached(&cache, || {
+            lookups += 1;
+            Some(JniCache { prediction_class: PREDICTION_CLASS, prediction_ctor: 1 })
+        })
+        .unwrap();
+        assert_eq!(resolved.prediction_class, PREDICTION_CLASS);
+        
+        // Later calls, like nativeGetPrediction's fallback, reuse the first resolve
+        let hit = cached(&cache, || {
+            lookups += 1;
+            Some(JniCache { prediction_class: "other/Prediction", prediction_ctor: 2 })
+        })
+        .unwrap();
+        assert_eq!(hit.prediction_ctor, 1);
+        assert_eq!(lookups, 2);
+    }
+    
+    #[test]
+    fn test_exceptions_share_module_package() {
+        let (package, _) = MODULE_CLASS.rsplit_once('/').unwrap();
+        for class in [&STALE_PREDICTOR_EXCEPTION, &INVALID_TOUCH_POINT_EXCEPTION, &TIMESTAMP_OUT_OF_ORDER_EXCEPTION] {
+            assert_eq!(class.rsplit_once('/').unwrap().0, package);
+        }
+    }
+    
+    #[test]
+    fn test_predict_into() {
+        let mut state = BridgeRegistry::new();
+        let context_id = state.reset_default_context(PhysicsConfig::default()).unwrap();
+        let predictor_id = state.create_predictor(context_id).unwrap();
+        let raw_id = predictor_id.to_raw();
+        let mut out = [0.0; PREDICTION_FIELDS];
+        
+        // Not enough points yet
+        assert_eq!(predict_into(&state, raw_id, &mut out), PREDICTION_UNAVAILABLE);
+        
+        let handle = state.predictor(predictor_id).unwrap();
+        for i in 0..6 {
+            crate::ffi::swipe_predictor_add_point(handle, i as f64 * 20.0, 0.0, i as f64 * 20.0);
+        }
+        assert_eq!(predict_into(&state, raw_id, &mut out), PREDICTION_WRITTEN);
+        assert!(out[0] > 100.0);
+        assert!(out[2] > 0.0);
+        
+        assert_eq!(predict_into(&state, -1, &mut out), PREDICTION_INVALID_ARGUMENT);
+        
+        state.remove_predictor(predictor_id).unwrap();
+        assert_eq!(predict_into(&state, raw_id, &mut out), PREDICTION_STALE_HANDLE);
+    }
+    
+    #[test]
+    fn test_add_point_status() {
+        let mut state = BridgeRegistry::new();
+        let context_id = state.reset_default_context(PhysicsConfig::default()).unwrap();
+        let predictor_id = state.create_predictor(context_id).unwrap();
+        let raw_id = predictor_id.to_raw();
+        
+        assert_eq!(add_point_status(&state, raw_id, 0.0, 0.0, 10.0), 0);
+        
+        let out_of_order = PredictorError::TimestampOutOfOrder { previous: 10.0, current: 5.0 };
+        assert_eq!(add_point_status(&state, raw_id, 0.0, 0.0, 5.0), out_of_order.code());
+        assert_eq!(add_point_exception_class(&out_of_order), TIMESTAMP_OUT_OF_ORDER_EXCEPTION.as_str());
+        
+        let nan = PredictorError::InvalidCoordinate { x: f64::NAN, y: 0.0 };
+        assert_eq!(add_point_status(&state, raw_id, f64::NAN, 0.0, 20.0), nan.code());
+        assert_eq!(add_point_exception_class(&nan), INVALID_TOUCH_POINT_EXCEPTION.as_str());
+        
+        // Samples for a gesture that already ended are rejected with the same type
+        let ended = PredictorError::InvalidStateTransition {
+            from: crate::types::GestureState::Ended,
+            event: "add_touch_point".into(),
+        };
+        assert_eq!(add_point_exception_class(&ended), INVALID_TOUCH_POINT_EXCEPTION.as_str());
+        
+        let handle = state.predictor(predictor_id).unwrap();
+        assert_eq!(crate::ffi::swipe_predictor_rejected_count(handle), 2);
+        
+        state.remove_predictor(predictor_id).unwrap();
+        let stale = PredictorError::StaleHandle { index: 0, generation: 0 };
+        assert_eq!(add_point_status(&state, raw_id, 0.0, 0.0, 30.0), stale.code());
+    }
+    
     #[test] 
     fn test_handle_id_mapping() {
         // Test that our ID mapping approach works correctly
-        let mut state = AndroidState::new();
-        
-        // Verify initial state
-        assert_eq!(state.next_id, 1);
-        assert!(state.handles.is_empty());
+        let mut state = BridgeRegistry::new();
+        let context_id = state.reset_default_context(PhysicsConfig::default()).unwrap();
         
         // Simulate adding handles
-        let test_handle = 0x12345678 as *mut SwipePredictorHandle;
-        let id = state.next_id;
-        state.next_id = state.next_id.wrapping_add(1);
-        state.handles.insert(id, HandlePtr(test_handle));
+        let raw_id = state.create_predictor(context_id).unwrap().to_raw();
+        assert!(raw_id > 0, "IDs stay positive so 0/-1 can indicate errors");
         
         // Verify we can retrieve it
-        assert_eq!(state.handles.get(&id).unwrap().0, test_handle);
+        let id = HandleId::from_raw(raw_id).unwrap();
+        assert!(state.predictor(id).is_ok());
         
-        // Test wrapping behavior
-        state.next_id = i32::MAX;
-        let wrapped_id = state.next_id.wrapping_add(1);
-        assert_eq!(wrapped_id, i32::MIN);
+        // A removed ID is reported as stale even after its slot is reused
+        state.remove_predictor(id).unwrap();
+        let reused = state.create_predictor(context_id).unwrap().to_raw();
+        assert_ne!(reused, raw_id);
+        assert!(matches!(
+            state.predictor(id),
+            Err(PredictorError::StaleHandle { .. })
+        ));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,346 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::config::PredictorConfig;
+use crate::error::{PredictorError, Result};
+use crate::predictor::GesturePredictor;
+use crate::types::{Point2D, Prediction};
+
+/// Recorded gestures as struct-of-arrays: gesture `i`'s samples are
+/// `xs[offsets[i]..offsets[i + 1]]`, and likewise for `ys` and `ts`
+#[derive(Debug, Clone, Copy)]
+pub struct GestureBatch<'a> {
+    offsets: &'a [usize],
+    xs: &'a [f64],
+    ys: &'a [f64],
+    ts: &'a [f64],
+}
+
+impl<'a> GestureBatch<'a> {
+    /// `offsets` has one more entry than there are gestures, starting at 0 and
+    /// ending at the number of samples
+    pub fn new(offsets: &'a [usize], xs: &'a [f64], ys: &'a [f64], ts: &'a [f64]) -> Result<Self> {
+        if ys.len() != xs.len() || ts.len() != xs.len() {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "samples".into(),
+                value: xs.len() as f64,
+                reason: "xs, ys and ts must have the same length".into(),
+            });
+        }
+
+        if offsets.first() != Some(&0) || offsets.last() != Some(&xs.len()) {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "offsets".into(),
+                value: offsets.len() as f64,
+                reason: "must start at 0 and end at the number of samples".into(),
+            });
+        }
+
+        if let Some(index) = offsets.windows(2).position(|pair| pair[1] < pair[0]) {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "offsets".into(),
+                value: (index + 1) as f64,
+                reason: "must be non-decreasing".into(),
+            });
+        }
+
+        Ok(Self { offsets, xs, ys, ts })
+    }
+
+    /// Number of gestures
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `(xs, ys, ts)` of one gesture
+    pub fn gesture(&self, index: usize) -> Option<(&'a [f64], &'a [f64], &'a [f64])> {
+        let range = *self.offsets.get(index)?..*self.offsets.get(index + 1)?;
+        Some((&self.xs[range.clone()], &self.ys[range.clone()], &self.ts[range]))
+    }
+}
+
+/// Predictions from `predict_batch`, one row per gesture with a column per
+/// cut time, flattened row by row
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatchPredictions {
+    /// Columns per gesture
+    pub cuts: usize,
+    /// NaN where there is no prediction
+    pub xs: Vec<f64>,
+    pub ys: Vec<f64>,
+    pub confidences: Vec<f64>,
+    /// 0 when there is a prediction, otherwise the `PredictorError::code`
+    /// explaining why there isn't
+    pub status: Vec<i32>,
+}
+
+impl BatchPredictions {
+    fn new(gestures: usize, cuts: usize) -> Self {
+        let len = gestures * cuts;
+        Self {
+            cuts,
+            xs: vec![f64::NAN; len],
+            ys: vec![f64::NAN; len],
+            confidences: vec![f64::NAN; len],
+            status: vec![0; len],
+        }
+    }
+
+    /// Number of gestures
+    pub fn len(&self) -> usize {
+        self.status.len().checked_div(self.cuts).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.status.is_empty()
+    }
+
+    pub fn get(&self, gesture: usize, cut: usize) -> Option<Prediction> {
+        if cut >= self.cuts {
+            return None;
+        }
+        let index = gesture * self.cuts + cut;
+        (*self.status.get(index)? == 0).then(|| {
+            Prediction::new(Point2D::new(self.xs[index], self.ys[index]), self.confidences[index])
+        })
+    }
+}
+
+/// One gesture's columns of a `BatchPredictions`
+struct Row<'a> {
+    xs: &'a mut [f64],
+    ys: &'a mut [f64],
+    confidences: &'a mut [f64],
+    status: &'a mut [i32],
+}
+
+/// Feed one gesture through `predictor`, predicting at each cut. Samples the
+/// predictor rejects are skipped, as the native bridges do.
+fn predict_gesture(
+    predictor: &mut GesturePredictor,
+    (xs, ys, ts): (&[f64], &[f64], &[f64]),
+    cut_times_ms: &[f64],
+    row: Row<'_>,
+) {
+    predictor.reset();
+    let start = ts.first().copied().unwrap_or(0.0);
+    let mut next = 0;
+
+    for (cut, &cut_time) in cut_times_ms.iter().enumerate() {
+        while next < ts.len() && ts[next] - start <= cut_time {
+            let _ = predictor.add_touch_point(xs[next], ys[next], ts[next]);
+            next += 1;
+        }
+
+        match predictor.predict() {
+            Ok(prediction) => {
+                row.xs[cut] = prediction.position.x;
+                row.ys[cut] = prediction.position.y;
+                row.confidences[cut] = prediction.confidence;
+                row.status[cut] = 0;
+            }
+            Err(e) => row.status[cut] = e.code(),
+        }
+    }
+}
+
+fn validate_cut_times(cut_times_ms: &[f64]) -> Result<()> {
+    if let Some(&cut) = cut_times_ms.iter().find(|cut| !cut.is_finite() || **cut < 0.0) {
+        return Err(PredictorError::InvalidConfiguration {
+            field: "cut_times_ms".into(),
+            value: cut,
+            reason: "must be non-negative and finite".into(),
+        });
+    }
+
+    if let Some(pair) = cut_times_ms.windows(2).find(|pair| pair[1] < pair[0]) {
+        return Err(PredictorError::InvalidConfiguration {
+            field: "cut_times_ms".into(),
+            value: pair[1],
+            reason: "must be in ascending order".into(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Predict where each gesture in `batch` comes to rest.
+///
+/// `cut_times_ms` are times after each gesture's first sample to predict at,
+/// using only the samples up to then, in ascending order; when empty there is
+/// one prediction per gesture from all of its samples. A single predictor is
+/// reset and reused across gestures, so this allocates only the output.
+pub fn predict_batch(
+    batch: &GestureBatch<'_>,
+    config: &PredictorConfig,
+    cut_times_ms: &[f64],
+) -> Result<BatchPredictions> {
+    config.validate()?;
+    validate_cut_times(cut_times_ms)?;
+    let cut_times_ms = if cut_times_ms.is_empty() { &[f64::INFINITY][..] } else { cut_times_ms };
+
+    let cuts = cut_times_ms.len();
+    let mut predictions = BatchPredictions::new(batch.len(), cuts);
+    let mut predictor = GesturePredictor::with_config_unchecked(config);
+
+    let rows = predictions
+        .xs
+        .chunks_mut(cuts)
+        .zip(predictions.ys.chunks_mut(cuts))
+        .zip(predictions.confidences.chunks_mut(cuts))
+        .zip(predictions.status.chunks_mut(cuts));
+    for (index, (((xs, ys), confidences), status)) in rows.enumerate() {
+        let gesture = batch.gesture(index).expect("one row per gesture");
+        let row = Row { xs, ys, confidences, status };
+        predict_gesture(&mut predictor, gesture, cut_times_ms, row);
+    }
+
+    Ok(predictions)
+}
+
+/// `predict_batch` spread across the rayon thread pool, with a predictor per
+/// worker. Results are identical to `predict_batch`.
+#[cfg(feature = "rayon")]
+pub fn par_predict_batch(
+    batch: &GestureBatch<'_>,
+    config: &PredictorConfig,
+    cut_times_ms: &[f64],
+) -> Result<BatchPredictions> {
+    use rayon::prelude::*;
+
+    config.validate()?;
+    validate_cut_times(cut_times_ms)?;
+    let cut_times_ms = if cut_times_ms.is_empty() { &[f64::INFINITY][..] } else { cut_times_ms };
+
+    let cuts = cut_times_ms.len();
+    let mut predictions = BatchPredictions::new(batch.len(), cuts);
+
+    predictions
+        .xs
+        .par_chunks_mut(cuts)
+        .zip(predictions.ys.par_chunks_mut(cuts))
+        .zip(predictions.confidences.par_chunks_mut(cuts))
+        .zip(predictions.status.par_chunks_mut(cuts))
+        .enumerate()
+        .for_each_init(
+            || GesturePredictor::with_config_unchecked(config),
+            |predictor, (index, (((xs, ys), confidences), status))| {
+                let gesture = batch.gesture(index).expect("one row per gesture");
+                let row = Row { xs, ys, confidences, status };
+                predict_gesture(predictor, gesture, cut_times_ms, row);
+            },
+        );
+
+    Ok(predictions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::PhysicsConfig;
+
+    /// Three gestures: a fast swipe, a single tap and a slower diagonal drag
+    fn samples() -> (Vec<usize>, Vec<f64>, Vec<f64>, Vec<f64>) {
+        let mut offsets = vec![0];
+        let (mut xs, mut ys, mut ts) = (Vec::new(), Vec::new(), Vec::new());
+        for i in 0..10 {
+            xs.push(i as f64 * 25.0);
+            ys.push(0.0);
+            ts.push(1000.0 + i as f64 * 16.0);
+        }
+        offsets.push(xs.len());
+        xs.push(50.0);
+        ys.push(50.0);
+        ts.push(0.0);
+        offsets.push(xs.len());
+        for i in 0..12 {
+            xs.push(i as f64 * 8.0);
+            ys.push(i as f64 * 6.0);
+            ts.push(i as f64 * 16.0);
+        }
+        offsets.push(xs.len());
+        (offsets, xs, ys, ts)
+    }
+
+    #[test]
+    fn test_matches_sequential_predictor() {
+        let (offsets, xs, ys, ts) = samples();
+        let batch = GestureBatch::new(&offsets, &xs, &ys, &ts).unwrap();
+        assert_eq!(batch.len(), 3);
+
+        let config = PredictorConfig::default();
+        let cut_times = [40.0, 200.0];
+        let predictions = predict_batch(&batch, &config, &cut_times).unwrap();
+        assert_eq!(predictions.len(), 3);
+        assert_eq!(predictions.cuts, 2);
+
+        for gesture in 0..batch.len() {
+            let (xs, ys, ts) = batch.gesture(gesture).unwrap();
+            for (cut, cut_time) in cut_times.iter().enumerate() {
+                let mut predictor = GesturePredictor::new(PhysicsConfig::default()).unwrap();
+                for i in (0..ts.len()).filter(|&i| ts[i] - ts[0] <= *cut_time) {
+                    predictor.add_touch_point(xs[i], ys[i], ts[i]).unwrap();
+                }
+
+                let index = gesture * 2 + cut;
+                match predictor.predict() {
+                    Ok(expected) => {
+                        let prediction = predictions.get(gesture, cut).unwrap();
+                        assert_eq!(prediction.position, expected.position);
+                        assert_eq!(prediction.confidence, expected.confidence);
+                    }
+                    Err(e) => {
+                        assert_eq!(predictions.status[index], e.code());
+                        assert!(predictions.xs[index].is_nan());
+                    }
+                }
+            }
+        }
+        // The tap never has enough samples
+        assert!(predictions.get(1, 1).is_none());
+
+        let whole = predict_batch(&batch, &config, &[]).unwrap();
+        assert_eq!(whole.cuts, 1);
+        assert_eq!(whole.get(2, 0).unwrap().position, predictions.get(2, 1).unwrap().position);
+    }
+
+    #[test]
+    fn test_invalid_batches() {
+        let (offsets, xs, ys, ts) = samples();
+        assert!(GestureBatch::new(&offsets, &xs, &ys[1..], &ts).is_err());
+        assert!(GestureBatch::new(&[0, 12, 10, 23], &xs, &ys, &ts).is_err());
+        assert!(GestureBatch::new(&[0, 10], &xs, &ys, &ts).is_err());
+        assert!(GestureBatch::new(&[0], &[], &[], &[]).unwrap().is_empty());
+
+        let batch = GestureBatch::new(&offsets, &xs, &ys, &ts).unwrap();
+        let config = PredictorConfig::default();
+        assert!(predict_batch(&batch, &config, &[100.0, 40.0]).is_err());
+        assert!(predict_batch(&batch, &config, &[f64::NAN]).is_err());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let (offsets, xs, ys, ts) = samples();
+        let batch = GestureBatch::new(&offsets, &xs, &ys, &ts).unwrap();
+        let config = PredictorConfig::default();
+        let cut_times = [20.0, 60.0, 150.0];
+
+        let sequential = predict_batch(&batch, &config, &cut_times).unwrap();
+        let parallel = par_predict_batch(&batch, &config, &cut_times).unwrap();
+        assert_eq!(sequential.status, parallel.status);
+        for gesture in 0..batch.len() {
+            for cut in 0..cut_times.len() {
+                assert_eq!(
+                    sequential.get(gesture, cut).map(|p| p.position),
+                    parallel.get(gesture, cut).map(|p| p.position)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use alloc::collections::VecDeque;
+
+use crate::types::{Point2D, Timestamp, TouchPoint};
+
+/// Storage for the touch samples a predictor analyses, oldest first.
+///
+/// Implemented for `VecDeque`, which `GesturePredictor` uses by default, and
+/// for the inline `RingBuffer`, which never allocates.
+pub trait SampleBuffer {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, index: usize) -> Option<&TouchPoint>;
+
+    fn front(&self) -> Option<&TouchPoint> {
+        self.get(0)
+    }
+
+    fn back(&self) -> Option<&TouchPoint> {
+        self.len().checked_sub(1).and_then(|last| self.get(last))
+    }
+
+    /// Append a sample. The predictor keeps the length within its buffer
+    /// size, so a full buffer is never pushed to.
+    fn push_back(&mut self, point: TouchPoint);
+
+    fn pop_front(&mut self) -> Option<TouchPoint>;
+
+    fn pop_back(&mut self) -> Option<TouchPoint>;
+
+    fn clear(&mut self);
+
+    fn iter(&self) -> impl DoubleEndedIterator<Item = &TouchPoint>;
+
+    fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut TouchPoint>;
+
+    /// Rearrange the samples in place so they can be read as one slice
+    fn make_contiguous(&mut self) -> &[TouchPoint];
+}
+
+impl SampleBuffer for VecDeque<TouchPoint> {
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn get(&self, index: usize) -> Option<&TouchPoint> {
+        VecDeque::get(self, index)
+    }
+
+    fn push_back(&mut self, point: TouchPoint) {
+        VecDeque::push_back(self, point)
+    }
+
+    fn pop_front(&mut self) -> Option<TouchPoint> {
+        VecDeque::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<TouchPoint> {
+        VecDeque::pop_back(self)
+    }
+
+    fn clear(&mut self) {
+        VecDeque::clear(self)
+    }
+
+    fn iter(&self) -> impl DoubleEndedIterator<Item = &TouchPoint> {
+        VecDeque::iter(self)
+    }
+
+    fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut TouchPoint> {
+        VecDeque::iter_mut(self)
+    }
+
+    fn make_contiguous(&mut self) -> &[TouchPoint] {
+        VecDeque::make_contiguous(self)
+    }
+}
+
+/// Fixed-capacity sample storage held inline, for predictors that must not
+/// allocate; see `FixedGesturePredictor`
+#[derive(Debug, Clone, Copy)]
+pub struct RingBuffer<const N: usize> {
+    points: [TouchPoint; N],
+    /// Index of the oldest sample
+    head: usize,
+    len: usize,
+}
+
+impl<const N: usize> Default for RingBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> RingBuffer<N> {
+    pub fn new() -> Self {
+        let empty = TouchPoint {
+            position: Point2D::new(0.0, 0.0),
+            timestamp: Timestamp::new(0.0),
+        };
+        Self {
+            points: [empty; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    fn slot(&self, index: usize) -> usize {
+        (self.head + index) % N
+    }
+
+    fn as_slices(&self) -> (&[TouchPoint], &[TouchPoint]) {
+        if self.head + self.len <= N {
+            (&self.points[self.head..self.head + self.len], &[])
+        } else {
+            let (wrapped, tail) = self.points.split_at(self.head);
+            (tail, &wrapped[..self.head + self.len - N])
+        }
+    }
+
+    fn as_mut_slices(&mut self) -> (&mut [TouchPoint], &mut [TouchPoint]) {
+        if self.head + self.len <= N {
+            (&mut self.points[self.head..self.head + self.len], &mut [])
+        } else {
+            let (wrapped, tail) = self.points.split_at_mut(self.head);
+            (tail, &mut wrapped[..self.head + self.len - N])
+        }
+    }
+}
+
+impl<const N: usize> SampleBuffer for RingBuffer<N> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> Option<&TouchPoint> {
+        (index < self.len).then(|| &self.points[self.slot(index)])
+    }
+
+    /// Appends, or overwrites the oldest sample when full
+    fn push_back(&mut self, point: TouchPoint) {
+        if N == 0 {
+            return;
+        }
+        if self.len == N {
+            self.head = self.slot(1);
+            self.len -= 1;
+        }
+        let slot = self.slot(self.len);
+        self.points[slot] = point;
+        self.len += 1;
+    }
+
+    fn pop_front(&mut self) -> Option<TouchPoint> {
+        let point = *self.get(0)?;
+        self.head = self.slot(1);
+        self.len -= 1;
+        Some(point)
+    }
+
+    fn pop_back(&mut self) -> Option<TouchPoint> {
+        let point = *self.back()?;
+        self.len -= 1;
+        Some(point)
+    }
+
+    fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    fn iter(&self) -> impl DoubleEndedIterator<Item = &TouchPoint> {
+        let (first, second) = self.as_slices();
+        first.iter().chain(second)
+    }
+
+    fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut TouchPoint> {
+        let (first, second) = self.as_mut_slices();
+        first.iter_mut().chain(second)
+    }
+
+    fn make_contiguous(&mut self) -> &[TouchPoint] {
+        self.points.rotate_left(self.head);
+        self.head = 0;
+        &self.points[..self.len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f64) -> TouchPoint {
+        TouchPoint::new(x, 0.0, x).unwrap()
+    }
+
+    fn xs(buffer: &impl SampleBuffer) -> Vec<f64> {
+        buffer.iter().map(|p| p.position.x).collect()
+    }
+
+    #[test]
+    fn test_ring_buffer_wraps() {
+        let mut ring = RingBuffer::<3>::new();
+        assert!(ring.is_empty() && ring.back().is_none());
+
+        for x in 0..5 {
+            ring.push_back(point(x as f64));
+        }
+        assert_eq!(xs(&ring), [2.0, 3.0, 4.0]);
+        assert_eq!(ring.front().unwrap().position.x, 2.0);
+        assert_eq!(ring.back().unwrap().position.x, 4.0);
+        assert_eq!(ring.iter().next_back().unwrap().position.x, 4.0);
+
+        assert_eq!(ring.pop_front().unwrap().position.x, 2.0);
+        ring.push_back(point(5.0));
+        assert_eq!(ring.pop_back().unwrap().position.x, 5.0);
+        for p in ring.iter_mut() {
+            p.position.x *= 10.0;
+        }
+        assert_eq!(xs(&ring), [30.0, 40.0]);
+
+        ring.push_back(point(6.0));
+        let contiguous: Vec<f64> = ring.make_contiguous().iter().map(|p| p.position.x).collect();
+        assert_eq!(contiguous, [30.0, 40.0, 6.0]);
+        assert_eq!(xs(&ring), contiguous);
+
+        ring.clear();
+        assert_eq!(ring.len(), 0);
+        assert!(ring.pop_front().is_none());
+    }
+}
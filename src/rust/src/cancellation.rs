@@ -0,0 +1,224 @@
+use core::fmt;
+
+use crate::error::{PredictorError, Result};
+
+/// Why a gesture looks like it is being abandoned
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub enum CancellationReason {
+    /// Recent motion turned back on itself by more than the reversal angle
+    DirectionReversal = 1,
+    /// The finger is barely moving
+    Stopped = 2,
+    /// Decelerating and now slower than a fraction of the fling threshold
+    SlowedBelowThreshold = 3,
+    /// Dragged back behind where the gesture started
+    ReturnedPastOrigin = 4,
+}
+
+impl fmt::Display for CancellationReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CancellationReason::DirectionReversal => "direction reversal",
+            CancellationReason::Stopped => "stopped",
+            CancellationReason::SlowedBelowThreshold => "slowed below threshold",
+            CancellationReason::ReturnedPastOrigin => "returned past origin",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Thresholds for cancellation analysis
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedCancellationConfig"))]
+pub struct CancellationConfig {
+    /// Angle in degrees between consecutive motion segments that counts as a reversal
+    pub reversal_angle_deg: f64,
+    /// Speed in points/s below which the finger counts as stopped
+    pub stop_speed: f64,
+    /// Fraction of `PhysicsConfig::min_velocity_threshold` below which a
+    /// decelerating gesture counts as slowed
+    pub slow_velocity_ratio: f64,
+    /// Motion segments shorter than this many points are treated as jitter, and a
+    /// gesture that hasn't moved this far from its origin is never cancelled
+    pub min_displacement: f64,
+    /// Consecutive samples that must agree before the verdict changes
+    pub hysteresis_samples: u32,
+}
+
+impl Default for CancellationConfig {
+    fn default() -> Self {
+        Self {
+            reversal_angle_deg: 120.0,
+            stop_speed: 20.0,
+            slow_velocity_ratio: 0.5,
+            min_displacement: 8.0,
+            hysteresis_samples: 2,
+        }
+    }
+}
+
+/// A deserialized `CancellationConfig` that hasn't been validated yet
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedCancellationConfig {
+    reversal_angle_deg: f64,
+    stop_speed: f64,
+    slow_velocity_ratio: f64,
+    min_displacement: f64,
+    hysteresis_samples: u32,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedCancellationConfig> for CancellationConfig {
+    type Error = PredictorError;
+
+    fn try_from(config: UncheckedCancellationConfig) -> Result<Self> {
+        let config = Self {
+            reversal_angle_deg: config.reversal_angle_deg,
+            stop_speed: config.stop_speed,
+            slow_velocity_ratio: config.slow_velocity_ratio,
+            min_displacement: config.min_displacement,
+            hysteresis_samples: config.hysteresis_samples,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl CancellationConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !(self.reversal_angle_deg > 0.0 && self.reversal_angle_deg <= 180.0) {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "reversal_angle_deg".into(),
+                value: self.reversal_angle_deg,
+                reason: "must be in (0, 180]".into(),
+            });
+        }
+
+        if !self.stop_speed.is_finite() || self.stop_speed < 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "stop_speed".into(),
+                value: self.stop_speed,
+                reason: "must be non-negative and finite".into(),
+            });
+        }
+
+        if !(0.0..=1.0).contains(&self.slow_velocity_ratio) {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "slow_velocity_ratio".into(),
+                value: self.slow_velocity_ratio,
+                reason: "must be in [0, 1]".into(),
+            });
+        }
+
+        if !self.min_displacement.is_finite() || self.min_displacement < 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "min_displacement".into(),
+                value: self.min_displacement,
+                reason: "must be non-negative and finite".into(),
+            });
+        }
+
+        if self.hysteresis_samples == 0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "hysteresis_samples".into(),
+                value: 0.0,
+                reason: "must be at least 1".into(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Debounces per-sample verdicts so the reported state only flips after
+/// `hysteresis_samples` consecutive samples disagree with it
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct CancellationLatch {
+    verdict: Option<CancellationReason>,
+    disagreeing: u32,
+}
+
+impl CancellationLatch {
+    pub(crate) fn update(&mut self, raw: Option<CancellationReason>, hysteresis_samples: u32) {
+        if raw.is_some() == self.verdict.is_some() {
+            // Still cancelled: follow the most specific current reason
+            self.verdict = raw;
+            self.disagreeing = 0;
+            return;
+        }
+
+        self.disagreeing += 1;
+        if self.disagreeing >= hysteresis_samples {
+            self.verdict = raw;
+            self.disagreeing = 0;
+        }
+    }
+
+    pub(crate) fn verdict(&self) -> Option<CancellationReason> {
+        self.verdict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latch_hysteresis() {
+        let mut latch = CancellationLatch::default();
+        let reversal = Some(CancellationReason::DirectionReversal);
+
+        // A single jittery sample doesn't flip the verdict
+        latch.update(reversal, 2);
+        assert_eq!(latch.verdict(), None);
+        latch.update(None, 2);
+        latch.update(reversal, 2);
+        assert_eq!(latch.verdict(), None);
+
+        latch.update(reversal, 2);
+        assert_eq!(latch.verdict(), reversal);
+
+        // Nor does a single clean one
+        latch.update(None, 2);
+        assert_eq!(latch.verdict(), reversal);
+        latch.update(Some(CancellationReason::Stopped), 2);
+        assert_eq!(latch.verdict(), Some(CancellationReason::Stopped));
+        latch.update(None, 2);
+        latch.update(None, 2);
+        assert_eq!(latch.verdict(), None);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(CancellationConfig::default().validate().is_ok());
+
+        let invalid = CancellationConfig {
+            reversal_angle_deg: 200.0,
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+
+        let invalid = CancellationConfig {
+            hysteresis_samples: 0,
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_validates() {
+        let json = serde_json::to_string(&CancellationConfig::default()).unwrap();
+        let config: CancellationConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(config, CancellationConfig::default());
+
+        let invalid = json.replace(r#""hysteresis_samples":2"#, r#""hysteresis_samples":0"#);
+        let error = serde_json::from_str::<CancellationConfig>(&invalid).unwrap_err();
+        assert!(error.to_string().contains("hysteresis_samples"));
+    }
+}
@@ -0,0 +1,589 @@
+use crate::error::{PredictorError, Result};
+use crate::physics::PhysicsConfig;
+use crate::predictor::{DEFAULT_BUFFER_SIZE, MAX_BUFFER_SIZE, MIN_BUFFER_SIZE};
+
+/// Named physics tunings for common platform feels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub enum Preset {
+    /// UIScrollView's normal deceleration
+    Ios,
+    /// Android's fling, which stops a little sooner
+    Android,
+    /// UIScrollView's `.fast` deceleration, for paging and short lists
+    IosFast,
+    /// `PhysicsConfig::default()`, the starting point for hand-tuned values
+    #[default]
+    Custom,
+}
+
+impl Preset {
+    pub fn physics(self) -> PhysicsConfig {
+        match self {
+            Preset::Ios => PhysicsConfig {
+                deceleration_rate: 1500.0,
+                min_velocity_threshold: 50.0,
+                min_gesture_time_ms: 30.0,
+            },
+            Preset::Android => PhysicsConfig {
+                deceleration_rate: 1800.0,
+                min_velocity_threshold: 50.0,
+                min_gesture_time_ms: 30.0,
+            },
+            // `.fast` decays about 5x quicker than `.normal`, so flings travel
+            // about a fifth as far
+            Preset::IosFast => PhysicsConfig {
+                deceleration_rate: 7500.0,
+                min_velocity_threshold: 50.0,
+                min_gesture_time_ms: 30.0,
+            },
+            Preset::Custom => PhysicsConfig::default(),
+        }
+    }
+}
+
+/// How the release velocity is estimated from buffered samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedEstimatorConfig"))]
+pub struct EstimatorConfig {
+    /// Segment `i` of `n` is weighted by `(i / n)^recency_exponent`, so higher
+    /// values follow the latest motion more closely; 0 weights all equally
+    pub recency_exponent: f64,
+    /// If the touch is released this long after the last sample, the finger
+    /// is assumed to have stopped before lifting and no fling is predicted
+    pub release_stop_ms: f64,
+}
+
+impl Default for EstimatorConfig {
+    fn default() -> Self {
+        Self {
+            recency_exponent: 2.0,
+            // Android's VelocityTracker "assume pointer stopped" window
+            release_stop_ms: 40.0,
+        }
+    }
+}
+
+/// A deserialized `EstimatorConfig` that hasn't been validated yet; omitted
+/// fields take their defaults
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct UncheckedEstimatorConfig {
+    recency_exponent: f64,
+    release_stop_ms: f64,
+}
+
+#[cfg(feature = "serde")]
+impl Default for UncheckedEstimatorConfig {
+    fn default() -> Self {
+        let config = EstimatorConfig::default();
+        Self {
+            recency_exponent: config.recency_exponent,
+            release_stop_ms: config.release_stop_ms,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedEstimatorConfig> for EstimatorConfig {
+    type Error = PredictorError;
+
+    fn try_from(config: UncheckedEstimatorConfig) -> Result<Self> {
+        let config = Self {
+            recency_exponent: config.recency_exponent,
+            release_stop_ms: config.release_stop_ms,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl EstimatorConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !self.recency_exponent.is_finite() || self.recency_exponent < 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "recency_exponent".into(),
+                value: self.recency_exponent,
+                reason: "must be non-negative and finite".into(),
+            });
+        }
+
+        if !self.release_stop_ms.is_finite() || self.release_stop_ms <= 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "release_stop_ms".into(),
+                value: self.release_stop_ms,
+                reason: "must be positive and finite".into(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// How a prediction's confidence is scored
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedConfidenceConfig"))]
+pub struct ConfidenceConfig {
+    /// Speed in points/second that gives full speed confidence
+    pub speed_scale: f64,
+    /// Gesture time in milliseconds beyond the minimum that gives full
+    /// duration confidence
+    pub duration_scale: f64,
+    /// Ratio each recent speed must fall below the previous one by for the
+    /// gesture to count as decelerating; 0.9 requires a 10% drop per sample
+    pub deceleration_factor: f64,
+    /// Multiplier applied to the confidence of a decelerating gesture
+    pub deceleration_penalty: f64,
+}
+
+impl Default for ConfidenceConfig {
+    fn default() -> Self {
+        Self {
+            // Typical fast swipe speed on mobile devices
+            speed_scale: 500.0,
+            duration_scale: 100.0,
+            deceleration_factor: 0.9,
+            deceleration_penalty: 0.5,
+        }
+    }
+}
+
+/// A deserialized `ConfidenceConfig` that hasn't been validated yet; omitted
+/// fields take their defaults
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct UncheckedConfidenceConfig {
+    speed_scale: f64,
+    duration_scale: f64,
+    deceleration_factor: f64,
+    deceleration_penalty: f64,
+}
+
+#[cfg(feature = "serde")]
+impl Default for UncheckedConfidenceConfig {
+    fn default() -> Self {
+        let config = ConfidenceConfig::default();
+        Self {
+            speed_scale: config.speed_scale,
+            duration_scale: config.duration_scale,
+            deceleration_factor: config.deceleration_factor,
+            deceleration_penalty: config.deceleration_penalty,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedConfidenceConfig> for ConfidenceConfig {
+    type Error = PredictorError;
+
+    fn try_from(config: UncheckedConfidenceConfig) -> Result<Self> {
+        let config = Self {
+            speed_scale: config.speed_scale,
+            duration_scale: config.duration_scale,
+            deceleration_factor: config.deceleration_factor,
+            deceleration_penalty: config.deceleration_penalty,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl ConfidenceConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !self.speed_scale.is_finite() || self.speed_scale <= 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "speed_scale".into(),
+                value: self.speed_scale,
+                reason: "must be positive and finite".into(),
+            });
+        }
+
+        if !self.duration_scale.is_finite() || self.duration_scale <= 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "duration_scale".into(),
+                value: self.duration_scale,
+                reason: "must be positive and finite".into(),
+            });
+        }
+
+        if !(self.deceleration_factor > 0.0 && self.deceleration_factor <= 1.0) {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "deceleration_factor".into(),
+                value: self.deceleration_factor,
+                reason: "must be in (0, 1]".into(),
+            });
+        }
+
+        if !(0.0..=1.0).contains(&self.deceleration_penalty) {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "deceleration_penalty".into(),
+                value: self.deceleration_penalty,
+                reason: "must be in [0, 1]".into(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Everything a predictor is created with
+#[derive(Debug, Clone, Copy)]
+pub struct PredictorConfig {
+    pub physics: PhysicsConfig,
+    /// Touch points kept for analysis
+    pub buffer_size: usize,
+    pub estimator: EstimatorConfig,
+    pub confidence: ConfidenceConfig,
+}
+
+impl Default for PredictorConfig {
+    fn default() -> Self {
+        Preset::default().into()
+    }
+}
+
+impl From<Preset> for PredictorConfig {
+    fn from(preset: Preset) -> Self {
+        Self {
+            physics: preset.physics(),
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            estimator: EstimatorConfig::default(),
+            confidence: ConfidenceConfig::default(),
+        }
+    }
+}
+
+impl PredictorConfig {
+    pub fn validate(&self) -> Result<()> {
+        self.physics.validate()?;
+
+        if !(MIN_BUFFER_SIZE..=MAX_BUFFER_SIZE).contains(&self.buffer_size) {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "buffer_size".into(),
+                value: self.buffer_size as f64,
+                reason: "must be between 2 and 100".into(),
+            });
+        }
+
+        self.estimator.validate()?;
+        self.confidence.validate()
+    }
+}
+
+#[cfg(feature = "config")]
+pub use document::{ConfigError, ConfigFormat};
+
+/// Config documents, e.g. tuned values shipped from a backend:
+///
+/// ```toml
+/// preset = "android"
+/// buffer_size = 12
+///
+/// [physics]
+/// deceleration_rate = 2000.0
+///
+/// [confidence]
+/// speed_scale = 600.0
+/// ```
+///
+/// Every key is optional. Physics values override the preset's; other
+/// sections start from their defaults. Unknown keys are rejected so typos
+/// don't go unnoticed.
+#[cfg(feature = "config")]
+mod document {
+    use std::fmt;
+
+    use super::{PredictorConfig, Preset, UncheckedConfidenceConfig, UncheckedEstimatorConfig};
+    use crate::error::PredictorError;
+
+    #[repr(i32)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ConfigFormat {
+        Json = 0,
+        Toml = 1,
+    }
+
+    impl TryFrom<i32> for ConfigFormat {
+        type Error = PredictorError;
+
+        fn try_from(value: i32) -> std::result::Result<Self, Self::Error> {
+            match value {
+                0 => Ok(Self::Json),
+                1 => Ok(Self::Toml),
+                _ => Err(PredictorError::InvalidConfiguration {
+                    field: "format".into(),
+                    value: value as f64,
+                    reason: "unknown config format".into(),
+                }),
+            }
+        }
+    }
+
+    /// Why a config document was rejected
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum ConfigError {
+        /// The document is malformed, or has an unknown key or a value of the
+        /// wrong type. `line` and `column` are 1-based, or 0 if unknown.
+        Syntax {
+            message: String,
+            line: usize,
+            column: usize,
+        },
+        /// The document parsed but a value is out of range
+        Invalid(PredictorError),
+    }
+
+    impl ConfigError {
+        /// `PredictorError::code` of the failure; syntax errors report
+        /// `InvalidConfiguration`
+        pub fn code(&self) -> i32 {
+            match self {
+                ConfigError::Syntax { .. } => PredictorError::InvalidConfiguration {
+                    field: "".into(),
+                    value: 0.0,
+                    reason: "".into(),
+                }
+                .code(),
+                ConfigError::Invalid(error) => error.code(),
+            }
+        }
+    }
+
+    impl fmt::Display for ConfigError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ConfigError::Syntax { message, line, column } if *line > 0 => {
+                    write!(f, "Invalid config at line {} column {}: {}", line, column, message)
+                }
+                ConfigError::Syntax { message, .. } => write!(f, "Invalid config: {}", message),
+                ConfigError::Invalid(error) => error.fmt(f),
+            }
+        }
+    }
+
+    impl std::error::Error for ConfigError {}
+
+    impl From<PredictorError> for ConfigError {
+        fn from(error: PredictorError) -> Self {
+            ConfigError::Invalid(error)
+        }
+    }
+
+    #[derive(serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Document {
+        #[serde(default)]
+        preset: Preset,
+        #[serde(default)]
+        physics: PhysicsOverrides,
+        buffer_size: Option<usize>,
+        // Unchecked so out-of-range values are reported as `Invalid` rather
+        // than `Syntax`
+        #[serde(default)]
+        estimator: UncheckedEstimatorConfig,
+        #[serde(default)]
+        confidence: UncheckedConfidenceConfig,
+    }
+
+    #[derive(Default, serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct PhysicsOverrides {
+        deceleration_rate: Option<f64>,
+        min_velocity_threshold: Option<f64>,
+        min_gesture_time_ms: Option<f64>,
+    }
+
+    impl Document {
+        fn resolve(self) -> Result<PredictorConfig, PredictorError> {
+            let mut config = PredictorConfig::from(self.preset);
+            let physics = &mut config.physics;
+            physics.deceleration_rate = self.physics.deceleration_rate.unwrap_or(physics.deceleration_rate);
+            physics.min_velocity_threshold = self
+                .physics
+                .min_velocity_threshold
+                .unwrap_or(physics.min_velocity_threshold);
+            physics.min_gesture_time_ms = self.physics.min_gesture_time_ms.unwrap_or(physics.min_gesture_time_ms);
+            config.buffer_size = self.buffer_size.unwrap_or(config.buffer_size);
+            config.estimator = self.estimator.try_into()?;
+            config.confidence = self.confidence.try_into()?;
+            Ok(config)
+        }
+    }
+
+    impl PredictorConfig {
+        /// Parse and validate a config document
+        pub fn parse(document: &str, format: ConfigFormat) -> Result<Self, ConfigError> {
+            let document: Document = match format {
+                ConfigFormat::Json => serde_json::from_str(document).map_err(|e| ConfigError::Syntax {
+                    message: e.to_string(),
+                    line: e.line(),
+                    column: e.column(),
+                })?,
+                ConfigFormat::Toml => toml::from_str(document).map_err(|e| {
+                    let (line, column) = e
+                        .span()
+                        .map(|span| line_and_column(document, span.start))
+                        .unwrap_or((0, 0));
+                    ConfigError::Syntax {
+                        message: e.message().to_string(),
+                        line,
+                        column,
+                    }
+                })?,
+            };
+
+            let config = document.resolve()?;
+            config.validate()?;
+            Ok(config)
+        }
+    }
+
+    /// 1-based line and column of a byte offset
+    fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+        let before = &text[..offset.min(text.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        for preset in [Preset::Ios, Preset::Android, Preset::IosFast, Preset::Custom] {
+            assert!(PredictorConfig::from(preset).validate().is_ok());
+        }
+        assert_eq!(
+            Preset::Custom.physics().deceleration_rate,
+            PhysicsConfig::default().deceleration_rate
+        );
+        assert!(Preset::IosFast.physics().deceleration_rate > Preset::Ios.physics().deceleration_rate);
+    }
+
+    #[test]
+    fn test_validation() {
+        let config = PredictorConfig {
+            buffer_size: 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(PredictorError::InvalidConfiguration { field, .. }) if field == "buffer_size"
+        ));
+
+        let config = PredictorConfig {
+            confidence: ConfidenceConfig {
+                deceleration_penalty: 1.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(PredictorError::InvalidConfiguration { field, .. }) if field == "deceleration_penalty"
+        ));
+
+        let estimator = EstimatorConfig {
+            release_stop_ms: 0.0,
+            ..Default::default()
+        };
+        assert!(estimator.validate().is_err());
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_parse_documents() {
+        let json = r#"{
+            "preset": "android",
+            "physics": { "min_velocity_threshold": 80.0 },
+            "buffer_size": 12,
+            "confidence": { "speed_scale": 600.0 }
+        }"#;
+        let toml = r#"
+            preset = "android"
+            buffer_size = 12
+
+            [physics]
+            min_velocity_threshold = 80.0
+
+            [confidence]
+            speed_scale = 600.0
+        "#;
+
+        for (document, format) in [(json, ConfigFormat::Json), (toml, ConfigFormat::Toml)] {
+            let config = PredictorConfig::parse(document, format).unwrap();
+            assert_eq!(config.physics.deceleration_rate, 1800.0);
+            assert_eq!(config.physics.min_velocity_threshold, 80.0);
+            assert_eq!(config.buffer_size, 12);
+            assert_eq!(config.confidence.speed_scale, 600.0);
+            assert_eq!(config.confidence.deceleration_penalty, 0.5);
+            assert_eq!(config.estimator, EstimatorConfig::default());
+        }
+
+        let empty = PredictorConfig::parse("{}", ConfigFormat::Json).unwrap();
+        assert_eq!(empty.physics.deceleration_rate, PhysicsConfig::default().deceleration_rate);
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_parse_errors() {
+        let error = PredictorConfig::parse("preset = \"ios\"\nbuffer_sise = 4\n", ConfigFormat::Toml).unwrap_err();
+        assert!(matches!(error, ConfigError::Syntax { line: 2, column: 1, .. }), "{:?}", error);
+
+        let error = PredictorConfig::parse(r#"{"preset": "windows"}"#, ConfigFormat::Json).unwrap_err();
+        assert!(matches!(error, ConfigError::Syntax { line: 1, .. }));
+
+        let error =
+            PredictorConfig::parse(r#"{"physics": {"deceleration_rate": -5}}"#, ConfigFormat::Json).unwrap_err();
+        assert_eq!(
+            error,
+            ConfigError::Invalid(PredictorError::InvalidConfiguration {
+                field: "deceleration_rate".into(),
+                value: -5.0,
+                reason: "must be positive".into(),
+            })
+        );
+        assert_eq!(error.code(), 7);
+        assert!(ConfigFormat::try_from(2).is_err());
+
+        let error =
+            PredictorConfig::parse("[confidence]\ndeceleration_penalty = 5.0\n", ConfigFormat::Toml).unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::Invalid(PredictorError::InvalidConfiguration { field, .. }) if field == "deceleration_penalty"
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_validates() {
+        let estimator: EstimatorConfig = serde_json::from_str(r#"{"recency_exponent": 1.0}"#).unwrap();
+        assert_eq!(estimator.recency_exponent, 1.0);
+        assert_eq!(estimator.release_stop_ms, EstimatorConfig::default().release_stop_ms);
+
+        let confidence: ConfidenceConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(confidence, ConfidenceConfig::default());
+
+        let json = serde_json::to_string(&confidence).unwrap();
+        assert_eq!(serde_json::from_str::<ConfidenceConfig>(&json).unwrap(), confidence);
+
+        let invalid = serde_json::from_str::<EstimatorConfig>(r#"{"release_stop_ms": 0.0}"#);
+        assert!(invalid.unwrap_err().to_string().contains("release_stop_ms"));
+
+        let invalid = serde_json::from_str::<ConfidenceConfig>(r#"{"speed_scale": -1.0}"#);
+        assert!(invalid.unwrap_err().to_string().contains("speed_scale"));
+
+        let invalid = serde_json::from_str::<ConfidenceConfig>(r#"{"deceleration_penalty": 5.0}"#);
+        assert!(invalid.unwrap_err().to_string().contains("deceleration_penalty"));
+
+        assert!(serde_json::from_str::<ConfidenceConfig>(r#"{"speed": 1.0}"#).is_err());
+    }
+}
@@ -1,6 +1,12 @@
-use std::fmt;
+use alloc::borrow::Cow;
+use core::fmt;
 
+use crate::types::GestureState;
+
+/// Names and reasons are `Cow` so errors raised by the crate borrow static
+/// text, while ones deserialized with the `serde` feature own theirs.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PredictorError {
     // Data collection errors
     InsufficientData {
@@ -9,12 +15,23 @@ pub enum PredictorError {
     },
     InvalidTimestamp {
         timestamp: f64,
-        reason: &'static str,
+        reason: Cow<'static, str>,
     },
     TimestampOutOfOrder {
         previous: f64,
         current: f64,
     },
+    InvalidCoordinate {
+        x: f64,
+        y: f64,
+    },
+    
+    // Gesture lifecycle errors
+    InvalidStateTransition {
+        from: GestureState,
+        event: Cow<'static, str>,
+    },
+    GestureCancelled,
     
     // Gesture analysis errors
     GestureTooShort {
@@ -28,15 +45,27 @@ pub enum PredictorError {
     
     // Configuration errors
     InvalidConfiguration {
-        field: &'static str,
+        field: Cow<'static, str>,
         value: f64,
-        reason: &'static str,
+        reason: Cow<'static, str>,
     },
     
     // Calculation errors
     NumericalError {
-        operation: &'static str,
-        details: &'static str,
+        operation: Cow<'static, str>,
+        details: Cow<'static, str>,
+    },
+    
+    // Handle errors
+    InvalidHandle {
+        index: u32,
+    },
+    StaleHandle {
+        index: u32,
+        generation: u32,
+    },
+    HandleLimitReached {
+        limit: usize,
     },
 }
 
@@ -52,11 +81,20 @@ impl fmt::Display for PredictorError {
             PredictorError::TimestampOutOfOrder { previous, current } => {
                 write!(f, "Timestamp out of order: {} after {}", current, previous)
             }
+            PredictorError::InvalidCoordinate { x, y } => {
+                write!(f, "Invalid coordinate ({}, {}): must be finite", x, y)
+            }
+            PredictorError::InvalidStateTransition { from, event } => {
+                write!(f, "Invalid state transition: cannot {} while {}", event, from)
+            }
+            PredictorError::GestureCancelled => {
+                write!(f, "Gesture was cancelled")
+            }
             PredictorError::GestureTooShort { duration_ms, minimum_ms } => {
                 write!(f, "Gesture too short: {}ms (minimum {}ms)", duration_ms, minimum_ms)
             }
             PredictorError::VelocityTooLow { velocity, minimum } => {
-                write!(f, "Velocity too low: {} px/s (minimum {} px/s)", velocity, minimum)
+                write!(f, "Velocity too low: {} points/s (minimum {} points/s)", velocity, minimum)
             }
             PredictorError::InvalidConfiguration { field, value, reason } => {
                 write!(f, "Invalid configuration: {} = {} ({})", field, value, reason)
@@ -64,13 +102,44 @@ impl fmt::Display for PredictorError {
             PredictorError::NumericalError { operation, details } => {
                 write!(f, "Numerical error in {}: {}", operation, details)
             }
+            PredictorError::InvalidHandle { index } => {
+                write!(f, "Invalid handle: slot {} was never allocated", index)
+            }
+            PredictorError::StaleHandle { index, generation } => {
+                write!(f, "Stale handle: slot {} generation {} has been released", index, generation)
+            }
+            PredictorError::HandleLimitReached { limit } => {
+                write!(f, "Handle limit reached: at most {} live handles", limit)
+            }
         }
     }
 }
 
-impl std::error::Error for PredictorError {}
+impl PredictorError {
+    /// Stable numeric code for reporting errors across FFI boundaries.
+    /// Codes are positive so callers can keep 0 for success.
+    pub fn code(&self) -> i32 {
+        match self {
+            PredictorError::InsufficientData { .. } => 1,
+            PredictorError::InvalidTimestamp { .. } => 2,
+            PredictorError::TimestampOutOfOrder { .. } => 3,
+            PredictorError::InvalidCoordinate { .. } => 4,
+            PredictorError::GestureTooShort { .. } => 5,
+            PredictorError::VelocityTooLow { .. } => 6,
+            PredictorError::InvalidConfiguration { .. } => 7,
+            PredictorError::NumericalError { .. } => 8,
+            PredictorError::InvalidHandle { .. } => 9,
+            PredictorError::StaleHandle { .. } => 10,
+            PredictorError::HandleLimitReached { .. } => 11,
+            PredictorError::InvalidStateTransition { .. } => 12,
+            PredictorError::GestureCancelled => 13,
+        }
+    }
+}
 
-pub type Result<T> = std::result::Result<T, PredictorError>;
+impl core::error::Error for PredictorError {}
+
+pub type Result<T> = core::result::Result<T, PredictorError>;
 
 #[cfg(test)]
 mod tests {
@@ -89,5 +158,17 @@ mod tests {
             minimum_ms: 30.0,
         };
         assert_eq!(err.to_string(), "Gesture too short: 20ms (minimum 30ms)");
+
+        let err = PredictorError::StaleHandle {
+            index: 3,
+            generation: 7,
+        };
+        assert_eq!(err.to_string(), "Stale handle: slot 3 generation 7 has been released");
+
+        let err = PredictorError::InvalidStateTransition {
+            from: GestureState::Ended,
+            event: "update".into(),
+        };
+        assert_eq!(err.to_string(), "Invalid state transition: cannot update while ended");
     }
 }
\ No newline at end of file
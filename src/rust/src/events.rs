@@ -0,0 +1,357 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::error::{PredictorError, Result};
+use crate::math;
+use crate::types::{Point2D, Prediction, Timestamp};
+
+/// Events kept before the oldest is dropped, so a consumer that stops polling
+/// can't grow the queue without bound
+const MAX_QUEUED_EVENTS: usize = 32;
+
+/// Minimum length in points of a predicted travel vector for its direction to count
+const MIN_DIRECTION_LENGTH: f64 = 1.0;
+
+/// When a prediction is different enough from the last emitted one to be
+/// worth an event
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedEventThresholds"))]
+pub struct EventThresholds {
+    /// Distance in points the predicted point must move from the last emitted one
+    pub min_distance: f64,
+    /// Confidence is bucketed into bands of this width; entering another band emits
+    pub confidence_band: f64,
+    /// Candidate resting positions; emits when the nearest one to the prediction changes
+    pub snap_points: Vec<Point2D>,
+    /// Emit when the predicted direction of travel reverses
+    pub direction_flip: bool,
+}
+
+impl Default for EventThresholds {
+    fn default() -> Self {
+        Self {
+            min_distance: 8.0,
+            confidence_band: 0.25,
+            snap_points: Vec::new(),
+            direction_flip: true,
+        }
+    }
+}
+
+/// A deserialized `EventThresholds` that hasn't been validated yet
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedEventThresholds {
+    min_distance: f64,
+    confidence_band: f64,
+    snap_points: Vec<Point2D>,
+    direction_flip: bool,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedEventThresholds> for EventThresholds {
+    type Error = PredictorError;
+
+    fn try_from(config: UncheckedEventThresholds) -> Result<Self> {
+        let config = Self {
+            min_distance: config.min_distance,
+            confidence_band: config.confidence_band,
+            snap_points: config.snap_points,
+            direction_flip: config.direction_flip,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl EventThresholds {
+    pub fn validate(&self) -> Result<()> {
+        if !self.min_distance.is_finite() || self.min_distance < 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "min_distance".into(),
+                value: self.min_distance,
+                reason: "must be non-negative and finite".into(),
+            });
+        }
+
+        if !(self.confidence_band > 0.0 && self.confidence_band <= 1.0) {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "confidence_band".into(),
+                value: self.confidence_band,
+                reason: "must be in (0, 1]".into(),
+            });
+        }
+
+        if let Some(point) = self.snap_points.iter().find(|p| !p.x.is_finite() || !p.y.is_finite()) {
+            return Err(PredictorError::InvalidCoordinate {
+                x: point.x,
+                y: point.y,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn band(&self, confidence: f64) -> u32 {
+        let bands = math::ceil(1.0 / self.confidence_band) as u32;
+        ((confidence / self.confidence_band) as u32).min(bands.saturating_sub(1))
+    }
+
+    fn nearest_snap_point(&self, position: Point2D) -> Option<usize> {
+        self.snap_points
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| position.distance_to(a).total_cmp(&position.distance_to(b)))
+            .map(|(index, _)| index)
+    }
+}
+
+/// Which thresholds a prediction crossed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PredictionChanges {
+    /// First prediction of the gesture
+    pub first: bool,
+    pub moved: bool,
+    pub confidence_band: bool,
+    pub snap_target: bool,
+    pub direction_flip: bool,
+}
+
+impl PredictionChanges {
+    pub const FIRST: u32 = 1 << 0;
+    pub const MOVED: u32 = 1 << 1;
+    pub const CONFIDENCE_BAND: u32 = 1 << 2;
+    pub const SNAP_TARGET: u32 = 1 << 3;
+    pub const DIRECTION_FLIP: u32 = 1 << 4;
+
+    pub fn any(&self) -> bool {
+        self.bits() != 0
+    }
+
+    /// Bitmask of the `PredictionChanges` constants, for FFI
+    pub fn bits(&self) -> u32 {
+        let mut bits = 0;
+        for (set, bit) in [
+            (self.first, Self::FIRST),
+            (self.moved, Self::MOVED),
+            (self.confidence_band, Self::CONFIDENCE_BAND),
+            (self.snap_target, Self::SNAP_TARGET),
+            (self.direction_flip, Self::DIRECTION_FLIP),
+        ] {
+            if set {
+                bits |= bit;
+            }
+        }
+        bits
+    }
+}
+
+/// A prediction that crossed at least one threshold
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PredictionEvent {
+    pub prediction: Prediction,
+    pub changes: PredictionChanges,
+    /// Index into `EventThresholds::snap_points` of the target nearest the prediction
+    pub snap_index: Option<usize>,
+    /// Timestamp of the sample that produced the prediction
+    pub timestamp: Timestamp,
+}
+
+/// State of the last emitted event that new predictions are compared against
+#[derive(Debug, Clone, Copy)]
+struct Emitted {
+    position: Point2D,
+    band: u32,
+    snap_index: Option<usize>,
+    direction: Option<Point2D>,
+}
+
+/// Filters a stream of predictions down to meaningful changes
+#[derive(Debug, Clone)]
+pub struct PredictionEvents {
+    thresholds: EventThresholds,
+    last: Option<Emitted>,
+    queue: VecDeque<PredictionEvent>,
+}
+
+impl PredictionEvents {
+    pub fn new(thresholds: EventThresholds) -> Result<Self> {
+        thresholds.validate()?;
+        Ok(Self {
+            thresholds,
+            last: None,
+            queue: VecDeque::with_capacity(MAX_QUEUED_EVENTS),
+        })
+    }
+
+    pub fn thresholds(&self) -> &EventThresholds {
+        &self.thresholds
+    }
+
+    /// Compare a new prediction against the last emitted one, queueing an event
+    /// if it crossed any threshold. `current` is the latest touch position, used
+    /// for the direction of travel.
+    pub fn observe(&mut self, prediction: Prediction, current: Point2D, timestamp: Timestamp) {
+        let band = self.thresholds.band(prediction.confidence);
+        let snap_index = self.thresholds.nearest_snap_point(prediction.position);
+        let travel = prediction.position - current;
+        let direction = if travel.magnitude() >= MIN_DIRECTION_LENGTH {
+            Some(travel)
+        } else {
+            None
+        };
+
+        let changes = match self.last {
+            None => PredictionChanges {
+                first: true,
+                ..Default::default()
+            },
+            Some(last) => PredictionChanges {
+                first: false,
+                moved: last.position.distance_to(&prediction.position) >= self.thresholds.min_distance,
+                confidence_band: band != last.band,
+                snap_target: snap_index != last.snap_index,
+                direction_flip: self.thresholds.direction_flip
+                    && matches!((last.direction, direction), (Some(a), Some(b)) if a.x * b.x + a.y * b.y < 0.0),
+            },
+        };
+
+        if !changes.any() {
+            return;
+        }
+
+        self.last = Some(Emitted {
+            position: prediction.position,
+            band,
+            snap_index,
+            direction: direction.or(self.last.and_then(|last| last.direction)),
+        });
+
+        if self.queue.len() >= MAX_QUEUED_EVENTS {
+            self.queue.pop_front();
+        }
+        self.queue.push_back(PredictionEvent {
+            prediction,
+            changes,
+            snap_index,
+            timestamp,
+        });
+    }
+
+    /// Take the oldest queued event
+    pub fn poll(&mut self) -> Option<PredictionEvent> {
+        self.queue.pop_front()
+    }
+
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Forget the last emitted prediction and drop queued events, so the next
+    /// prediction is reported as the first of a new gesture
+    pub fn clear(&mut self) {
+        self.last = None;
+        self.queue.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prediction(x: f64, confidence: f64) -> Prediction {
+        Prediction::new(Point2D::new(x, 0.0), confidence)
+    }
+
+    fn observe(events: &mut PredictionEvents, x: f64, confidence: f64) -> Option<PredictionChanges> {
+        events.observe(prediction(x, confidence), Point2D::new(0.0, 0.0), Timestamp::new(0.0));
+        events.poll().map(|event| event.changes)
+    }
+
+    #[test]
+    fn test_only_meaningful_changes_emit() {
+        let mut events = PredictionEvents::new(EventThresholds::default()).unwrap();
+
+        assert!(observe(&mut events, 100.0, 0.6).unwrap().first);
+        // Small move within the same band
+        assert!(observe(&mut events, 104.0, 0.6).is_none());
+
+        let changes = observe(&mut events, 110.0, 0.6).unwrap();
+        assert!(changes.moved);
+        assert!(!changes.confidence_band);
+
+        let changes = observe(&mut events, 110.0, 0.8).unwrap();
+        assert_eq!(changes.bits(), PredictionChanges::CONFIDENCE_BAND);
+
+        // Direction is relative to the current touch at the origin
+        let changes = observe(&mut events, -110.0, 0.8).unwrap();
+        assert!(changes.direction_flip);
+    }
+
+    #[test]
+    fn test_snap_target_change() {
+        let thresholds = EventThresholds {
+            min_distance: 1000.0,
+            snap_points: vec![Point2D::new(0.0, 0.0), Point2D::new(300.0, 0.0)],
+            ..Default::default()
+        };
+        let mut events = PredictionEvents::new(thresholds).unwrap();
+
+        events.observe(prediction(100.0, 0.5), Point2D::new(0.0, 0.0), Timestamp::new(0.0));
+        assert_eq!(events.poll().unwrap().snap_index, Some(0));
+
+        assert!(observe(&mut events, 140.0, 0.5).is_none());
+        events.observe(prediction(160.0, 0.5), Point2D::new(0.0, 0.0), Timestamp::new(0.0));
+        let event = events.poll().unwrap();
+        assert!(event.changes.snap_target);
+        assert_eq!(event.snap_index, Some(1));
+    }
+
+    #[test]
+    fn test_queue_is_bounded_and_cleared() {
+        let thresholds = EventThresholds {
+            min_distance: 0.0,
+            ..Default::default()
+        };
+        let mut events = PredictionEvents::new(thresholds).unwrap();
+        for i in 0..(MAX_QUEUED_EVENTS + 10) {
+            events.observe(prediction(i as f64, 0.5), Point2D::new(0.0, 0.0), Timestamp::new(i as f64));
+        }
+        assert_eq!(events.pending(), MAX_QUEUED_EVENTS);
+        // The oldest events were dropped
+        assert_eq!(events.poll().unwrap().timestamp.as_millis(), 10.0);
+
+        events.clear();
+        assert_eq!(events.pending(), 0);
+        assert!(observe(&mut events, 0.0, 0.5).unwrap().first);
+    }
+
+    #[test]
+    fn test_invalid_thresholds() {
+        let invalid = EventThresholds {
+            confidence_band: 0.0,
+            ..Default::default()
+        };
+        assert!(PredictionEvents::new(invalid).is_err());
+
+        let invalid = EventThresholds {
+            snap_points: vec![Point2D::new(f64::NAN, 0.0)],
+            ..Default::default()
+        };
+        assert!(PredictionEvents::new(invalid).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_validates() {
+        let json = serde_json::to_string(&EventThresholds::default()).unwrap();
+        assert_eq!(serde_json::from_str::<EventThresholds>(&json).unwrap(), EventThresholds::default());
+
+        let invalid = json.replace(r#""confidence_band":0.25"#, r#""confidence_band":1.5"#);
+        let error = serde_json::from_str::<EventThresholds>(&invalid).unwrap_err();
+        assert!(error.to_string().contains("confidence_band"));
+    }
+}
@@ -1,11 +1,29 @@
-use std::collections::HashMap;
+#[cfg(feature = "config")]
+use std::ffi::{c_char, CStr};
 use std::panic;
 use std::sync::{Arc, Mutex};
 
+use crate::cancellation::CancellationConfig;
+use crate::config::PredictorConfig;
+#[cfg(feature = "config")]
+use crate::config::{ConfigError, ConfigFormat};
+use crate::error::{PredictorError, Result};
+use crate::events::EventThresholds;
+use crate::filter::{FilterStats, InputFilterConfig, OneEuroConfig};
+use crate::handle::{HandleId, HandleMap};
 use crate::physics::PhysicsConfig;
 use crate::predictor::GesturePredictor;
+use crate::resample::{Interpolation, ResampleConfig};
+use crate::spsc::{PredictionReader, SampleWriter};
+use crate::types::{
+    DismissalPrediction, DisplayScale, DuplicateTimestampPolicy, Point2D, Transform2D,
+};
 
-const MAX_PREDICTORS: usize = 10000;
+pub(crate) const MAX_PREDICTORS: usize = 10000;
+
+/// Returned by `swipe_predictor_add_point_checked` when the call panicked or
+/// the context lock was poisoned, as opposed to a `PredictorError` code
+pub const SWIPE_PREDICTOR_ERR_INTERNAL: i32 = -1;
 
 /// Initialize the panic handler for the FFI module.
 /// This should be called once when the library is loaded.
@@ -45,49 +63,391 @@ pub struct SwipePredictorHandle {
     _private: [u8; 0],
 }
 
+/// Physics configuration passed by value, for callers such as a C++
+/// TurboModule that build the whole config at once
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwipePredictorConfig {
+    pub deceleration_rate: f64,
+    pub min_velocity_threshold: f64,
+    pub min_gesture_time_ms: f64,
+}
+
+impl From<SwipePredictorConfig> for PhysicsConfig {
+    fn from(config: SwipePredictorConfig) -> Self {
+        PhysicsConfig {
+            deceleration_rate: config.deceleration_rate,
+            min_velocity_threshold: config.min_velocity_threshold,
+            min_gesture_time_ms: config.min_gesture_time_ms,
+        }
+    }
+}
+
+impl From<PhysicsConfig> for SwipePredictorConfig {
+    fn from(config: PhysicsConfig) -> Self {
+        SwipePredictorConfig {
+            deceleration_rate: config.deceleration_rate,
+            min_velocity_threshold: config.min_velocity_threshold,
+            min_gesture_time_ms: config.min_gesture_time_ms,
+        }
+    }
+}
+
+/// Prediction returned by value from `swipe_predictor_predict`.
+///
+/// `status` is 0 when `x`, `y` and `confidence` hold a prediction, otherwise the
+/// `PredictorError::code` explaining why there is none (e.g. the gesture is too
+/// short or too slow) or `SWIPE_PREDICTOR_ERR_INTERNAL`; the other fields are then 0.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwipePredictorPrediction {
+    pub status: i32,
+    pub x: f64,
+    pub y: f64,
+    pub confidence: f64,
+}
+
+impl SwipePredictorPrediction {
+    fn unavailable(status: i32) -> Self {
+        Self {
+            status,
+            x: 0.0,
+            y: 0.0,
+            confidence: 0.0,
+        }
+    }
+}
+
+/// Opaque producer half of a lock-free SPSC predictor
+#[repr(C)]
+pub struct SwipePredictorWriter {
+    _private: [u8; 0],
+}
+
+/// Opaque consumer half of a lock-free SPSC predictor
+#[repr(C)]
+pub struct SwipePredictorReader {
+    _private: [u8; 0],
+}
+
+/// Latest prediction read from a `SwipePredictorReader`.
+///
+/// `sequence` changes every time the writer publishes; `status` follows the
+/// same convention as `SwipePredictorPrediction`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwipePredictorSnapshot {
+    pub sequence: u64,
+    pub status: i32,
+    pub x: f64,
+    pub y: f64,
+    pub confidence: f64,
+}
+
+/// Thresholds for `swipe_predictor_events_enable`.
+///
+/// `snap_points` holds `snap_point_count` interleaved x/y pairs and may be null
+/// when the count is 0; it is copied, so it needn't outlive the call. Distances
+/// are in pixels at the context's display scale.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SwipePredictorEventConfig {
+    pub min_distance: f64,
+    pub confidence_band: f64,
+    pub direction_flip: i32,
+    pub snap_points: *const f64,
+    pub snap_point_count: usize,
+}
+
+/// Prediction-changed event from `swipe_predictor_poll_event`.
+///
+/// `changes` is a bitmask of the `PredictionChanges` constants; `snap_index`
+/// is -1 when no snap points are configured.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwipePredictorEvent {
+    pub changes: u32,
+    pub snap_index: i32,
+    pub timestamp: f64,
+    pub x: f64,
+    pub y: f64,
+    pub confidence: f64,
+}
+
+/// Result of `swipe_predictor_predict_dismissal`; see `DismissalPrediction`.
+///
+/// `status` follows the `SwipePredictorPrediction` convention; the other
+/// fields are 0 unless it is 0.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwipePredictorDismissal {
+    pub status: i32,
+    pub will_dismiss: i32,
+    pub probability: f64,
+    pub current_distance: f64,
+    pub projected_distance: f64,
+}
+
+impl SwipePredictorDismissal {
+    fn unavailable(status: i32) -> Self {
+        Self {
+            status,
+            will_dismiss: 0,
+            probability: 0.0,
+            current_distance: 0.0,
+            projected_distance: 0.0,
+        }
+    }
+}
+
+/// Cancellation thresholds passed by value; see `CancellationConfig`.
+/// Speeds and distances are in pixels at the context's display scale.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwipePredictorCancellationConfig {
+    pub reversal_angle_deg: f64,
+    pub stop_speed: f64,
+    pub slow_velocity_ratio: f64,
+    pub min_displacement: f64,
+    pub hysteresis_samples: u32,
+}
+
+impl From<SwipePredictorCancellationConfig> for CancellationConfig {
+    fn from(config: SwipePredictorCancellationConfig) -> Self {
+        CancellationConfig {
+            reversal_angle_deg: config.reversal_angle_deg,
+            stop_speed: config.stop_speed,
+            slow_velocity_ratio: config.slow_velocity_ratio,
+            min_displacement: config.min_displacement,
+            hysteresis_samples: config.hysteresis_samples,
+        }
+    }
+}
+
+/// Input filter for `swipe_predictor_set_input_filter`; see `InputFilterConfig`.
+///
+/// `max_acceleration` and `beta` are in pixels at the context's display scale.
+/// A `max_acceleration` of 0 disables spike rejection, and the One Euro fields
+/// are ignored unless `one_euro_enabled` is non-zero.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SwipePredictorInputFilterConfig {
+    pub max_acceleration: f64,
+    pub merge_duplicates: i32,
+    pub one_euro_enabled: i32,
+    pub min_cutoff: f64,
+    pub beta: f64,
+    pub derivative_cutoff: f64,
+}
+
+impl From<SwipePredictorInputFilterConfig> for InputFilterConfig {
+    fn from(config: SwipePredictorInputFilterConfig) -> Self {
+        InputFilterConfig {
+            max_acceleration: (config.max_acceleration != 0.0).then_some(config.max_acceleration),
+            merge_duplicates: config.merge_duplicates != 0,
+            one_euro: (config.one_euro_enabled != 0).then_some(OneEuroConfig {
+                min_cutoff: config.min_cutoff,
+                beta: config.beta,
+                derivative_cutoff: config.derivative_cutoff,
+            }),
+        }
+    }
+}
+
+/// Counters from `swipe_predictor_filter_stats`; see `FilterStats`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SwipePredictorFilterStats {
+    pub spikes_rejected: u64,
+    pub duplicates_merged: u64,
+}
+
+impl From<FilterStats> for SwipePredictorFilterStats {
+    fn from(stats: FilterStats) -> Self {
+        Self {
+            spikes_rejected: stats.spikes_rejected,
+            duplicates_merged: stats.duplicates_merged,
+        }
+    }
+}
+
+/// Affine map passed by value, laid out like `CGAffineTransform`; see
+/// `Transform2D`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwipePredictorTransform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub tx: f64,
+    pub ty: f64,
+}
+
+impl From<SwipePredictorTransform> for Transform2D {
+    fn from(transform: SwipePredictorTransform) -> Self {
+        Transform2D {
+            a: transform.a,
+            b: transform.b,
+            c: transform.c,
+            d: transform.d,
+            tx: transform.tx,
+            ty: transform.ty,
+        }
+    }
+}
+
+/// Outcome of `swipe_predictor_context_load_config`; see `ConfigError`.
+///
+/// Strings are NUL-terminated and truncated to fit.
+#[cfg(feature = "config")]
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SwipePredictorConfigError {
+    /// 0 on success, otherwise a `PredictorError::code`
+    pub status: i32,
+    /// 1-based location of a syntax error, or 0
+    pub line: u32,
+    pub column: u32,
+    /// The rejected value of an out-of-range setting
+    pub value: f64,
+    /// Key of an out-of-range setting, e.g. "deceleration_rate"; empty for
+    /// syntax errors
+    pub field: [c_char; 64],
+    pub message: [c_char; 256],
+}
+
+#[cfg(feature = "config")]
+impl SwipePredictorConfigError {
+    fn ok() -> Self {
+        Self {
+            status: 0,
+            line: 0,
+            column: 0,
+            value: 0.0,
+            field: [0; 64],
+            message: [0; 256],
+        }
+    }
+
+    fn internal() -> Self {
+        Self {
+            status: SWIPE_PREDICTOR_ERR_INTERNAL,
+            ..Self::ok()
+        }
+    }
+
+    fn from_error(error: &ConfigError) -> Self {
+        let mut result = Self::ok();
+        result.status = error.code();
+        match error {
+            ConfigError::Syntax { line, column, .. } => {
+                result.line = u32::try_from(*line).unwrap_or(u32::MAX);
+                result.column = u32::try_from(*column).unwrap_or(u32::MAX);
+            }
+            ConfigError::Invalid(PredictorError::InvalidConfiguration { field, value, .. }) => {
+                copy_c_string(&mut result.field, field);
+                result.value = *value;
+            }
+            ConfigError::Invalid(_) => {}
+        }
+        copy_c_string(&mut result.message, &error.to_string());
+        result
+    }
+}
+
+/// Copy as much of `text` as fits into `buffer`, keeping it NUL-terminated
+/// and cutting on a character boundary
+#[cfg(feature = "config")]
+fn copy_c_string(buffer: &mut [c_char], text: &str) {
+    let mut len = text.len().min(buffer.len() - 1);
+    while !text.is_char_boundary(len) {
+        len -= 1;
+    }
+    for (dst, &src) in buffer.iter_mut().zip(&text.as_bytes()[..len]) {
+        *dst = src as c_char;
+    }
+    buffer[len] = 0;
+}
+
 /// Internal state for a predictor context
 struct PredictorContextInner {
-    predictors: HashMap<u32, GesturePredictor>,
-    next_id: u32,
-    physics_config: PhysicsConfig,
+    predictors: HandleMap<GesturePredictor>,
+    config: PredictorConfig,
+    /// Converts the caller's pixel coordinates to the points predictors work in
+    display_scale: DisplayScale,
 }
 
 impl PredictorContextInner {
     fn new(physics_config: PhysicsConfig) -> Self {
         Self {
-            predictors: HashMap::new(),
-            next_id: 0,
-            physics_config,
+            predictors: HandleMap::new(),
+            config: PredictorConfig {
+                physics: physics_config,
+                ..Default::default()
+            },
+            display_scale: DisplayScale::default(),
         }
     }
 
-    fn create_predictor(&mut self) -> Option<u32> {
+    fn create_predictor(&mut self) -> Option<HandleId> {
         if self.predictors.len() >= MAX_PREDICTORS {
             return None;
         }
 
-        let id = self.next_id;
-        self.next_id = self.next_id.wrapping_add(1);
+        match GesturePredictor::with_config(self.config) {
+            Ok(predictor) => self.predictors.insert(predictor),
+            Err(_) => None,
+        }
+    }
 
-        match GesturePredictor::new(self.physics_config) {
-            Ok(predictor) => {
-                self.predictors.insert(id, predictor);
-                Some(id)
+    /// Replace the context's physics configuration.
+    ///
+    /// The config is validated before anything changes, so either every target
+    /// predictor picks it up or none does.
+    fn update_physics_config(
+        &mut self,
+        physics_config: PhysicsConfig,
+        apply_to_existing: bool,
+    ) -> Result<()> {
+        physics_config.validate()?;
+
+        if apply_to_existing {
+            for predictor in self.predictors.values_mut() {
+                predictor.set_physics_config_unchecked(physics_config);
+            }
+        }
+
+        self.config.physics = physics_config;
+        Ok(())
+    }
+
+    /// Replace the whole configuration, like `update_physics_config`. Existing
+    /// predictors keep their buffer size.
+    #[cfg(feature = "config")]
+    fn update_config(&mut self, config: PredictorConfig, apply_to_existing: bool) -> Result<()> {
+        config.validate()?;
+
+        if apply_to_existing {
+            for predictor in self.predictors.values_mut() {
+                predictor.set_config_unchecked(&config);
             }
-            Err(_) => None,
         }
+
+        self.config = config;
+        Ok(())
     }
 
-    fn get_predictor_mut(&mut self, id: u32) -> Option<&mut GesturePredictor> {
-        self.predictors.get_mut(&id)
+    fn get_predictor_mut(&mut self, id: HandleId) -> Result<&mut GesturePredictor> {
+        self.predictors.get_mut(id)
     }
 
-    fn get_predictor(&self, id: u32) -> Option<&GesturePredictor> {
-        self.predictors.get(&id)
+    fn get_predictor(&self, id: HandleId) -> Result<&GesturePredictor> {
+        self.predictors.get(id)
     }
 
-    fn remove_predictor(&mut self, id: u32) -> bool {
-        self.predictors.remove(&id).is_some()
+    fn remove_predictor(&mut self, id: HandleId) -> bool {
+        self.predictors.remove(id).is_ok()
     }
 }
 
@@ -107,7 +467,7 @@ impl PredictorContext {
 /// Combined handle that safely shares ownership of the context
 struct PredictorHandle {
     context: Arc<Mutex<PredictorContextInner>>,
-    predictor_id: u32,
+    predictor_id: HandleId,
 }
 
 /// Create a new swipe predictor context with the given physics configuration
@@ -150,6 +510,234 @@ pub extern "C" fn swipe_predictor_context_create_default() -> *mut SwipePredicto
     Box::into_raw(context) as *mut SwipePredictorContext
 }
 
+/// Create a new swipe predictor context from a config struct
+/// 
+/// # Thread Safety
+/// The returned context is thread-safe. Multiple threads can safely create
+/// predictors from the same context simultaneously.
+/// 
+/// # Returns
+/// Returns a context handle on success, or null on invalid configuration.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_context_create_with_config(
+    config: SwipePredictorConfig,
+) -> *mut SwipePredictorContext {
+    swipe_predictor_context_create(
+        config.deceleration_rate,
+        config.min_velocity_threshold,
+        config.min_gesture_time_ms,
+    )
+}
+
+/// Get the configuration new predictors in the context are created with
+/// 
+/// # Returns
+/// The context's config, or the default config for a null context.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_context_get_config(
+    ctx: *mut SwipePredictorContext,
+) -> SwipePredictorConfig {
+    panic::catch_unwind(|| {
+        if ctx.is_null() {
+            return PhysicsConfig::default();
+        }
+
+        // SAFETY: We trust the caller to pass a valid context pointer
+        let context = unsafe { &*(ctx as *const PredictorContext) };
+
+        match context.inner.lock() {
+            Ok(inner) => inner.config.physics,
+            Err(_) => PhysicsConfig::default(), // Poisoned mutex
+        }
+    }).unwrap_or_default().into()
+}
+
+/// Update the physics configuration of a live context
+/// 
+/// Existing predictor handles stay valid. When `apply_to_existing` is non-zero the
+/// new configuration is applied to every predictor already in the context as well as
+/// to predictors created afterwards; when it is 0 only new predictors use it.
+/// 
+/// # Thread Safety
+/// The update happens under the context lock, so concurrent calls on any handle
+/// observe either the old or the new configuration, never a mix.
+/// 
+/// # Returns
+/// Returns 1 on success, or 0 on a null context or invalid configuration
+/// (in which case nothing is changed).
+#[no_mangle]
+pub extern "C" fn swipe_predictor_context_update_config(
+    ctx: *mut SwipePredictorContext,
+    deceleration_rate: f64,
+    min_velocity_threshold: f64,
+    min_gesture_time_ms: f64,
+    apply_to_existing: i32,
+) -> i32 {
+    panic::catch_unwind(|| {
+        if ctx.is_null() {
+            return 0;
+        }
+
+        let physics_config = PhysicsConfig {
+            deceleration_rate,
+            min_velocity_threshold,
+            min_gesture_time_ms,
+        };
+
+        // SAFETY: We trust the caller to pass a valid context pointer
+        let context = unsafe { &*(ctx as *const PredictorContext) };
+
+        let mut inner = match context.inner.lock() {
+            Ok(guard) => guard,
+            Err(_) => return 0, // Poisoned mutex
+        };
+
+        match inner.update_physics_config(physics_config, apply_to_existing != 0) {
+            Ok(()) => 1,
+            Err(_) => 0,
+        }
+    }).unwrap_or(0)
+}
+
+/// Load a JSON or TOML config document (see `PredictorConfig::parse`) into a
+/// live context, for predictors created afterwards and, when
+/// `apply_to_existing` is non-zero, for existing ones too (except their
+/// buffer size). `format` is a `ConfigFormat` value.
+/// 
+/// # Returns
+/// A status of 0 on success. Otherwise nothing is changed and the result
+/// describes the first problem found.
+/// 
+/// # Thread Safety
+/// The update happens under the context lock, like
+/// `swipe_predictor_context_update_config`.
+#[cfg(feature = "config")]
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn swipe_predictor_context_load_config(
+    ctx: *mut SwipePredictorContext,
+    document: *const c_char,
+    format: i32,
+    apply_to_existing: i32,
+) -> SwipePredictorConfigError {
+    panic::catch_unwind(|| {
+        if ctx.is_null() {
+            let error = ConfigError::Invalid(PredictorError::InvalidHandle { index: 0 });
+            return SwipePredictorConfigError::from_error(&error);
+        }
+        if document.is_null() {
+            let error = ConfigError::Syntax {
+                message: "document is NULL".to_string(),
+                line: 0,
+                column: 0,
+            };
+            return SwipePredictorConfigError::from_error(&error);
+        }
+
+        // SAFETY: We trust the caller to pass a NUL-terminated string
+        let document = unsafe { CStr::from_ptr(document) };
+        let config = document
+            .to_str()
+            .map_err(|_| ConfigError::Syntax {
+                message: "document is not valid UTF-8".to_string(),
+                line: 0,
+                column: 0,
+            })
+            .and_then(|document| {
+                PredictorConfig::parse(document, ConfigFormat::try_from(format)?)
+            });
+        let config = match config {
+            Ok(config) => config,
+            Err(error) => return SwipePredictorConfigError::from_error(&error),
+        };
+
+        // SAFETY: We trust the caller to pass a valid context pointer
+        let context = unsafe { &*(ctx as *const PredictorContext) };
+
+        let result = match context.inner.lock() {
+            Ok(mut inner) => inner.update_config(config, apply_to_existing != 0),
+            Err(_) => return SwipePredictorConfigError::internal(), // Poisoned mutex
+        };
+        match result {
+            Ok(()) => SwipePredictorConfigError::ok(),
+            Err(error) => SwipePredictorConfigError::from_error(&error.into()),
+        }
+    }).unwrap_or_else(|_| SwipePredictorConfigError::internal())
+}
+
+/// Set the display density of a live context, in physical pixels per point
+/// 
+/// Coordinates passed to and returned from every handle in the context are
+/// then in pixels and converted to points at the boundary, so the physics
+/// config, which is in points, behaves the same at any density. Takes effect
+/// for existing handles immediately, so a different scale is rejected while
+/// any handle in the context has samples buffered; reset them first.
+/// 
+/// # Returns
+/// 0 on success, or the `PredictorError::code` of an invalid scale or of a
+/// change while samples are buffered.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_context_set_display_scale(
+    ctx: *mut SwipePredictorContext,
+    pixels_per_point: f64,
+) -> i32 {
+    panic::catch_unwind(|| {
+        if ctx.is_null() {
+            return PredictorError::InvalidHandle { index: 0 }.code();
+        }
+
+        let display_scale = match DisplayScale::new(pixels_per_point) {
+            Ok(scale) => scale,
+            Err(e) => return e.code(),
+        };
+
+        // SAFETY: We trust the caller to pass a valid context pointer
+        let context = unsafe { &*(ctx as *const PredictorContext) };
+
+        match context.inner.lock() {
+            Ok(mut inner) => {
+                // Buffered samples were converted at the old scale
+                let buffered = inner.predictors.iter().any(|(_, predictor)| predictor.point_count() > 0);
+                if display_scale != inner.display_scale && buffered {
+                    return PredictorError::InvalidConfiguration {
+                        field: "display_scale".into(),
+                        value: pixels_per_point,
+                        reason: "can't change while samples are buffered".into(),
+                    }
+                    .code();
+                }
+                inner.display_scale = display_scale;
+                0
+            }
+            Err(_) => SWIPE_PREDICTOR_ERR_INTERNAL, // Poisoned mutex
+        }
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Get the display density of a context, in physical pixels per point
+/// 
+/// # Returns
+/// The context's scale, or 1 for a null context.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_context_get_display_scale(ctx: *mut SwipePredictorContext) -> f64 {
+    panic::catch_unwind(|| {
+        if ctx.is_null() {
+            return DisplayScale::default();
+        }
+
+        // SAFETY: We trust the caller to pass a valid context pointer
+        let context = unsafe { &*(ctx as *const PredictorContext) };
+
+        match context.inner.lock() {
+            Ok(inner) => inner.display_scale,
+            Err(_) => DisplayScale::default(), // Poisoned mutex
+        }
+    }).unwrap_or_default().pixels_per_point()
+}
+
 /// Free a swipe predictor context
 /// 
 /// # Safety
@@ -189,192 +777,1413 @@ pub extern "C" fn swipe_predictor_create_in_context(
             Err(_) => return std::ptr::null_mut(), // Poisoned mutex
         };
 
-        match inner.create_predictor() {
-            Some(predictor_id) => {
-                let handle = Box::new(PredictorHandle {
-                    context: Arc::clone(&context.inner),
-                    predictor_id,
-                });
-                Box::into_raw(handle) as *mut SwipePredictorHandle
-            }
-            None => std::ptr::null_mut(),
+        match inner.create_predictor() {
+            Some(predictor_id) => {
+                let handle = Box::new(PredictorHandle {
+                    context: Arc::clone(&context.inner),
+                    predictor_id,
+                });
+                Box::into_raw(handle) as *mut SwipePredictorHandle
+            }
+            None => std::ptr::null_mut(),
+        }
+    }).unwrap_or(std::ptr::null_mut())
+}
+
+/// Free a predictor handle
+/// 
+/// # Safety
+/// The handle remains safe to use until this function is called,
+/// even if the original context has been destroyed.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_destroy(handle: *mut SwipePredictorHandle) {
+    if handle.is_null() {
+        return;
+    }
+
+    // SAFETY: We created this pointer with Box::into_raw
+    let handle = unsafe { Box::from_raw(handle as *mut PredictorHandle) };
+
+    // Remove the predictor from the context
+    let context = Arc::clone(&handle.context);
+    let predictor_id = handle.predictor_id;
+    
+    // Drop the handle first
+    drop(handle);
+    
+    // Then try to remove the predictor
+    if let Ok(mut inner) = context.lock() {
+        inner.remove_predictor(predictor_id);
+    };
+    // If mutex is poisoned, it's not a problem since we're cleaning up
+}
+
+/// Add a touch point to the predictor
+/// 
+/// # Thread Safety
+/// This function is thread-safe. The same handle can be used from multiple
+/// threads, though this is not typically recommended for gesture prediction.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_add_point(
+    handle: *mut SwipePredictorHandle,
+    x: f64,
+    y: f64,
+    timestamp: f64,
+) -> i32 {
+    i32::from(swipe_predictor_add_point_checked(handle, x, y, timestamp) == 0)
+}
+
+/// Add a touch point, reporting why it was rejected
+///
+/// # Returns
+/// 0 if the point was accepted, the `PredictorError::code` of the failure
+/// otherwise, or `SWIPE_PREDICTOR_ERR_INTERNAL` on a panic or poisoned lock.
+///
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_add_point_checked(
+    handle: *mut SwipePredictorHandle,
+    x: f64,
+    y: f64,
+    timestamp: f64,
+) -> i32 {
+    panic::catch_unwind(|| match add_point(handle, x, y, timestamp) {
+        Some(Ok(())) => 0,
+        Some(Err(e)) => e.code(),
+        None => SWIPE_PREDICTOR_ERR_INTERNAL, // Poisoned mutex
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Add a touch point, keeping the full error for bridges that report it.
+/// Returns `None` if the context lock is poisoned.
+pub(crate) fn add_point(
+    handle: *mut SwipePredictorHandle,
+    x: f64,
+    y: f64,
+    timestamp: f64,
+) -> Option<Result<()>> {
+    with_scaled_predictor_mut(handle, |predictor, scale| {
+        let point = scale.to_points(Point2D::new(x, y));
+        predictor.add_touch_point(point.x, point.y, timestamp)
+    })
+}
+
+/// Run `f` on the handle's predictor under the context lock.
+/// Returns `None` if the context lock is poisoned.
+fn with_predictor_mut<T>(
+    handle: *mut SwipePredictorHandle,
+    f: impl FnOnce(&mut GesturePredictor) -> Result<T>,
+) -> Option<Result<T>> {
+    with_scaled_predictor_mut(handle, |predictor, _| f(predictor))
+}
+
+/// Like `with_predictor_mut`, also passing the context's display scale for
+/// converting coordinates that cross the boundary
+fn with_scaled_predictor_mut<T>(
+    handle: *mut SwipePredictorHandle,
+    f: impl FnOnce(&mut GesturePredictor, DisplayScale) -> Result<T>,
+) -> Option<Result<T>> {
+    if handle.is_null() {
+        return Some(Err(PredictorError::InvalidHandle { index: 0 }));
+    }
+
+    // SAFETY: We trust the caller to pass a valid handle
+    let handle = unsafe { &*(handle as *const PredictorHandle) };
+
+    let mut inner = handle.context.lock().ok()?;
+    let scale = inner.display_scale;
+    Some(
+        inner
+            .get_predictor_mut(handle.predictor_id)
+            .and_then(|predictor| f(predictor, scale)),
+    )
+}
+
+/// Map a lifecycle call's result to 0 or an error code
+fn status_code(result: Option<Result<()>>) -> i32 {
+    match result {
+        Some(Ok(())) => 0,
+        Some(Err(e)) => e.code(),
+        None => SWIPE_PREDICTOR_ERR_INTERNAL, // Poisoned mutex
+    }
+}
+
+/// Start a new gesture at touch-down from any state, discarding the previous
+/// one even if it is still active
+/// 
+/// # Returns
+/// 0 on success, or the `PredictorError::code` of the failure, e.g. an
+/// invalid timestamp.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_begin(
+    handle: *mut SwipePredictorHandle,
+    x: f64,
+    y: f64,
+    timestamp: f64,
+) -> i32 {
+    panic::catch_unwind(|| {
+        status_code(with_scaled_predictor_mut(handle, |predictor, scale| {
+            let point = scale.to_points(Point2D::new(x, y));
+            predictor.begin(point.x, point.y, timestamp)
+        }))
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Add a sample to the active gesture
+/// 
+/// Unlike `swipe_predictor_add_point_checked`, fails unless a gesture was begun.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_update(
+    handle: *mut SwipePredictorHandle,
+    x: f64,
+    y: f64,
+    timestamp: f64,
+) -> i32 {
+    panic::catch_unwind(|| {
+        status_code(with_scaled_predictor_mut(handle, |predictor, scale| {
+            let point = scale.to_points(Point2D::new(x, y));
+            predictor.update(point.x, point.y, timestamp)
+        }))
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Mark the touch as released at `release_time`
+/// 
+/// The final prediction stays available until the next begin or reset.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_end(handle: *mut SwipePredictorHandle, release_time: f64) -> i32 {
+    panic::catch_unwind(|| {
+        status_code(with_predictor_mut(handle, |predictor| predictor.end(release_time)))
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Mark the touch as cancelled by the system
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_cancel(handle: *mut SwipePredictorHandle) -> i32 {
+    panic::catch_unwind(|| {
+        status_code(with_predictor_mut(handle, |predictor| predictor.cancel()))
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Start queueing prediction-changed events for this predictor
+/// 
+/// After every accepted sample the new prediction is compared with the last
+/// reported one, and an event is queued only if it crossed a threshold in
+/// `config`. Pass a null `config` to stop and drop queued events.
+/// 
+/// # Returns
+/// 0 on success, or the `PredictorError::code` of an invalid configuration.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn swipe_predictor_events_enable(
+    handle: *mut SwipePredictorHandle,
+    config: *const SwipePredictorEventConfig,
+) -> i32 {
+    panic::catch_unwind(|| {
+        let thresholds = if config.is_null() {
+            None
+        } else {
+            // SAFETY: We checked that the config pointer is not null
+            let config = unsafe { &*config };
+            let snap_points = if config.snap_points.is_null() || config.snap_point_count == 0 {
+                Vec::new()
+            } else {
+                let Some(coord_count) = config.snap_point_count.checked_mul(2) else {
+                    return PredictorError::InvalidConfiguration {
+                        field: "snap_point_count".into(),
+                        value: config.snap_point_count as f64,
+                        reason: "too many snap points".into(),
+                    }
+                    .code();
+                };
+                // SAFETY: The caller guarantees `snap_points` holds `snap_point_count` x/y pairs
+                let coords = unsafe { std::slice::from_raw_parts(config.snap_points, coord_count) };
+                coords.chunks_exact(2).map(|xy| Point2D::new(xy[0], xy[1])).collect()
+            };
+
+            Some(EventThresholds {
+                min_distance: config.min_distance,
+                confidence_band: config.confidence_band,
+                snap_points,
+                direction_flip: config.direction_flip != 0,
+            })
+        };
+
+        status_code(with_scaled_predictor_mut(handle, |predictor, scale| {
+            let thresholds = thresholds.map(|thresholds| EventThresholds {
+                min_distance: scale.distance_to_points(thresholds.min_distance),
+                snap_points: thresholds.snap_points.into_iter().map(|p| scale.to_points(p)).collect(),
+                ..thresholds
+            });
+            predictor.set_event_thresholds(thresholds)
+        }))
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Take the oldest queued prediction-changed event
+/// 
+/// # Returns
+/// 1 if an event was written to `out_event`, 0 if the queue is empty, events
+/// are disabled or the arguments are invalid.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn swipe_predictor_poll_event(
+    handle: *mut SwipePredictorHandle,
+    out_event: *mut SwipePredictorEvent,
+) -> i32 {
+    panic::catch_unwind(|| {
+        if out_event.is_null() {
+            return 0;
+        }
+
+        match with_scaled_predictor_mut(handle, |predictor, scale| Ok((predictor.poll_event(), scale))) {
+            Some(Ok((Some(event), scale))) => {
+                let position = scale.to_pixels(event.prediction.position);
+                // SAFETY: We checked that the out-pointer is not null
+                unsafe {
+                    *out_event = SwipePredictorEvent {
+                        changes: event.changes.bits(),
+                        snap_index: event.snap_index.map_or(-1, |index| index as i32),
+                        timestamp: event.timestamp.as_millis(),
+                        x: position.x,
+                        y: position.y,
+                        confidence: event.prediction.confidence,
+                    };
+                }
+                1
+            }
+            _ => 0,
+        }
+    }).unwrap_or(0)
+}
+
+/// Get the gesture state as a `GestureState` value
+/// 
+/// # Returns
+/// 0 idle, 1 active, 2 ended, 3 cancelled, or -1 for an invalid handle.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_state(handle: *mut SwipePredictorHandle) -> i32 {
+    panic::catch_unwind(|| {
+        match with_predictor_mut(handle, |predictor| Ok(predictor.state())) {
+            Some(Ok(state)) => state as i32,
+            _ => -1,
+        }
+    }).unwrap_or(-1)
+}
+
+/// Number of samples the predictor has rejected since it was created
+///
+/// # Returns
+/// The count, or 0 for an invalid handle.
+///
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_rejected_count(handle: *mut SwipePredictorHandle) -> u64 {
+    panic::catch_unwind(|| {
+        if handle.is_null() {
+            return 0;
+        }
+
+        // SAFETY: We trust the caller to pass a valid handle
+        let handle = unsafe { &*(handle as *const PredictorHandle) };
+
+        let inner = match handle.context.lock() {
+            Ok(guard) => guard,
+            Err(_) => return 0,
+        };
+
+        inner
+            .get_predictor(handle.predictor_id)
+            .map(|predictor| predictor.rejected_sample_count())
+            .unwrap_or(0)
+    }).unwrap_or(0)
+}
+
+/// Filter samples before they enter the buffer; pass null to disable
+/// 
+/// # Returns
+/// 0 on success, or the `PredictorError::code` of an invalid configuration.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn swipe_predictor_set_input_filter(
+    handle: *mut SwipePredictorHandle,
+    config: *const SwipePredictorInputFilterConfig,
+) -> i32 {
+    panic::catch_unwind(|| {
+        let config = if config.is_null() {
+            None
+        } else {
+            // SAFETY: We checked that the config pointer is not null
+            Some(InputFilterConfig::from(unsafe { *config }))
+        };
+
+        status_code(with_scaled_predictor_mut(handle, |predictor, scale| {
+            let config = config.map(|config| InputFilterConfig {
+                max_acceleration: config.max_acceleration.map(|a| scale.distance_to_points(a)),
+                // The cutoff rises by `beta` per px/s, so by `beta * scale` per point/s
+                one_euro: config.one_euro.map(|one_euro| OneEuroConfig {
+                    beta: scale.distance_to_pixels(one_euro.beta),
+                    ..one_euro
+                }),
+                ..config
+            });
+            predictor.set_input_filter(config)
+        }))
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Get how many samples the input filter has dropped or merged.
+/// All zero for an invalid handle or when no filter is set.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_filter_stats(handle: *mut SwipePredictorHandle) -> SwipePredictorFilterStats {
+    panic::catch_unwind(|| {
+        match with_predictor_mut(handle, |predictor| Ok(predictor.filter_stats())) {
+            Some(Ok(stats)) => stats.into(),
+            _ => SwipePredictorFilterStats::default(),
+        }
+    }).unwrap_or_default()
+}
+
+/// Choose how samples sharing the previous sample's timestamp are buffered,
+/// as a `DuplicateTimestampPolicy` value
+/// 
+/// # Returns
+/// 0 on success, or the `PredictorError::code` of an unknown policy.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_set_duplicate_timestamp_policy(
+    handle: *mut SwipePredictorHandle,
+    policy: i32,
+) -> i32 {
+    panic::catch_unwind(|| {
+        status_code(with_predictor_mut(handle, |predictor| {
+            predictor.set_duplicate_timestamp_policy(DuplicateTimestampPolicy::try_from(policy)?);
+            Ok(())
+        }))
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Resample samples to `rate_hz` before analysis, interpolating with an
+/// `Interpolation` value; a rate of 0 analyses samples as they arrived
+/// 
+/// # Returns
+/// 0 on success, or the `PredictorError::code` of an invalid configuration.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_set_resampling(
+    handle: *mut SwipePredictorHandle,
+    rate_hz: f64,
+    interpolation: i32,
+) -> i32 {
+    panic::catch_unwind(|| {
+        status_code(with_predictor_mut(handle, |predictor| {
+            let config = if rate_hz == 0.0 {
+                None
+            } else {
+                Some(ResampleConfig {
+                    rate_hz,
+                    interpolation: Interpolation::try_from(interpolation)?,
+                })
+            };
+            predictor.set_resampling(config)
+        }))
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Map incoming points into a view's local space, and predictions back out;
+/// see `GesturePredictor::set_transform`. The transform works in points, after
+/// the context's display scale is applied.
+/// 
+/// # Returns
+/// 0 on success, or the `PredictorError::code` of a non-invertible transform.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_set_transform(
+    handle: *mut SwipePredictorHandle,
+    transform: SwipePredictorTransform,
+) -> i32 {
+    panic::catch_unwind(|| {
+        status_code(with_predictor_mut(handle, |predictor| {
+            predictor.set_transform(transform.into())
+        }))
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Get prediction from the predictor
+/// 
+/// # Thread Safety
+/// This function is thread-safe. The same handle can be used from multiple
+/// threads, though this is not typically recommended for gesture prediction.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn swipe_predictor_get_prediction(
+    handle: *mut SwipePredictorHandle,
+    out_x: *mut f64,
+    out_y: *mut f64,
+    out_confidence: *mut f64,
+) -> i32 {
+    panic::catch_unwind(|| {
+        if handle.is_null() || out_x.is_null() || out_y.is_null() || out_confidence.is_null() {
+            return 0;
+        }
+
+        // SAFETY: We trust the caller to pass a valid handle
+        let handle = unsafe { &*(handle as *const PredictorHandle) };
+        
+        let inner = match handle.context.lock() {
+            Ok(guard) => guard,
+            Err(_) => return 0, // Poisoned mutex
+        };
+
+        match inner.get_predictor(handle.predictor_id) {
+            Ok(predictor) => match predictor.predict() {
+                Ok(prediction) => {
+                    let position = inner.display_scale.to_pixels(prediction.position);
+                    // SAFETY: We checked that pointers are not null at the beginning
+                    unsafe {
+                        *out_x = position.x;
+                        *out_y = position.y;
+                        *out_confidence = prediction.confidence;
+                    }
+                    1
+                }
+                Err(_) => 0,
+            },
+            Err(_) => 0,
+        }
+    }).unwrap_or(0)
+}
+
+/// Get a prediction as a struct, without out-pointers
+/// 
+/// Suited to synchronous calls from a JSI host object: the result can be
+/// converted to a JS object directly, and `status` says why no prediction
+/// is available.
+/// 
+/// # Thread Safety
+/// This function is thread-safe. The same handle can be used from multiple
+/// threads, though this is not typically recommended for gesture prediction.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_predict(handle: *mut SwipePredictorHandle) -> SwipePredictorPrediction {
+    panic::catch_unwind(|| {
+        if handle.is_null() {
+            let code = PredictorError::InvalidHandle { index: 0 }.code();
+            return SwipePredictorPrediction::unavailable(code);
+        }
+
+        // SAFETY: We trust the caller to pass a valid handle
+        let handle = unsafe { &*(handle as *const PredictorHandle) };
+
+        let inner = match handle.context.lock() {
+            Ok(guard) => guard,
+            Err(_) => return SwipePredictorPrediction::unavailable(SWIPE_PREDICTOR_ERR_INTERNAL),
+        };
+
+        match inner
+            .get_predictor(handle.predictor_id)
+            .and_then(|predictor| predictor.predict())
+        {
+            Ok(prediction) => {
+                let position = inner.display_scale.to_pixels(prediction.position);
+                SwipePredictorPrediction {
+                    status: 0,
+                    x: position.x,
+                    y: position.y,
+                    confidence: prediction.confidence,
+                }
+            }
+            Err(e) => SwipePredictorPrediction::unavailable(e.code()),
+        }
+    }).unwrap_or(SwipePredictorPrediction::unavailable(SWIPE_PREDICTOR_ERR_INTERNAL))
+}
+
+/// SPSC writer behind a `SwipePredictorWriter`, converting pixel input to points
+struct ScaledWriter {
+    writer: SampleWriter,
+    display_scale: DisplayScale,
+}
+
+/// SPSC reader behind a `SwipePredictorReader`, converting predictions to pixels
+struct ScaledReader {
+    reader: PredictionReader,
+    display_scale: DisplayScale,
+}
+
+/// Create a lock-free single-producer/single-consumer predictor
+/// 
+/// The writer feeds samples and republishes the prediction after each one; the
+/// reader returns the latest prediction. Neither call takes a lock, so the reader
+/// can be polled from the UI thread (e.g. a Reanimated worklet) without ever
+/// waiting on the thread that feeds samples.
+/// 
+/// Coordinates on both halves are in pixels at `pixels_per_point`, as with
+/// `swipe_predictor_context_set_display_scale`; pass 1 to work in points.
+/// The scale is fixed for the lifetime of the pair.
+/// 
+/// # Thread Safety
+/// Each half may be used from one thread at a time; the two halves may be used
+/// concurrently. Free each half with its own destroy function.
+/// 
+/// # Returns
+/// Returns 1 and writes both handles on success, or 0 on invalid configuration,
+/// an invalid scale or null out-pointers.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn swipe_predictor_spsc_create(
+    config: SwipePredictorConfig,
+    pixels_per_point: f64,
+    out_writer: *mut *mut SwipePredictorWriter,
+    out_reader: *mut *mut SwipePredictorReader,
+) -> i32 {
+    panic::catch_unwind(|| {
+        if out_writer.is_null() || out_reader.is_null() {
+            return 0;
+        }
+
+        let Ok(display_scale) = DisplayScale::new(pixels_per_point) else {
+            return 0;
+        };
+        let (writer, reader) = match crate::spsc::split(config.into()) {
+            Ok(halves) => halves,
+            Err(_) => return 0,
+        };
+
+        let writer = ScaledWriter { writer, display_scale };
+        let reader = ScaledReader { reader, display_scale };
+        // SAFETY: We checked that the out-pointers are not null
+        unsafe {
+            *out_writer = Box::into_raw(Box::new(writer)) as *mut SwipePredictorWriter;
+            *out_reader = Box::into_raw(Box::new(reader)) as *mut SwipePredictorReader;
+        }
+        1
+    }).unwrap_or(0)
+}
+
+/// Add a touch point through the SPSC writer and publish the new prediction
+/// 
+/// # Returns
+/// 0 if the point was accepted, otherwise the same codes as
+/// `swipe_predictor_add_point_checked`.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_writer_add_point(
+    writer: *mut SwipePredictorWriter,
+    x: f64,
+    y: f64,
+    timestamp: f64,
+) -> i32 {
+    panic::catch_unwind(|| {
+        if writer.is_null() {
+            return PredictorError::InvalidHandle { index: 0 }.code();
+        }
+
+        // SAFETY: We trust the caller to pass a valid writer used from one thread
+        let writer = unsafe { &mut *(writer as *mut ScaledWriter) };
+
+        let point = writer.display_scale.to_points(Point2D::new(x, y));
+        match writer.writer.add_touch_point(point.x, point.y, timestamp) {
+            Ok(()) => 0,
+            Err(e) => e.code(),
+        }
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+/// Reset the SPSC writer's gesture and publish an empty snapshot
+#[no_mangle]
+pub extern "C" fn swipe_predictor_writer_reset(writer: *mut SwipePredictorWriter) -> i32 {
+    panic::catch_unwind(|| {
+        if writer.is_null() {
+            return 0;
+        }
+
+        // SAFETY: We trust the caller to pass a valid writer used from one thread
+        let writer = unsafe { &mut *(writer as *mut ScaledWriter) };
+        writer.writer.reset();
+        1
+    }).unwrap_or(0)
+}
+
+/// Free the SPSC writer. The reader keeps returning the last published snapshot.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_writer_destroy(writer: *mut SwipePredictorWriter) {
+    if writer.is_null() {
+        return;
+    }
+
+    // SAFETY: We created this pointer with Box::into_raw
+    unsafe {
+        let _ = Box::from_raw(writer as *mut ScaledWriter);
+    }
+}
+
+/// Read the latest prediction published by the SPSC writer, without blocking
+#[no_mangle]
+pub extern "C" fn swipe_predictor_reader_latest(reader: *mut SwipePredictorReader) -> SwipePredictorSnapshot {
+    panic::catch_unwind(|| {
+        if reader.is_null() {
+            return SwipePredictorSnapshot {
+                sequence: 0,
+                status: PredictorError::InvalidHandle { index: 0 }.code(),
+                x: 0.0,
+                y: 0.0,
+                confidence: 0.0,
+            };
+        }
+
+        // SAFETY: We trust the caller to pass a valid reader
+        let reader = unsafe { &*(reader as *const ScaledReader) };
+        let snapshot = reader.reader.latest();
+        let (x, y, confidence) = match snapshot.prediction {
+            Some(prediction) => {
+                let position = reader.display_scale.to_pixels(prediction.position);
+                (position.x, position.y, prediction.confidence)
+            }
+            None => (0.0, 0.0, 0.0),
+        };
+
+        SwipePredictorSnapshot {
+            sequence: snapshot.sequence,
+            status: snapshot.status,
+            x,
+            y,
+            confidence,
+        }
+    }).unwrap_or(SwipePredictorSnapshot {
+        sequence: 0,
+        status: SWIPE_PREDICTOR_ERR_INTERNAL,
+        x: 0.0,
+        y: 0.0,
+        confidence: 0.0,
+    })
+}
+
+/// Free the SPSC reader
+#[no_mangle]
+pub extern "C" fn swipe_predictor_reader_destroy(reader: *mut SwipePredictorReader) {
+    if reader.is_null() {
+        return;
+    }
+
+    // SAFETY: We created this pointer with Box::into_raw
+    unsafe {
+        let _ = Box::from_raw(reader as *mut ScaledReader);
+    }
+}
+
+/// Reset the predictor
+/// 
+/// # Thread Safety
+/// This function is thread-safe. The same handle can be used from multiple
+/// threads, though this is not typically recommended for gesture prediction.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_reset(handle: *mut SwipePredictorHandle) -> i32 {
+    panic::catch_unwind(|| {
+        if handle.is_null() {
+            return 0;
+        }
+
+        // SAFETY: We trust the caller to pass a valid handle
+        let handle = unsafe { &*(handle as *const PredictorHandle) };
+        
+        let mut inner = match handle.context.lock() {
+            Ok(guard) => guard,
+            Err(_) => return 0, // Poisoned mutex
+        };
+
+        match inner.get_predictor_mut(handle.predictor_id) {
+            Ok(predictor) => {
+                predictor.reset();
+                1
+            }
+            Err(_) => 0,
+        }
+    }).unwrap_or(0)
+}
+
+/// Detect if the gesture appears to be cancelled
+/// 
+/// # Thread Safety
+/// This function is thread-safe. The same handle can be used from multiple
+/// threads, though this is not typically recommended for gesture prediction.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_detect_cancellation(handle: *mut SwipePredictorHandle) -> i32 {
+    panic::catch_unwind(|| {
+        if handle.is_null() {
+            return 0;
+        }
+
+        // SAFETY: We trust the caller to pass a valid handle
+        let handle = unsafe { &*(handle as *const PredictorHandle) };
+        
+        let inner = match handle.context.lock() {
+            Ok(guard) => guard,
+            Err(_) => return 0, // Poisoned mutex
+        };
+
+        match inner.get_predictor(handle.predictor_id) {
+            Ok(predictor) => i32::from(predictor.detect_cancellation()),
+            Err(_) => 0,
+        }
+    }).unwrap_or(0)
+}
+
+/// Predict whether a swipe-to-dismiss gesture ends past `threshold` px from
+/// where it started, or returns
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_predict_dismissal(
+    handle: *mut SwipePredictorHandle,
+    threshold: f64,
+) -> SwipePredictorDismissal {
+    panic::catch_unwind(|| {
+        let dismissal = with_scaled_predictor_mut(handle, |predictor, scale| {
+            let dismissal = predictor.predict_dismissal(scale.distance_to_points(threshold))?;
+            Ok(DismissalPrediction {
+                current_distance: scale.distance_to_pixels(dismissal.current_distance),
+                projected_distance: scale.distance_to_pixels(dismissal.projected_distance),
+                ..dismissal
+            })
+        });
+        match dismissal {
+            Some(Ok(dismissal)) => SwipePredictorDismissal {
+                status: 0,
+                will_dismiss: i32::from(dismissal.will_dismiss),
+                probability: dismissal.probability,
+                current_distance: dismissal.current_distance,
+                projected_distance: dismissal.projected_distance,
+            },
+            Some(Err(e)) => SwipePredictorDismissal::unavailable(e.code()),
+            None => SwipePredictorDismissal::unavailable(SWIPE_PREDICTOR_ERR_INTERNAL),
+        }
+    }).unwrap_or(SwipePredictorDismissal::unavailable(SWIPE_PREDICTOR_ERR_INTERNAL))
+}
+
+/// Get why the gesture appears to be cancelled
+/// 
+/// # Returns
+/// 0 if it doesn't, otherwise a `CancellationReason` value: 1 direction
+/// reversal, 2 stopped, 3 slowed below threshold, 4 returned past origin.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_cancellation_reason(handle: *mut SwipePredictorHandle) -> i32 {
+    panic::catch_unwind(|| {
+        match with_predictor_mut(handle, |predictor| Ok(predictor.cancellation())) {
+            Some(Ok(Some(reason))) => reason as i32,
+            _ => 0,
+        }
+    }).unwrap_or(0)
+}
+
+/// Replace the predictor's cancellation thresholds
+/// 
+/// # Returns
+/// 0 on success, or the `PredictorError::code` of an invalid configuration.
+/// 
+/// # Thread Safety
+/// This function is thread-safe.
+#[no_mangle]
+pub extern "C" fn swipe_predictor_set_cancellation_config(
+    handle: *mut SwipePredictorHandle,
+    config: SwipePredictorCancellationConfig,
+) -> i32 {
+    panic::catch_unwind(|| {
+        status_code(with_scaled_predictor_mut(handle, |predictor, scale| {
+            let config = CancellationConfig::from(config);
+            predictor.set_cancellation_config(CancellationConfig {
+                stop_speed: scale.distance_to_points(config.stop_speed),
+                min_displacement: scale.distance_to_points(config.min_displacement),
+                ..config
+            })
+        }))
+    }).unwrap_or(SWIPE_PREDICTOR_ERR_INTERNAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_struct_api() {
+        let config = SwipePredictorConfig {
+            deceleration_rate: 750.0,
+            min_velocity_threshold: 50.0,
+            min_gesture_time_ms: 30.0,
+        };
+        let ctx = swipe_predictor_context_create_with_config(config);
+        assert!(!ctx.is_null());
+        assert_eq!(swipe_predictor_context_get_config(ctx), config);
+
+        let handle = swipe_predictor_create_in_context(ctx);
+        swipe_predictor_add_point(handle, 0.0, 0.0, 0.0);
+
+        let too_few = PredictorError::InsufficientData { required: 2, actual: 1 };
+        let result = swipe_predictor_predict(handle);
+        assert_eq!(result.status, too_few.code());
+        assert_eq!(result.confidence, 0.0);
+
+        for i in 1..6 {
+            swipe_predictor_add_point(handle, i as f64 * 20.0, 0.0, i as f64 * 20.0);
+        }
+        let result = swipe_predictor_predict(handle);
+        assert_eq!(result.status, 0);
+        assert!(result.x > 100.0);
+        assert!(result.confidence > 0.0);
+
+        // Matches the out-pointer API
+        let mut x = 0.0;
+        let mut y = 0.0;
+        let mut confidence = 0.0;
+        swipe_predictor_get_prediction(handle, &mut x, &mut y, &mut confidence);
+        assert_eq!((result.x, result.y, result.confidence), (x, y, confidence));
+
+        let invalid = SwipePredictorConfig {
+            deceleration_rate: -1.0,
+            ..config
+        };
+        assert!(swipe_predictor_context_create_with_config(invalid).is_null());
+        assert_ne!(swipe_predictor_predict(std::ptr::null_mut()).status, 0);
+
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
+
+    #[test]
+    fn test_spsc_api() {
+        let mut writer = std::ptr::null_mut();
+        let mut reader = std::ptr::null_mut();
+        let config = SwipePredictorConfig::from(PhysicsConfig::default());
+        assert_eq!(swipe_predictor_spsc_create(config, 1.0, &mut writer, &mut reader), 1);
+
+        let empty = swipe_predictor_reader_latest(reader);
+        assert_ne!(empty.status, 0);
+
+        for i in 0..6 {
+            assert_eq!(swipe_predictor_writer_add_point(writer, i as f64 * 20.0, 0.0, i as f64 * 20.0), 0);
+        }
+        let snapshot = swipe_predictor_reader_latest(reader);
+        assert_eq!(snapshot.status, 0);
+        assert!(snapshot.x > 100.0);
+        assert!(snapshot.sequence > empty.sequence);
+
+        // The reader outlives the writer
+        assert_eq!(swipe_predictor_writer_reset(writer), 1);
+        swipe_predictor_writer_destroy(writer);
+        assert_ne!(swipe_predictor_reader_latest(reader).status, 0);
+        swipe_predictor_reader_destroy(reader);
+
+        let invalid = SwipePredictorConfig {
+            deceleration_rate: 0.0,
+            ..config
+        };
+        assert_eq!(swipe_predictor_spsc_create(invalid, 1.0, &mut writer, &mut reader), 0);
+        assert_eq!(swipe_predictor_spsc_create(config, 0.0, &mut writer, &mut reader), 0);
+    }
+
+    #[test]
+    fn test_spsc_display_scale() {
+        let config = SwipePredictorConfig::from(PhysicsConfig::default());
+        let latest_x = |pixels_per_point: f64| {
+            let mut writer = std::ptr::null_mut();
+            let mut reader = std::ptr::null_mut();
+            assert_eq!(swipe_predictor_spsc_create(config, pixels_per_point, &mut writer, &mut reader), 1);
+            for i in 0..8 {
+                let x = i as f64 * 10.0 * pixels_per_point;
+                assert_eq!(swipe_predictor_writer_add_point(writer, x, 0.0, i as f64 * 16.0), 0);
+            }
+            let snapshot = swipe_predictor_reader_latest(reader);
+            swipe_predictor_writer_destroy(writer);
+            swipe_predictor_reader_destroy(reader);
+            assert_eq!(snapshot.status, 0);
+            snapshot.x
+        };
+
+        // The same swipe in points and at 3x in pixels
+        assert!((latest_x(3.0) - latest_x(1.0) * 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lifecycle_api() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
+        let transition = PredictorError::InvalidStateTransition {
+            from: crate::types::GestureState::Idle,
+            event: "update".into(),
+        };
+
+        assert_eq!(swipe_predictor_state(handle), 0);
+        assert_eq!(swipe_predictor_update(handle, 0.0, 0.0, 0.0), transition.code());
+
+        assert_eq!(swipe_predictor_begin(handle, 0.0, 0.0, 0.0), 0);
+        for i in 1..6 {
+            assert_eq!(swipe_predictor_update(handle, i as f64 * 20.0, 0.0, i as f64 * 20.0), 0);
+        }
+        assert_eq!(swipe_predictor_state(handle), 1);
+        assert_eq!(swipe_predictor_end(handle, 100.0), 0);
+        assert_eq!(swipe_predictor_state(handle), 2);
+        assert_eq!(swipe_predictor_predict(handle).status, 0);
+
+        assert_eq!(swipe_predictor_begin(handle, 0.0, 0.0, 200.0), 0);
+        // Begin also restarts a gesture that is still active
+        assert_eq!(swipe_predictor_begin(handle, 0.0, 0.0, 150.0), 0);
+        assert_eq!(swipe_predictor_state(handle), 1);
+        assert_eq!(swipe_predictor_cancel(handle), 0);
+        assert_eq!(swipe_predictor_state(handle), 3);
+        assert_eq!(
+            swipe_predictor_predict(handle).status,
+            PredictorError::GestureCancelled.code()
+        );
+        assert_eq!(swipe_predictor_state(std::ptr::null_mut()), -1);
+
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
+
+    #[test]
+    fn test_event_queue_api() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
+        let mut event = SwipePredictorEvent {
+            changes: 0,
+            snap_index: 0,
+            timestamp: 0.0,
+            x: 0.0,
+            y: 0.0,
+            confidence: 0.0,
+        };
+
+        let snap_points = [-1000.0, 0.0, 1000.0, 0.0];
+        let config = SwipePredictorEventConfig {
+            min_distance: 8.0,
+            confidence_band: 0.25,
+            direction_flip: 1,
+            snap_points: snap_points.as_ptr(),
+            snap_point_count: 2,
+        };
+        assert_eq!(swipe_predictor_events_enable(handle, &config), 0);
+
+        assert_eq!(swipe_predictor_poll_event(handle, &mut event), 0);
+        for i in 0..6 {
+            swipe_predictor_add_point(handle, i as f64 * 20.0, 0.0, i as f64 * 20.0);
+        }
+        assert_eq!(swipe_predictor_poll_event(handle, &mut event), 1);
+        assert_ne!(event.changes & crate::events::PredictionChanges::FIRST, 0);
+        assert_eq!(event.snap_index, 1);
+        assert!(event.x > 0.0);
+
+        let invalid = SwipePredictorEventConfig {
+            confidence_band: 2.0,
+            ..config
+        };
+        assert_ne!(swipe_predictor_events_enable(handle, &invalid), 0);
+
+        let overflowing = SwipePredictorEventConfig {
+            snap_point_count: usize::MAX,
+            ..config
+        };
+        assert_eq!(
+            swipe_predictor_events_enable(handle, &overflowing),
+            PredictorError::InvalidConfiguration {
+                field: "snap_point_count".into(),
+                value: 0.0,
+                reason: "".into(),
+            }
+            .code()
+        );
+
+        // Disabling drops the queue
+        assert_eq!(swipe_predictor_events_enable(handle, std::ptr::null()), 0);
+        assert_eq!(swipe_predictor_poll_event(handle, &mut event), 0);
+
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
+
+    #[test]
+    fn test_input_filter_api() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
+
+        let mut config = SwipePredictorInputFilterConfig {
+            max_acceleration: 250_000.0,
+            merge_duplicates: 1,
+            one_euro_enabled: 0,
+            min_cutoff: 0.0,
+            beta: 0.0,
+            derivative_cutoff: 0.0,
+        };
+        assert_eq!(swipe_predictor_set_input_filter(handle, &config), 0);
+
+        for i in 0..4 {
+            swipe_predictor_add_point(handle, i as f64 * 16.0, 0.0, i as f64 * 16.0);
+        }
+        swipe_predictor_add_point(handle, 50.0, 0.0, 48.0);
+        assert_eq!(swipe_predictor_add_point_checked(handle, 900.0, 0.0, 64.0), 0);
+        assert_eq!(
+            swipe_predictor_filter_stats(handle),
+            SwipePredictorFilterStats {
+                spikes_rejected: 1,
+                duplicates_merged: 1,
+            }
+        );
+
+        // Enabled One Euro fields are validated
+        config.one_euro_enabled = 1;
+        let invalid = PredictorError::InvalidConfiguration {
+            field: "min_cutoff".into(),
+            value: 0.0,
+            reason: "".into(),
+        };
+        assert_eq!(swipe_predictor_set_input_filter(handle, &config), invalid.code());
+
+        assert_eq!(swipe_predictor_set_input_filter(handle, std::ptr::null()), 0);
+        assert_eq!(swipe_predictor_filter_stats(handle), SwipePredictorFilterStats::default());
+        assert_eq!(
+            swipe_predictor_filter_stats(std::ptr::null_mut()),
+            SwipePredictorFilterStats::default()
+        );
+
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
+
+    #[test]
+    fn test_duplicate_timestamp_policy_api() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
+
+        let unknown = PredictorError::InvalidConfiguration {
+            field: "duplicate_timestamp_policy".into(),
+            value: 5.0,
+            reason: "".into(),
+        };
+        assert_eq!(swipe_predictor_set_duplicate_timestamp_policy(handle, 5), unknown.code());
+
+        let reject = DuplicateTimestampPolicy::Reject as i32;
+        assert_eq!(swipe_predictor_set_duplicate_timestamp_policy(handle, reject), 0);
+        assert_eq!(swipe_predictor_add_point_checked(handle, 0.0, 0.0, 0.0), 0);
+        let duplicate = PredictorError::InvalidTimestamp {
+            timestamp: 0.0,
+            reason: "".into(),
+        };
+        assert_eq!(swipe_predictor_add_point_checked(handle, 1.0, 0.0, 0.0), duplicate.code());
+
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
+
+    #[test]
+    fn test_resampling_api() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
+
+        let catmull_rom = Interpolation::CatmullRom as i32;
+        assert_eq!(swipe_predictor_set_resampling(handle, 120.0, catmull_rom), 0);
+        let invalid = PredictorError::InvalidConfiguration {
+            field: "rate_hz".into(),
+            value: -1.0,
+            reason: "".into(),
+        };
+        assert_eq!(swipe_predictor_set_resampling(handle, -1.0, catmull_rom), invalid.code());
+        assert_eq!(swipe_predictor_set_resampling(handle, 120.0, 7), invalid.code());
+
+        for i in 0..8 {
+            swipe_predictor_add_point(handle, i as f64 * 20.0, 0.0, i as f64 * 16.0);
         }
-    }).unwrap_or(std::ptr::null_mut())
-}
+        assert_eq!(swipe_predictor_predict(handle).status, 0);
+        assert_eq!(swipe_predictor_set_resampling(handle, 0.0, 0), 0);
+        let invalid_handle = PredictorError::InvalidHandle { index: 0 };
+        assert_eq!(swipe_predictor_set_resampling(std::ptr::null_mut(), 0.0, 0), invalid_handle.code());
 
-/// Free a predictor handle
-/// 
-/// # Safety
-/// The handle remains safe to use until this function is called,
-/// even if the original context has been destroyed.
-#[no_mangle]
-pub extern "C" fn swipe_predictor_destroy(handle: *mut SwipePredictorHandle) {
-    if handle.is_null() {
-        return;
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
     }
 
-    // SAFETY: We created this pointer with Box::into_raw
-    let handle = unsafe { Box::from_raw(handle as *mut PredictorHandle) };
-
-    // Remove the predictor from the context
-    let context = Arc::clone(&handle.context);
-    let predictor_id = handle.predictor_id;
-    
-    // Drop the handle first
-    drop(handle);
-    
-    // Then try to remove the predictor
-    if let Ok(mut inner) = context.lock() {
-        inner.remove_predictor(predictor_id);
-    };
-    // If mutex is poisoned, it's not a problem since we're cleaning up
-}
+    #[test]
+    fn test_transform_api() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
 
-/// Add a touch point to the predictor
-/// 
-/// # Thread Safety
-/// This function is thread-safe. The same handle can be used from multiple
-/// threads, though this is not typically recommended for gesture prediction.
-#[no_mangle]
-pub extern "C" fn swipe_predictor_add_point(
-    handle: *mut SwipePredictorHandle,
-    x: f64,
-    y: f64,
-    timestamp: f64,
-) -> i32 {
-    panic::catch_unwind(|| {
-        if handle.is_null() {
-            return 0;
+        // Local space is the screen shifted by 100 and doubled
+        let transform = SwipePredictorTransform {
+            a: 2.0,
+            b: 0.0,
+            c: 0.0,
+            d: 2.0,
+            tx: -200.0,
+            ty: 0.0,
+        };
+        assert_eq!(swipe_predictor_set_transform(handle, transform), 0);
+        for i in 0..8 {
+            swipe_predictor_add_point(handle, 100.0 + i as f64 * 10.0, 0.0, i as f64 * 16.0);
         }
-
-        // SAFETY: We trust the caller to pass a valid handle
-        let handle = unsafe { &*(handle as *const PredictorHandle) };
-        
-        let mut inner = match handle.context.lock() {
-            Ok(guard) => guard,
-            Err(_) => return 0, // Poisoned mutex
+        // Dismissal distances are on screen: 70, i.e. 140 local units
+        let dismissal = swipe_predictor_predict_dismissal(handle, 100.0);
+        assert!((dismissal.current_distance - 70.0).abs() < 1e-9);
+
+        let prediction = swipe_predictor_predict(handle);
+        assert_eq!(prediction.status, 0);
+        assert!(prediction.x > 170.0);
+
+        let singular = SwipePredictorTransform { a: 0.0, ..transform };
+        let invalid = PredictorError::InvalidConfiguration {
+            field: "transform".into(),
+            value: 0.0,
+            reason: "".into(),
         };
+        assert_eq!(swipe_predictor_set_transform(handle, singular), invalid.code());
+        let invalid_handle = PredictorError::InvalidHandle { index: 0 };
+        assert_eq!(swipe_predictor_set_transform(std::ptr::null_mut(), transform), invalid_handle.code());
 
-        match inner.get_predictor_mut(handle.predictor_id) {
-            Some(predictor) => match predictor.add_touch_point(x, y, timestamp) {
-                Ok(_) => 1,
-                Err(_) => 0,
-            },
-            None => 0,
-        }
-    }).unwrap_or(0)
-}
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
 
-/// Get prediction from the predictor
-/// 
-/// # Thread Safety
-/// This function is thread-safe. The same handle can be used from multiple
-/// threads, though this is not typically recommended for gesture prediction.
-#[no_mangle]
-#[allow(clippy::not_unsafe_ptr_arg_deref)]
-pub extern "C" fn swipe_predictor_get_prediction(
-    handle: *mut SwipePredictorHandle,
-    out_x: *mut f64,
-    out_y: *mut f64,
-    out_confidence: *mut f64,
-) -> i32 {
-    panic::catch_unwind(|| {
-        if handle.is_null() || out_x.is_null() || out_y.is_null() || out_confidence.is_null() {
-            return 0;
-        }
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_load_config() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
+        let toml = ConfigFormat::Toml as i32;
+        let json = ConfigFormat::Json as i32;
+
+        let document = c"preset = \"android\"\n[physics]\nmin_gesture_time_ms = 20.0\n";
+        let result = swipe_predictor_context_load_config(ctx, document.as_ptr(), toml, 1);
+        assert_eq!(result.status, 0);
+        let config = swipe_predictor_context_get_config(ctx);
+        assert_eq!(config.deceleration_rate, 1800.0);
+        assert_eq!(config.min_gesture_time_ms, 20.0);
+        let existing = with_predictor_mut(handle, |predictor| Ok(predictor.physics_config())).unwrap().unwrap();
+        assert_eq!(existing.deceleration_rate, 1800.0);
+
+        let field = |result: &SwipePredictorConfigError| {
+            unsafe { CStr::from_ptr(result.field.as_ptr()) }.to_str().unwrap().to_string()
+        };
 
-        // SAFETY: We trust the caller to pass a valid handle
-        let handle = unsafe { &*(handle as *const PredictorHandle) };
-        
-        let inner = match handle.context.lock() {
-            Ok(guard) => guard,
-            Err(_) => return 0, // Poisoned mutex
+        let out_of_range = c"{\"physics\": {\"deceleration_rate\": 0}}";
+        let result = swipe_predictor_context_load_config(ctx, out_of_range.as_ptr(), json, 0);
+        let invalid = PredictorError::InvalidConfiguration {
+            field: "deceleration_rate".into(),
+            value: 0.0,
+            reason: "".into(),
         };
+        assert_eq!(result.status, invalid.code());
+        assert_eq!(field(&result), "deceleration_rate");
+        assert_eq!(result.value, 0.0);
+        assert_eq!(result.line, 0);
+
+        let typo = c"{\n  \"bufer_size\": 3\n}";
+        let result = swipe_predictor_context_load_config(ctx, typo.as_ptr(), json, 0);
+        assert_eq!(result.status, invalid.code());
+        assert_eq!((result.line, field(&result).as_str()), (2, ""));
+        let message = unsafe { CStr::from_ptr(result.message.as_ptr()) }.to_str().unwrap();
+        assert!(message.contains("bufer_size"));
+
+        // Failed loads leave the config alone
+        assert_eq!(swipe_predictor_context_get_config(ctx).deceleration_rate, 1800.0);
+
+        let result = swipe_predictor_context_load_config(ctx, std::ptr::null(), json, 0);
+        assert_eq!(result.status, invalid.code());
+        let result = swipe_predictor_context_load_config(ctx, document.as_ptr(), 9, 0);
+        assert_eq!(result.status, invalid.code());
+        let result = swipe_predictor_context_load_config(std::ptr::null_mut(), document.as_ptr(), toml, 0);
+        assert_eq!(result.status, PredictorError::InvalidHandle { index: 0 }.code());
 
-        match inner.get_predictor(handle.predictor_id) {
-            Some(predictor) => match predictor.predict() {
-                Ok(prediction) => {
-                    // SAFETY: We checked that pointers are not null at the beginning
-                    unsafe {
-                        *out_x = prediction.position.x;
-                        *out_y = prediction.position.y;
-                        *out_confidence = prediction.confidence;
-                    }
-                    1
-                }
-                Err(_) => 0,
-            },
-            None => 0,
-        }
-    }).unwrap_or(0)
-}
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
 
-/// Reset the predictor
-/// 
-/// # Thread Safety
-/// This function is thread-safe. The same handle can be used from multiple
-/// threads, though this is not typically recommended for gesture prediction.
-#[no_mangle]
-pub extern "C" fn swipe_predictor_reset(handle: *mut SwipePredictorHandle) -> i32 {
-    panic::catch_unwind(|| {
-        if handle.is_null() {
-            return 0;
-        }
+    #[test]
+    fn test_display_scale() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
 
-        // SAFETY: We trust the caller to pass a valid handle
-        let handle = unsafe { &*(handle as *const PredictorHandle) };
-        
-        let mut inner = match handle.context.lock() {
-            Ok(guard) => guard,
-            Err(_) => return 0, // Poisoned mutex
+        // The same swipe in points, then at 3x in pixels
+        for i in 0..8 {
+            swipe_predictor_add_point(handle, i as f64 * 10.0, 0.0, i as f64 * 16.0);
+        }
+        let in_points = swipe_predictor_predict(handle);
+        let dismissal_in_points = swipe_predictor_predict_dismissal(handle, 100.0);
+
+        // Buffered samples would mix scales
+        let buffered = PredictorError::InvalidConfiguration {
+            field: "display_scale".into(),
+            value: 3.0,
+            reason: "".into(),
         };
-
-        match inner.get_predictor_mut(handle.predictor_id) {
-            Some(predictor) => {
-                predictor.reset();
-                1
-            }
-            None => 0,
+        assert_eq!(swipe_predictor_context_set_display_scale(ctx, 3.0), buffered.code());
+        assert_eq!(swipe_predictor_context_get_display_scale(ctx), 1.0);
+        assert_eq!(swipe_predictor_context_set_display_scale(ctx, 1.0), 0);
+
+        assert_eq!(swipe_predictor_reset(handle), 1);
+        assert_eq!(swipe_predictor_context_set_display_scale(ctx, 3.0), 0);
+        assert_eq!(swipe_predictor_context_get_display_scale(ctx), 3.0);
+        for i in 0..8 {
+            swipe_predictor_add_point(handle, i as f64 * 30.0, 0.0, i as f64 * 16.0);
         }
-    }).unwrap_or(0)
-}
+        let in_pixels = swipe_predictor_predict(handle);
+        assert_eq!(in_pixels.status, 0);
+        assert!((in_pixels.x - in_points.x * 3.0).abs() < 1e-9);
+        assert_eq!(in_pixels.confidence, in_points.confidence);
+
+        let dismissal_in_pixels = swipe_predictor_predict_dismissal(handle, 300.0);
+        assert_eq!(dismissal_in_pixels.will_dismiss, dismissal_in_points.will_dismiss);
+        assert!((dismissal_in_pixels.probability - dismissal_in_points.probability).abs() < 1e-9);
+        assert!(
+            (dismissal_in_pixels.projected_distance - dismissal_in_points.projected_distance * 3.0).abs()
+                < 1e-9
+        );
 
-/// Detect if the gesture appears to be cancelled
-/// 
-/// # Thread Safety
-/// This function is thread-safe. The same handle can be used from multiple
-/// threads, though this is not typically recommended for gesture prediction.
-#[no_mangle]
-pub extern "C" fn swipe_predictor_detect_cancellation(handle: *mut SwipePredictorHandle) -> i32 {
-    panic::catch_unwind(|| {
-        if handle.is_null() {
-            return 0;
-        }
+        let invalid = DisplayScale::new(0.0).unwrap_err();
+        assert_eq!(swipe_predictor_context_set_display_scale(ctx, 0.0), invalid.code());
+        assert_eq!(swipe_predictor_context_get_display_scale(ctx), 3.0);
+        assert_eq!(swipe_predictor_context_get_display_scale(std::ptr::null_mut()), 1.0);
 
-        // SAFETY: We trust the caller to pass a valid handle
-        let handle = unsafe { &*(handle as *const PredictorHandle) };
-        
-        let inner = match handle.context.lock() {
-            Ok(guard) => guard,
-            Err(_) => return 0, // Poisoned mutex
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
+
+    #[test]
+    fn test_thresholds_converted_to_points() {
+        let ctx = swipe_predictor_context_create_default();
+        assert_eq!(swipe_predictor_context_set_display_scale(ctx, 2.0), 0);
+        let handle = swipe_predictor_create_in_context(ctx);
+
+        let cancellation = SwipePredictorCancellationConfig {
+            reversal_angle_deg: 120.0,
+            stop_speed: 40.0,
+            slow_velocity_ratio: 0.5,
+            min_displacement: 16.0,
+            hysteresis_samples: 2,
+        };
+        assert_eq!(swipe_predictor_set_cancellation_config(handle, cancellation), 0);
+
+        let filter = SwipePredictorInputFilterConfig {
+            max_acceleration: 500_000.0,
+            merge_duplicates: 1,
+            one_euro_enabled: 1,
+            min_cutoff: 1.0,
+            beta: 0.007,
+            derivative_cutoff: 1.0,
         };
+        assert_eq!(swipe_predictor_set_input_filter(handle, &filter), 0);
+
+        let snap_points = [200.0, 0.0];
+        let events = SwipePredictorEventConfig {
+            min_distance: 16.0,
+            confidence_band: 0.25,
+            direction_flip: 1,
+            snap_points: snap_points.as_ptr(),
+            snap_point_count: 1,
+        };
+        assert_eq!(swipe_predictor_events_enable(handle, &events), 0);
+
+        // Thresholds given in pixels at 2x are stored in points
+        let (cancellation, filter, events) = with_predictor_mut(handle, |predictor| {
+            Ok((
+                predictor.cancellation_config(),
+                predictor.input_filter().unwrap(),
+                predictor.event_thresholds().cloned().unwrap(),
+            ))
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(cancellation.stop_speed, 20.0);
+        assert_eq!(cancellation.min_displacement, 8.0);
+        assert_eq!(cancellation.reversal_angle_deg, 120.0);
+        assert_eq!(filter.max_acceleration, Some(250_000.0));
+        assert_eq!(filter.one_euro.unwrap().beta, 0.014);
+        assert_eq!(filter.one_euro.unwrap().min_cutoff, 1.0);
+        assert_eq!(events.min_distance, 8.0);
+        assert_eq!(events.snap_points, [Point2D::new(100.0, 0.0)]);
 
-        match inner.get_predictor(handle.predictor_id) {
-            Some(predictor) => {
-                if predictor.detect_cancellation() {
-                    1
-                } else {
-                    0
-                }
-            }
-            None => 0,
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
+
+    #[test]
+    fn test_predict_dismissal_api() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
+
+        let too_few = PredictorError::InsufficientData { required: 2, actual: 0 };
+        assert_eq!(swipe_predictor_predict_dismissal(handle, 200.0).status, too_few.code());
+
+        for i in 0..8 {
+            swipe_predictor_add_point(handle, i as f64 * 20.0, 0.0, i as f64 * 16.0);
         }
-    }).unwrap_or(0)
-}
+        let dismissal = swipe_predictor_predict_dismissal(handle, 200.0);
+        assert_eq!(dismissal.status, 0);
+        assert_eq!(dismissal.will_dismiss, 1);
+        assert!(dismissal.probability > 0.5);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
+
+    #[test]
+    fn test_add_point_checked_reports_error_codes() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
+
+        assert_eq!(swipe_predictor_add_point_checked(handle, 0.0, 0.0, 10.0), 0);
+
+        let out_of_order = PredictorError::TimestampOutOfOrder { previous: 10.0, current: 5.0 };
+        assert_eq!(swipe_predictor_add_point_checked(handle, 1.0, 0.0, 5.0), out_of_order.code());
+
+        let nan = PredictorError::InvalidCoordinate { x: f64::NAN, y: 0.0 };
+        assert_eq!(swipe_predictor_add_point_checked(handle, f64::NAN, 0.0, 20.0), nan.code());
+
+        assert_eq!(swipe_predictor_add_point(handle, 2.0, 0.0, -1.0), 0);
+        assert_eq!(swipe_predictor_rejected_count(handle), 3);
+
+        let null = PredictorError::InvalidHandle { index: 0 };
+        assert_eq!(
+            swipe_predictor_add_point_checked(std::ptr::null_mut(), 0.0, 0.0, 0.0),
+            null.code()
+        );
+        assert_eq!(swipe_predictor_rejected_count(std::ptr::null_mut()), 0);
+
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
 
     #[test]
     fn test_context_lifecycle() {
@@ -586,17 +2395,101 @@ mod tests {
         let cancel = swipe_predictor_detect_cancellation(handle);
         assert_eq!(cancel, 0); // Should not be cancelled
 
-        // Reverse direction
-        swipe_predictor_add_point(handle, 15.0, 0.0, 30.0);
+        // Reverse direction, sustained past the hysteresis window
+        swipe_predictor_add_point(handle, 10.0, 0.0, 30.0);
+        swipe_predictor_add_point(handle, 5.0, 0.0, 40.0);
 
         let cancel = swipe_predictor_detect_cancellation(handle);
         assert_eq!(cancel, 1); // Should be cancelled
+        assert_eq!(
+            swipe_predictor_cancellation_reason(handle),
+            crate::cancellation::CancellationReason::DirectionReversal as i32
+        );
 
         // Clean up
         swipe_predictor_destroy(handle);
         swipe_predictor_context_destroy(ctx);
     }
 
+    fn predict_x(handle: *mut SwipePredictorHandle) -> f64 {
+        let mut x = 0.0;
+        let mut y = 0.0;
+        let mut conf = 0.0;
+        let result = swipe_predictor_get_prediction(handle, &mut x, &mut y, &mut conf);
+        assert_eq!(result, 1);
+        x
+    }
+
+    fn feed_horizontal_swipe(handle: *mut SwipePredictorHandle) {
+        for i in 0..6 {
+            swipe_predictor_add_point(handle, i as f64 * 20.0, 0.0, i as f64 * 20.0);
+        }
+    }
+
+    #[test]
+    fn test_update_config_applies_to_existing() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
+        feed_horizontal_swipe(handle);
+        let before = predict_x(handle);
+
+        let result = swipe_predictor_context_update_config(ctx, 750.0, 50.0, 30.0, 1);
+        assert_eq!(result, 1);
+
+        // Same handle, same buffered points, new physics
+        let after = predict_x(handle);
+        assert!(after > before, "lower deceleration should travel further");
+
+        // Predictors created later use the new config too
+        let new_handle = swipe_predictor_create_in_context(ctx);
+        feed_horizontal_swipe(new_handle);
+        assert_eq!(predict_x(new_handle), after);
+
+        swipe_predictor_destroy(handle);
+        swipe_predictor_destroy(new_handle);
+        swipe_predictor_context_destroy(ctx);
+    }
+
+    #[test]
+    fn test_update_config_new_predictors_only() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
+        feed_horizontal_swipe(handle);
+        let before = predict_x(handle);
+
+        let result = swipe_predictor_context_update_config(ctx, 750.0, 50.0, 30.0, 0);
+        assert_eq!(result, 1);
+
+        // Existing predictor keeps its config
+        assert_eq!(predict_x(handle), before);
+
+        let new_handle = swipe_predictor_create_in_context(ctx);
+        feed_horizontal_swipe(new_handle);
+        assert!(predict_x(new_handle) > before);
+
+        swipe_predictor_destroy(handle);
+        swipe_predictor_destroy(new_handle);
+        swipe_predictor_context_destroy(ctx);
+    }
+
+    #[test]
+    fn test_update_config_rejects_invalid() {
+        let ctx = swipe_predictor_context_create_default();
+        let handle = swipe_predictor_create_in_context(ctx);
+        feed_horizontal_swipe(handle);
+        let before = predict_x(handle);
+
+        let result = swipe_predictor_context_update_config(ctx, -1.0, 50.0, 30.0, 1);
+        assert_eq!(result, 0);
+        assert_eq!(predict_x(handle), before);
+
+        let result = swipe_predictor_context_update_config(std::ptr::null_mut(), 750.0, 50.0, 30.0, 1);
+        assert_eq!(result, 0);
+
+        swipe_predictor_destroy(handle);
+        swipe_predictor_context_destroy(ctx);
+    }
+
     #[test]
     fn test_use_after_context_destroyed() {
         // This test verifies that handles remain valid even after the context is destroyed
@@ -0,0 +1,484 @@
+use core::f64::consts::PI;
+
+use crate::error::{PredictorError, Result};
+use crate::types::{Point2D, Timestamp, TouchPoint};
+
+/// Spikes dropped in a row before the filter accepts the new position as real,
+/// so a genuine jump (e.g. another finger taking over) isn't rejected forever
+const MAX_CONSECUTIVE_SPIKES: u32 = 2;
+
+/// One Euro filter parameters (Casiez et al., CHI 2012).
+///
+/// Low speeds are smoothed at `min_cutoff`; the cutoff rises with speed by
+/// `beta`, so fast motion is followed with little lag.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedOneEuroConfig"))]
+pub struct OneEuroConfig {
+    /// Cutoff frequency in Hz at rest; lower removes more jitter
+    pub min_cutoff: f64,
+    /// How much the cutoff rises per point/s of speed; higher reduces lag
+    pub beta: f64,
+    /// Cutoff frequency in Hz for the speed estimate itself
+    pub derivative_cutoff: f64,
+}
+
+impl Default for OneEuroConfig {
+    fn default() -> Self {
+        Self {
+            min_cutoff: 1.0,
+            beta: 0.007,
+            derivative_cutoff: 1.0,
+        }
+    }
+}
+
+/// A deserialized `OneEuroConfig` that hasn't been validated yet
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedOneEuroConfig {
+    min_cutoff: f64,
+    beta: f64,
+    derivative_cutoff: f64,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedOneEuroConfig> for OneEuroConfig {
+    type Error = PredictorError;
+
+    fn try_from(config: UncheckedOneEuroConfig) -> Result<Self> {
+        let config = Self {
+            min_cutoff: config.min_cutoff,
+            beta: config.beta,
+            derivative_cutoff: config.derivative_cutoff,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl OneEuroConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !self.min_cutoff.is_finite() || self.min_cutoff <= 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "min_cutoff".into(),
+                value: self.min_cutoff,
+                reason: "must be positive and finite".into(),
+            });
+        }
+
+        if !self.beta.is_finite() || self.beta < 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "beta".into(),
+                value: self.beta,
+                reason: "must be non-negative and finite".into(),
+            });
+        }
+
+        if !self.derivative_cutoff.is_finite() || self.derivative_cutoff <= 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "derivative_cutoff".into(),
+                value: self.derivative_cutoff,
+                reason: "must be positive and finite".into(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Input filter stage applied to samples before they enter the touch buffer
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedInputFilterConfig"))]
+pub struct InputFilterConfig {
+    /// Acceleration in points/s² above which a sample is treated as a glitch and
+    /// dropped; `None` disables spike rejection
+    pub max_acceleration: Option<f64>,
+    /// Fold a sample with the same timestamp as the previous one into it
+    /// instead of buffering both
+    pub merge_duplicates: bool,
+    /// Smooth positions with a One Euro filter; `None` disables smoothing
+    pub one_euro: Option<OneEuroConfig>,
+}
+
+impl Default for InputFilterConfig {
+    fn default() -> Self {
+        Self {
+            // Going from rest to 4000 points/s within one 16ms frame
+            max_acceleration: Some(250_000.0),
+            merge_duplicates: true,
+            one_euro: None,
+        }
+    }
+}
+
+/// A deserialized `InputFilterConfig` that hasn't been validated yet
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedInputFilterConfig {
+    max_acceleration: Option<f64>,
+    merge_duplicates: bool,
+    one_euro: Option<OneEuroConfig>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedInputFilterConfig> for InputFilterConfig {
+    type Error = PredictorError;
+
+    fn try_from(config: UncheckedInputFilterConfig) -> Result<Self> {
+        let config = Self {
+            max_acceleration: config.max_acceleration,
+            merge_duplicates: config.merge_duplicates,
+            one_euro: config.one_euro,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl InputFilterConfig {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(max_acceleration) = self.max_acceleration {
+            if !max_acceleration.is_finite() || max_acceleration <= 0.0 {
+                return Err(PredictorError::InvalidConfiguration {
+                    field: "max_acceleration".into(),
+                    value: max_acceleration,
+                    reason: "must be positive and finite".into(),
+                });
+            }
+        }
+
+        if let Some(one_euro) = self.one_euro {
+            one_euro.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Samples the input filter has dropped or folded since it was configured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FilterStats {
+    pub spikes_rejected: u64,
+    /// Duplicates folded into the previous sample, whether by
+    /// `merge_duplicates` or by the predictor's `DuplicateTimestampPolicy`
+    pub duplicates_merged: u64,
+}
+
+/// What to do with a sample after filtering
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Filtered {
+    Append(TouchPoint),
+    /// Replace the most recently buffered sample
+    ReplaceLast(TouchPoint),
+    Dropped,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OneEuroState {
+    position: Point2D,
+    /// Smoothed velocity in points/s
+    derivative: Point2D,
+    timestamp: Timestamp,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct InputFilter {
+    config: InputFilterConfig,
+    stats: FilterStats,
+    /// Last two raw samples that were let through, oldest first
+    previous: Option<TouchPoint>,
+    last: Option<TouchPoint>,
+    consecutive_spikes: u32,
+    /// Smoother state after and before the last sample, so a merged
+    /// duplicate can be re-filtered in its place
+    smoothed: Option<OneEuroState>,
+    smoothed_before_last: Option<OneEuroState>,
+}
+
+impl InputFilter {
+    pub(crate) fn new(config: InputFilterConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            stats: FilterStats::default(),
+            previous: None,
+            last: None,
+            consecutive_spikes: 0,
+            smoothed: None,
+            smoothed_before_last: None,
+        })
+    }
+
+    pub(crate) fn config(&self) -> InputFilterConfig {
+        self.config
+    }
+
+    pub(crate) fn stats(&self) -> FilterStats {
+        self.stats
+    }
+
+    pub(crate) fn process(&mut self, point: TouchPoint) -> Filtered {
+        if self.config.merge_duplicates
+            && self.last.is_some_and(|last| last.timestamp == point.timestamp)
+        {
+            return Filtered::ReplaceLast(self.merge(point));
+        }
+        self.append(point)
+    }
+
+    /// Filter a sample that is buffered after the last one even if it shares
+    /// its timestamp, bypassing `merge_duplicates`
+    pub(crate) fn append(&mut self, point: TouchPoint) -> Filtered {
+        if self.is_spike(point) {
+            if self.consecutive_spikes < MAX_CONSECUTIVE_SPIKES {
+                self.consecutive_spikes += 1;
+                self.stats.spikes_rejected += 1;
+                return Filtered::Dropped;
+            }
+            // The jump persisted, so treat it as real and measure from here on
+            self.last = None;
+        }
+
+        self.consecutive_spikes = 0;
+        self.previous = self.last;
+        self.last = Some(point);
+        self.smoothed_before_last = self.smoothed;
+        self.smoothed = self.smooth(self.smoothed, point);
+        Filtered::Append(self.output(point))
+    }
+
+    /// Filter a duplicate that replaces the last sample let through.
+    /// Merged duplicates are never treated as spikes.
+    pub(crate) fn merge(&mut self, point: TouchPoint) -> TouchPoint {
+        self.stats.duplicates_merged += 1;
+        self.last = Some(point);
+        self.smoothed = self.smooth(self.smoothed_before_last, point);
+        self.output(point)
+    }
+
+    /// Forget the current gesture, keeping the stats
+    pub(crate) fn clear(&mut self) {
+        self.previous = None;
+        self.last = None;
+        self.consecutive_spikes = 0;
+        self.smoothed = None;
+        self.smoothed_before_last = None;
+    }
+
+    fn is_spike(&self, point: TouchPoint) -> bool {
+        let (Some(max_acceleration), Some(previous), Some(last)) =
+            (self.config.max_acceleration, self.previous, self.last)
+        else {
+            return false;
+        };
+
+        let dt_before = (last.timestamp - previous.timestamp) / 1000.0;
+        let dt = (point.timestamp - last.timestamp) / 1000.0;
+        if dt_before <= 0.0 || dt <= 0.0 {
+            return false;
+        }
+
+        let velocity_before = (last.position - previous.position) / dt_before;
+        let velocity = (point.position - last.position) / dt;
+        (velocity - velocity_before).magnitude() / dt > max_acceleration
+    }
+
+    fn smooth(&self, state: Option<OneEuroState>, point: TouchPoint) -> Option<OneEuroState> {
+        let config = self.config.one_euro?;
+        let Some(state) = state else {
+            return Some(OneEuroState {
+                position: point.position,
+                derivative: Point2D::new(0.0, 0.0),
+                timestamp: point.timestamp,
+            });
+        };
+
+        let dt = (point.timestamp - state.timestamp) / 1000.0;
+        if dt <= 0.0 {
+            // No time has passed to smooth over
+            return Some(OneEuroState {
+                position: point.position,
+                ..state
+            });
+        }
+
+        let raw_derivative = (point.position - state.position) / dt;
+        let derivative = lerp(
+            state.derivative,
+            raw_derivative,
+            smoothing_factor(config.derivative_cutoff, dt),
+        );
+        let cutoff = config.min_cutoff + config.beta * derivative.magnitude();
+
+        Some(OneEuroState {
+            position: lerp(state.position, point.position, smoothing_factor(cutoff, dt)),
+            derivative,
+            timestamp: point.timestamp,
+        })
+    }
+
+    fn output(&self, point: TouchPoint) -> TouchPoint {
+        match self.smoothed {
+            Some(state) => TouchPoint {
+                position: state.position,
+                ..point
+            },
+            None => point,
+        }
+    }
+}
+
+/// Exponential smoothing factor for a low-pass filter at `cutoff` Hz
+fn smoothing_factor(cutoff: f64, dt: f64) -> f64 {
+    let tau = 1.0 / (2.0 * PI * cutoff);
+    1.0 / (1.0 + tau / dt)
+}
+
+fn lerp(from: Point2D, to: Point2D, t: f64) -> Point2D {
+    from + (to - from) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(x: f64, timestamp_ms: f64) -> TouchPoint {
+        TouchPoint::new(x, 0.0, timestamp_ms).unwrap()
+    }
+
+    fn appended_x(filtered: Filtered) -> Option<f64> {
+        match filtered {
+            Filtered::Append(point) => Some(point.position.x),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_spike_rejection() {
+        let mut filter = InputFilter::new(InputFilterConfig::default()).unwrap();
+
+        for i in 0..4 {
+            assert!(appended_x(filter.process(sample(i as f64 * 16.0, i as f64 * 16.0))).is_some());
+        }
+        // A 500px glitch in one frame
+        assert!(matches!(filter.process(sample(548.0, 64.0)), Filtered::Dropped));
+        assert!(appended_x(filter.process(sample(64.0, 64.0))).is_some());
+        assert_eq!(filter.stats().spikes_rejected, 1);
+
+        // A jump that persists is eventually accepted
+        assert!(matches!(filter.process(sample(1000.0, 80.0)), Filtered::Dropped));
+        assert!(matches!(filter.process(sample(1016.0, 96.0)), Filtered::Dropped));
+        assert_eq!(appended_x(filter.process(sample(1032.0, 112.0))), Some(1032.0));
+        assert_eq!(appended_x(filter.process(sample(1048.0, 128.0))), Some(1048.0));
+        assert_eq!(filter.stats().spikes_rejected, 3);
+    }
+
+    #[test]
+    fn test_duplicate_merging() {
+        let mut filter = InputFilter::new(InputFilterConfig::default()).unwrap();
+
+        filter.process(sample(0.0, 0.0));
+        filter.process(sample(10.0, 16.0));
+        match filter.process(sample(12.0, 16.0)) {
+            Filtered::ReplaceLast(point) => assert_eq!(point.position.x, 12.0),
+            other => panic!("expected a merge, got {other:?}"),
+        }
+        assert_eq!(filter.stats().duplicates_merged, 1);
+
+        let config = InputFilterConfig {
+            merge_duplicates: false,
+            ..Default::default()
+        };
+        let mut filter = InputFilter::new(config).unwrap();
+        filter.process(sample(0.0, 0.0));
+        assert!(appended_x(filter.process(sample(2.0, 0.0))).is_some());
+    }
+
+    #[test]
+    fn test_merge_refilters() {
+        let config = InputFilterConfig {
+            one_euro: Some(OneEuroConfig::default()),
+            ..Default::default()
+        };
+        let mut filter = InputFilter::new(config).unwrap();
+        filter.process(sample(0.0, 0.0));
+        filter.process(sample(10.0, 16.0));
+
+        // Same result as if the duplicate had arrived instead
+        let merged = filter.merge(sample(12.0, 16.0));
+        let mut fresh = InputFilter::new(config).unwrap();
+        fresh.process(sample(0.0, 0.0));
+        let Filtered::Append(expected) = fresh.process(sample(12.0, 16.0)) else {
+            panic!("expected an append");
+        };
+        assert_eq!(merged.position, expected.position);
+    }
+
+    #[test]
+    fn test_one_euro_smooths_jitter() {
+        let config = InputFilterConfig {
+            max_acceleration: None,
+            one_euro: Some(OneEuroConfig::default()),
+            ..Default::default()
+        };
+        let mut filter = InputFilter::new(config).unwrap();
+
+        // A stationary finger jittering by ±2px
+        let mut max_offset: f64 = 0.0;
+        for i in 0..20 {
+            let jitter = match i {
+                0 => 0.0,
+                _ if i % 2 == 0 => 2.0,
+                _ => -2.0,
+            };
+            let x = appended_x(filter.process(sample(100.0 + jitter, i as f64 * 8.0))).unwrap();
+            max_offset = max_offset.max((x - 100.0).abs());
+        }
+        assert!(max_offset < 1.0);
+
+        filter.clear();
+        assert_eq!(appended_x(filter.process(sample(500.0, 200.0))), Some(500.0));
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(InputFilterConfig::default().validate().is_ok());
+
+        let invalid = InputFilterConfig {
+            max_acceleration: Some(0.0),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+
+        let invalid = InputFilterConfig {
+            one_euro: Some(OneEuroConfig {
+                min_cutoff: -1.0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_validates() {
+        let config: InputFilterConfig = serde_json::from_str(
+            r#"{"max_acceleration":null,"merge_duplicates":false,"one_euro":{"min_cutoff":2.0,"beta":0.01,"derivative_cutoff":1.0}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.one_euro.unwrap().min_cutoff, 2.0);
+
+        let invalid = serde_json::from_str::<InputFilterConfig>(
+            r#"{"max_acceleration":null,"merge_duplicates":false,"one_euro":{"min_cutoff":0.0,"beta":0.01,"derivative_cutoff":1.0}}"#,
+        );
+        assert!(invalid.unwrap_err().to_string().contains("min_cutoff"));
+        assert!(serde_json::from_str::<InputFilterConfig>(
+            r#"{"max_acceleration":-1.0,"merge_duplicates":true,"one_euro":null}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use crate::error::{PredictorError, Result};
+
+/// Number of low bits of a raw ID used for the slot index
+const INDEX_BITS: u32 = 14;
+
+/// Maximum number of slots a map can hold (16384)
+pub(crate) const MAX_SLOTS: u32 = 1 << INDEX_BITS;
+
+/// Largest generation that still keeps raw IDs positive in an `i32`
+const MAX_GENERATION: u32 = (1 << (31 - INDEX_BITS)) - 1;
+
+/// Generational handle: a slot index plus the generation the slot had when
+/// the handle was issued. Reusing a slot bumps its generation, so an old
+/// handle to the same slot is detected as stale instead of silently
+/// addressing the new occupant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct HandleId {
+    index: u32,
+    generation: u32,
+}
+
+impl HandleId {
+    /// Pack into a positive `i32` for bridges that hand IDs to JS.
+    /// Generations start at 1, so a packed ID is never 0 or negative.
+    pub(crate) fn to_raw(self) -> i32 {
+        ((self.generation << INDEX_BITS) | self.index) as i32
+    }
+
+    pub(crate) fn from_raw(raw: i32) -> Option<Self> {
+        if raw <= 0 {
+            return None;
+        }
+
+        let raw = raw as u32;
+        Some(Self {
+            index: raw & (MAX_SLOTS - 1),
+            generation: raw >> INDEX_BITS,
+        })
+    }
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// Slot map keyed by generational handles
+pub(crate) struct HandleMap<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<T> HandleMap<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Store a value, returning `None` once every slot is in use or retired
+    pub(crate) fn insert(&mut self, value: T) -> Option<HandleId> {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                if self.slots.len() as u32 >= MAX_SLOTS {
+                    return None;
+                }
+                self.slots.push(Slot {
+                    generation: 1,
+                    value: None,
+                });
+                self.slots.len() as u32 - 1
+            }
+        };
+
+        let slot = &mut self.slots[index as usize];
+        slot.value = Some(value);
+        self.len += 1;
+
+        Some(HandleId {
+            index,
+            generation: slot.generation,
+        })
+    }
+
+    pub(crate) fn get(&self, id: HandleId) -> Result<&T> {
+        let slot = self.slot(id)?;
+        slot.value.as_ref().ok_or(PredictorError::StaleHandle {
+            index: id.index,
+            generation: id.generation,
+        })
+    }
+
+    pub(crate) fn get_mut(&mut self, id: HandleId) -> Result<&mut T> {
+        self.slot(id)?;
+        let slot = &mut self.slots[id.index as usize];
+        slot.value.as_mut().ok_or(PredictorError::StaleHandle {
+            index: id.index,
+            generation: id.generation,
+        })
+    }
+
+    pub(crate) fn remove(&mut self, id: HandleId) -> Result<T> {
+        self.get(id)?;
+        let value = self.release(id.index);
+        value.ok_or(PredictorError::StaleHandle {
+            index: id.index,
+            generation: id.generation,
+        })
+    }
+
+    /// Remove every value, invalidating all outstanding handles
+    pub(crate) fn drain(&mut self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+        for index in 0..self.slots.len() as u32 {
+            if self.slots[index as usize].value.is_some() {
+                values.extend(self.release(index));
+            }
+        }
+        values
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (HandleId, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.value.as_ref().map(|value| {
+                let id = HandleId {
+                    index: index as u32,
+                    generation: slot.generation,
+                };
+                (id, value)
+            })
+        })
+    }
+
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Validate that `id` was issued by this map, distinguishing never-issued
+    /// handles from ones whose slot has since been released or reused
+    fn slot(&self, id: HandleId) -> Result<&Slot<T>> {
+        let slot = self
+            .slots
+            .get(id.index as usize)
+            .filter(|slot| id.generation != 0 && id.generation <= slot.generation)
+            .ok_or(PredictorError::InvalidHandle { index: id.index })?;
+
+        if slot.generation != id.generation {
+            return Err(PredictorError::StaleHandle {
+                index: id.index,
+                generation: id.generation,
+            });
+        }
+
+        Ok(slot)
+    }
+
+    fn release(&mut self, index: u32) -> Option<T> {
+        let slot = &mut self.slots[index as usize];
+        let value = slot.value.take();
+        if value.is_some() {
+            self.len -= 1;
+            // Retire a slot whose generation is exhausted rather than wrap it
+            if slot.generation < MAX_GENERATION {
+                slot.generation += 1;
+                self.free.push(index);
+            }
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut map = HandleMap::new();
+        let a = map.insert("a").unwrap();
+        let b = map.insert("b").unwrap();
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(*map.get(a).unwrap(), "a");
+        assert_eq!(*map.get(b).unwrap(), "b");
+
+        assert_eq!(map.remove(a).unwrap(), "a");
+        assert_eq!(map.len(), 1);
+        assert!(matches!(map.get(a), Err(PredictorError::StaleHandle { .. })));
+        assert!(matches!(map.remove(a), Err(PredictorError::StaleHandle { .. })));
+    }
+
+    #[test]
+    fn test_reused_slot_is_stale() {
+        let mut map = HandleMap::new();
+        let old = map.insert(1).unwrap();
+        map.remove(old).unwrap();
+
+        // The slot is reused with a new generation
+        let new = map.insert(2).unwrap();
+        assert_ne!(old, new);
+        assert_ne!(old.to_raw(), new.to_raw());
+
+        assert!(matches!(map.get(old), Err(PredictorError::StaleHandle { .. })));
+        assert_eq!(*map.get(new).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_drain_invalidates_handles() {
+        let mut map = HandleMap::new();
+        let ids: Vec<_> = (0..3).map(|i| map.insert(i).unwrap()).collect();
+
+        let mut drained = map.drain();
+        drained.sort();
+        assert_eq!(drained, vec![0, 1, 2]);
+        assert!(map.is_empty());
+
+        for id in ids {
+            assert!(matches!(map.get(id), Err(PredictorError::StaleHandle { .. })));
+        }
+    }
+
+    #[test]
+    fn test_unknown_handle() {
+        let mut map: HandleMap<i32> = HandleMap::new();
+        let id = HandleId::from_raw(HandleId { index: 5, generation: 1 }.to_raw()).unwrap();
+        assert!(matches!(map.get(id), Err(PredictorError::InvalidHandle { index: 5 })));
+
+        // A generation that was never issued for an existing slot is invalid, not stale
+        let real = map.insert(1).unwrap();
+        let future = HandleId {
+            index: real.index,
+            generation: real.generation + 1,
+        };
+        assert!(matches!(map.get(future), Err(PredictorError::InvalidHandle { .. })));
+    }
+
+    #[test]
+    fn test_raw_round_trip() {
+        let id = HandleId {
+            index: MAX_SLOTS - 1,
+            generation: MAX_GENERATION,
+        };
+        let raw = id.to_raw();
+        assert!(raw > 0);
+        assert_eq!(HandleId::from_raw(raw), Some(id));
+
+        assert_eq!(HandleId::from_raw(0), None);
+        assert_eq!(HandleId::from_raw(-1), None);
+    }
+
+    #[test]
+    fn test_exhausted_generation_is_retired() {
+        let mut map = HandleMap::new();
+        let id = map.insert(1).unwrap();
+        map.slots[id.index as usize].generation = MAX_GENERATION;
+        let id = HandleId {
+            index: id.index,
+            generation: MAX_GENERATION,
+        };
+        map.remove(id).unwrap();
+
+        // The slot is not reused, so the old handle stays stale forever
+        let next = map.insert(2).unwrap();
+        assert_ne!(next.index, id.index);
+        assert!(matches!(map.get(id), Err(PredictorError::StaleHandle { .. })));
+    }
+}
@@ -1,51 +1,189 @@
 // iOS Bridge - Maps legacy function names to new context-based API
-use crate::ffi::{SwipePredictorContext, SwipePredictorHandle};
-use std::collections::HashMap;
+use crate::error::{PredictorError, Result};
+use crate::ffi::SwipePredictorHandle;
+use crate::handle::HandleId;
+use crate::physics::PhysicsConfig;
+use crate::registry::BridgeRegistry;
 use std::sync::{Mutex, Once};
 
-// Wrapper to make the raw pointer Send + Sync
-struct ContextPtr(*mut SwipePredictorContext);
-unsafe impl Send for ContextPtr {}
-unsafe impl Sync for ContextPtr {}
-
-// Wrapper for handle pointers to make them Send + Sync
-struct HandlePtr(*mut SwipePredictorHandle);
-unsafe impl Send for HandlePtr {}
-unsafe impl Sync for HandlePtr {}
-
-// Handle storage to map i32 IDs to actual pointers
-struct HandleStorage {
-    context: Option<ContextPtr>,
-    handles: HashMap<i32, HandlePtr>,
-    next_id: i32,
-}
-
-impl HandleStorage {
-    fn new() -> Self {
-        Self {
-            context: None,
-            handles: HashMap::new(),
-            next_id: 1, // Start from 1 so 0/-1 can indicate errors
-        }
-    }
-}
+/// Returned instead of 0 when a predictor or context ID refers to one that has
+/// been removed or was created before the manager was re-initialized
+pub const PREDICTOR_STALE_HANDLE: i32 = -2;
 
 // Use OnceLock for lazy initialization
 use std::sync::OnceLock;
 
-static IOS_STORAGE: OnceLock<Mutex<HandleStorage>> = OnceLock::new();
+static IOS_STORAGE: OnceLock<Mutex<BridgeRegistry>> = OnceLock::new();
 static INIT_ONCE: Once = Once::new();
 
-fn get_storage() -> &'static Mutex<HandleStorage> {
+fn get_storage() -> &'static Mutex<BridgeRegistry> {
     // Initialize panic handler on first access
     INIT_ONCE.call_once(|| {
         crate::ffi::swipe_predictor_init_panic_handler();
     });
-    
-    IOS_STORAGE.get_or_init(|| Mutex::new(HandleStorage::new()))
+
+    IOS_STORAGE.get_or_init(|| Mutex::new(BridgeRegistry::new()))
+}
+
+/// Map a bridge result to the i32 status convention: the value on success,
+/// `PREDICTOR_STALE_HANDLE` for stale IDs and 0 for any other failure
+fn status(result: Result<i32>) -> i32 {
+    match result {
+        Ok(value) => value,
+        Err(PredictorError::StaleHandle { .. }) => PREDICTOR_STALE_HANDLE,
+        Err(_) => 0,
+    }
+}
+
+/// Run `f` with the handle for `predictor_id`
+fn with_handle(predictor_id: i32, f: impl FnOnce(*mut SwipePredictorHandle) -> i32) -> i32 {
+    let id = match HandleId::from_raw(predictor_id) {
+        Some(id) => id,
+        None => return 0,
+    };
+
+    if let Ok(storage) = get_storage().lock() {
+        return status(storage.predictor(id).map(f));
+    }
+    0
+}
+
+/// Create an independent predictor context with its own physics config
+///
+/// Returns the context ID, or -1 on invalid config.
+#[no_mangle]
+pub extern "C" fn create_predictor_context(
+    deceleration_rate: f64,
+    min_velocity_threshold: f64,
+    min_gesture_time_ms: f64,
+) -> i32 {
+    let physics_config = PhysicsConfig {
+        deceleration_rate,
+        min_velocity_threshold,
+        min_gesture_time_ms,
+    };
+
+    if let Ok(mut storage) = get_storage().lock() {
+        if let Ok(id) = storage.create_context(physics_config) {
+            return id.to_raw();
+        }
+    }
+    -1
+}
+
+/// Destroy a context and every predictor created in it
+#[no_mangle]
+pub extern "C" fn destroy_predictor_context(context_id: i32) -> i32 {
+    let id = match HandleId::from_raw(context_id) {
+        Some(id) => id,
+        None => return 0,
+    };
+
+    if let Ok(mut storage) = get_storage().lock() {
+        return status(storage.destroy_context(id).map(|_| 1));
+    }
+    0
+}
+
+/// List live context IDs
+///
+/// Writes up to `capacity` IDs into `out_ids` and returns the total number of
+/// live contexts, which may exceed `capacity`. Pass a null `out_ids` to query the count.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn list_predictor_contexts(out_ids: *mut i32, capacity: i32) -> i32 {
+    if let Ok(storage) = get_storage().lock() {
+        let ids = storage.context_ids();
+
+        if !out_ids.is_null() {
+            let count = ids.len().min(capacity.max(0) as usize);
+            // SAFETY: The caller guarantees `out_ids` points to at least `capacity` i32s
+            let out = unsafe { std::slice::from_raw_parts_mut(out_ids, count) };
+            for (slot, id) in out.iter_mut().zip(&ids) {
+                *slot = id.to_raw();
+            }
+        }
+
+        return ids.len() as i32;
+    }
+    0
+}
+
+/// Update the physics config of a specific context
+///
+/// Pass a non-zero `apply_to_existing` to reconfigure live predictors as well.
+#[no_mangle]
+pub extern "C" fn update_predictor_context(
+    context_id: i32,
+    deceleration_rate: f64,
+    min_velocity_threshold: f64,
+    min_gesture_time_ms: f64,
+    apply_to_existing: i32,
+) -> i32 {
+    let id = match HandleId::from_raw(context_id) {
+        Some(id) => id,
+        None => return 0,
+    };
+
+    let physics_config = PhysicsConfig {
+        deceleration_rate,
+        min_velocity_threshold,
+        min_gesture_time_ms,
+    };
+
+    if let Ok(mut storage) = get_storage().lock() {
+        return status(
+            storage
+                .update_context_config(id, physics_config, apply_to_existing != 0)
+                .map(|_| 1),
+        );
+    }
+    0
+}
+
+/// Set the display density of a specific context, in physical pixels per point
+/// (`UIScreen.scale` when coordinates are in pixels, 1 when they are in points)
+///
+/// Returns 1 on success, 0 for an invalid scale or while a predictor in the
+/// context has samples buffered, or `PREDICTOR_STALE_HANDLE`.
+#[no_mangle]
+pub extern "C" fn set_predictor_context_display_scale(context_id: i32, pixels_per_point: f64) -> i32 {
+    let id = match HandleId::from_raw(context_id) {
+        Some(id) => id,
+        None => return 0,
+    };
+
+    if let Ok(mut storage) = get_storage().lock() {
+        return status(storage.set_display_scale(id, pixels_per_point).map(|_| 1));
+    }
+    0
+}
+
+/// Create a predictor in a specific context
+///
+/// Returns the predictor ID, -1 on failure, or `PREDICTOR_STALE_HANDLE`
+/// if the context has been destroyed.
+#[no_mangle]
+pub extern "C" fn init_predictor_in_context(context_id: i32) -> i32 {
+    let id = match HandleId::from_raw(context_id) {
+        Some(id) => id,
+        None => return -1,
+    };
+
+    if let Ok(mut storage) = get_storage().lock() {
+        return match storage.create_predictor(id) {
+            Ok(predictor_id) => predictor_id.to_raw(),
+            Err(PredictorError::StaleHandle { .. }) => PREDICTOR_STALE_HANDLE,
+            Err(_) => -1,
+        };
+    }
+    -1
 }
 
 /// Initialize the predictor manager with physics config (legacy API)
+///
+/// Replaces the default context; contexts created with
+/// `create_predictor_context` are untouched.
 #[no_mangle]
 pub extern "C" fn init_predictor_manager(
     deceleration_rate: f64,
@@ -53,41 +191,69 @@ pub extern "C" fn init_predictor_manager(
     min_gesture_time_ms: f64,
     _velocity_smoothing_factor: f64, // Unused in new API
 ) {
+    let physics_config = PhysicsConfig {
+        deceleration_rate,
+        min_velocity_threshold,
+        min_gesture_time_ms,
+    };
+
     if let Ok(mut storage) = get_storage().lock() {
-        // Clean up existing context and handles
-        if let Some(ctx_wrapper) = storage.context.take() {
-            // Destroy all existing handles first
-            for (_, handle_ptr) in storage.handles.drain() {
-                crate::ffi::swipe_predictor_destroy(handle_ptr.0);
-            }
-            crate::ffi::swipe_predictor_context_destroy(ctx_wrapper.0);
+        // Predictors in the old default context become stale
+        let _ = storage.reset_default_context(physics_config);
+    }
+}
+
+/// Update the physics config without destroying existing predictors
+///
+/// Pass a non-zero `apply_to_existing` to reconfigure live predictors as well;
+/// otherwise only predictors created afterwards use the new config.
+/// Returns 1 on success, 0 if the manager isn't initialized or the config is invalid.
+#[no_mangle]
+pub extern "C" fn update_predictor_manager(
+    deceleration_rate: f64,
+    min_velocity_threshold: f64,
+    min_gesture_time_ms: f64,
+    apply_to_existing: i32,
+) -> i32 {
+    let physics_config = PhysicsConfig {
+        deceleration_rate,
+        min_velocity_threshold,
+        min_gesture_time_ms,
+    };
+
+    if let Ok(mut storage) = get_storage().lock() {
+        if let Some(id) = storage.default_context() {
+            return status(
+                storage
+                    .update_context_config(id, physics_config, apply_to_existing != 0)
+                    .map(|_| 1),
+            );
         }
-        
-        // Create new context
-        let new_ctx = crate::ffi::swipe_predictor_context_create(
-            deceleration_rate,
-            min_velocity_threshold,
-            min_gesture_time_ms,
-        );
-        
-        if !new_ctx.is_null() {
-            storage.context = Some(ContextPtr(new_ctx));
-            storage.next_id = 1; // Reset ID counter
+    }
+    0
+}
+
+/// Set the display density of the default context, in physical pixels per point
+///
+/// Returns 1 on success, 0 if the manager isn't initialized, the scale is
+/// invalid or a predictor has samples buffered.
+#[no_mangle]
+pub extern "C" fn set_predictor_manager_display_scale(pixels_per_point: f64) -> i32 {
+    if let Ok(mut storage) = get_storage().lock() {
+        if let Some(id) = storage.default_context() {
+            return status(storage.set_display_scale(id, pixels_per_point).map(|_| 1));
         }
     }
+    0
 }
 
 /// Initialize a new predictor (legacy API)
 #[no_mangle]
 pub extern "C" fn init_predictor() -> i32 {
     if let Ok(mut storage) = get_storage().lock() {
-        if let Some(ref ctx_wrapper) = storage.context {
-            let handle = crate::ffi::swipe_predictor_create_in_context(ctx_wrapper.0);
-            if !handle.is_null() {
-                let id = storage.next_id;
-                storage.next_id = storage.next_id.wrapping_add(1);
-                storage.handles.insert(id, HandlePtr(handle));
-                return id;
+        if let Some(id) = storage.default_context() {
+            if let Ok(predictor_id) = storage.create_predictor(id) {
+                return predictor_id.to_raw();
             }
         }
     }
@@ -97,12 +263,9 @@ pub extern "C" fn init_predictor() -> i32 {
 /// Add a touch point (legacy API)
 #[no_mangle]
 pub extern "C" fn add_touch_point(predictor_id: i32, x: f64, y: f64, timestamp: f64) -> i32 {
-    if let Ok(storage) = get_storage().lock() {
-        if let Some(handle_ptr) = storage.handles.get(&predictor_id) {
-            return crate::ffi::swipe_predictor_add_point(handle_ptr.0, x, y, timestamp);
-        }
-    }
-    0
+    with_handle(predictor_id, |handle| {
+        crate::ffi::swipe_predictor_add_point(handle, x, y, timestamp)
+    })
 }
 
 /// Get prediction (legacy API)
@@ -113,60 +276,43 @@ pub extern "C" fn get_prediction(
     out_y: *mut f64,
     out_confidence: *mut f64,
 ) -> i32 {
-    if let Ok(storage) = get_storage().lock() {
-        if let Some(handle_ptr) = storage.handles.get(&predictor_id) {
-            return crate::ffi::swipe_predictor_get_prediction(handle_ptr.0, out_x, out_y, out_confidence);
-        }
-    }
-    0
+    with_handle(predictor_id, |handle| {
+        crate::ffi::swipe_predictor_get_prediction(handle, out_x, out_y, out_confidence)
+    })
 }
 
 /// Reset predictor (legacy API)
 #[no_mangle]
 pub extern "C" fn reset_predictor(predictor_id: i32) -> i32 {
-    if let Ok(storage) = get_storage().lock() {
-        if let Some(handle_ptr) = storage.handles.get(&predictor_id) {
-            return crate::ffi::swipe_predictor_reset(handle_ptr.0);
-        }
-    }
-    0
+    with_handle(predictor_id, |handle| crate::ffi::swipe_predictor_reset(handle))
 }
 
 /// Detect cancellation (legacy API)
 #[no_mangle]
 pub extern "C" fn detect_cancellation(predictor_id: i32) -> i32 {
-    if let Ok(storage) = get_storage().lock() {
-        if let Some(handle_ptr) = storage.handles.get(&predictor_id) {
-            return crate::ffi::swipe_predictor_detect_cancellation(handle_ptr.0);
-        }
-    }
-    0
+    with_handle(predictor_id, |handle| crate::ffi::swipe_predictor_detect_cancellation(handle))
 }
 
 /// Remove predictor (legacy API)
 #[no_mangle]
 pub extern "C" fn remove_predictor(predictor_id: i32) -> i32 {
+    let id = match HandleId::from_raw(predictor_id) {
+        Some(id) => id,
+        None => return 0,
+    };
+
     if let Ok(mut storage) = get_storage().lock() {
-        if let Some(handle_ptr) = storage.handles.remove(&predictor_id) {
-            crate::ffi::swipe_predictor_destroy(handle_ptr.0);
-            return 1;
-        }
+        return status(storage.remove_predictor(id).map(|_| 1));
     }
     0
 }
 
 /// Cleanup function for iOS (call this when the library is unloaded)
+///
+/// Destroys every context, including ones created with `create_predictor_context`.
 #[no_mangle]
 pub extern "C" fn cleanup_ios_bridge() {
     if let Ok(mut storage) = get_storage().lock() {
-        // Destroy all handles
-        for (_, handle_ptr) in storage.handles.drain() {
-            crate::ffi::swipe_predictor_destroy(handle_ptr.0);
-        }
-        
-        // Destroy context
-        if let Some(ctx_wrapper) = storage.context.take() {
-            crate::ffi::swipe_predictor_context_destroy(ctx_wrapper.0);
-        }
+        storage.clear();
     }
-}
\ No newline at end of file
+}
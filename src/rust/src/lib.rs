@@ -1,24 +1,77 @@
+// Without `std` the prediction core builds for bare-metal targets on `alloc`
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("building without `std` requires the `libm` feature for float math");
+
 // Core modules
+pub mod batch;
+pub mod buffer;
+pub mod cancellation;
+pub mod config;
 pub mod error;
+pub mod events;
+pub mod filter;
+mod math;
 pub mod physics;
 pub mod predictor;
+pub mod resample;
 pub mod types;
 
+// Lock-free handoff between threads
+#[cfg(feature = "std")]
+pub mod spsc;
+
 // FFI modules (only compiled when needed)
 #[cfg(feature = "ffi")]
 pub mod ffi;
 
+// ID bookkeeping for the mobile bridges
+#[cfg(feature = "ffi")]
+#[cfg_attr(not(any(target_os = "android", target_os = "ios")), allow(dead_code))]
+mod handle;
+
+#[cfg(feature = "ffi")]
+#[cfg_attr(not(any(target_os = "android", target_os = "ios")), allow(dead_code))]
+mod registry;
+
 #[cfg(all(feature = "ffi", target_os = "android"))]
 pub mod android;
 
 #[cfg(all(feature = "ffi", target_os = "ios"))]
 pub mod ios_bridge;
 
+// JavaScript bindings for the browser and Electron
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+// Python module for offline analysis
+#[cfg(feature = "python")]
+pub mod python;
+
 // Re-export commonly used types
+#[cfg(feature = "rayon")]
+pub use batch::par_predict_batch;
+pub use batch::{predict_batch, BatchPredictions, GestureBatch};
+pub use buffer::{RingBuffer, SampleBuffer};
+pub use cancellation::{CancellationConfig, CancellationReason};
+pub use config::{ConfidenceConfig, EstimatorConfig, PredictorConfig, Preset};
+#[cfg(feature = "config")]
+pub use config::{ConfigError, ConfigFormat};
 pub use error::{PredictorError, Result};
+pub use events::{EventThresholds, PredictionChanges, PredictionEvent};
+pub use filter::{FilterStats, InputFilterConfig, OneEuroConfig};
 pub use physics::PhysicsConfig;
-pub use predictor::GesturePredictor;
-pub use types::{Point2D, Prediction, Timestamp, Velocity2D};
+pub use predictor::{FixedGesturePredictor, GesturePredictor};
+pub use resample::{Interpolation, ResampleConfig};
+#[cfg(feature = "std")]
+pub use spsc::{PredictionReader, PredictionSnapshot, SampleWriter};
+pub use types::{
+    DismissalPrediction, DisplayScale, DuplicateTimestampPolicy, GestureState, Point2D, Prediction,
+    Timestamp, Transform2D, Velocity2D,
+};
 
 // Re-export FFI functions at the crate root so they're available for linking
 #[cfg(feature = "ffi")]
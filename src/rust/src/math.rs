@@ -0,0 +1,59 @@
+//! Float functions that `core` doesn't provide, from `std` when it's
+//! available and from `libm` otherwise (the `libm` feature, which `no_std`
+//! builds must enable).
+
+#[cfg(feature = "std")]
+mod imp {
+    pub fn sqrt(x: f64) -> f64 {
+        x.sqrt()
+    }
+
+    pub fn powf(x: f64, n: f64) -> f64 {
+        x.powf(n)
+    }
+
+    pub fn exp(x: f64) -> f64 {
+        x.exp()
+    }
+
+    pub fn cos(x: f64) -> f64 {
+        x.cos()
+    }
+
+    pub fn sin_cos(x: f64) -> (f64, f64) {
+        x.sin_cos()
+    }
+
+    pub fn ceil(x: f64) -> f64 {
+        x.ceil()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod imp {
+    pub fn sqrt(x: f64) -> f64 {
+        libm::sqrt(x)
+    }
+
+    pub fn powf(x: f64, n: f64) -> f64 {
+        libm::pow(x, n)
+    }
+
+    pub fn exp(x: f64) -> f64 {
+        libm::exp(x)
+    }
+
+    pub fn cos(x: f64) -> f64 {
+        libm::cos(x)
+    }
+
+    pub fn sin_cos(x: f64) -> (f64, f64) {
+        libm::sincos(x)
+    }
+
+    pub fn ceil(x: f64) -> f64 {
+        libm::ceil(x)
+    }
+}
+
+pub(crate) use imp::*;
@@ -1,10 +1,17 @@
 use crate::error::{PredictorError, Result};
+use crate::math;
 
+/// Fling physics.
+///
+/// Distances are in density-independent points (see `DisplayScale`), which
+/// are pixels at a display scale of 1.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedPhysicsConfig"))]
 pub struct PhysicsConfig {
-    /// Deceleration rate in pixels/second²
+    /// Deceleration rate in points/second²
     pub deceleration_rate: f64,
-    /// Minimum velocity threshold in pixels/second
+    /// Minimum velocity threshold in points/second
     pub min_velocity_threshold: f64,
     /// Minimum gesture time in milliseconds
     pub min_gesture_time_ms: f64,
@@ -13,13 +20,35 @@ pub struct PhysicsConfig {
 impl Default for PhysicsConfig {
     fn default() -> Self {
         Self {
-            deceleration_rate: 1500.0,      // pixels/second²
-            min_velocity_threshold: 50.0,   // pixels/second
+            deceleration_rate: 1500.0,      // points/second²
+            min_velocity_threshold: 50.0,   // points/second
             min_gesture_time_ms: 30.0,      // milliseconds
         }
     }
 }
 
+/// A deserialized `PhysicsConfig` that hasn't been validated yet
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedPhysicsConfig {
+    deceleration_rate: f64,
+    min_velocity_threshold: f64,
+    min_gesture_time_ms: f64,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedPhysicsConfig> for PhysicsConfig {
+    type Error = PredictorError;
+
+    fn try_from(config: UncheckedPhysicsConfig) -> Result<Self> {
+        Self::new(
+            config.deceleration_rate,
+            config.min_velocity_threshold,
+            config.min_gesture_time_ms,
+        )
+    }
+}
+
 impl PhysicsConfig {
     pub fn new(
         deceleration_rate: f64,
@@ -38,49 +67,49 @@ impl PhysicsConfig {
     pub fn validate(&self) -> Result<()> {
         if self.deceleration_rate <= 0.0 {
             return Err(PredictorError::InvalidConfiguration {
-                field: "deceleration_rate",
+                field: "deceleration_rate".into(),
                 value: self.deceleration_rate,
-                reason: "must be positive",
+                reason: "must be positive".into(),
             });
         }
         
         if !self.deceleration_rate.is_finite() {
             return Err(PredictorError::InvalidConfiguration {
-                field: "deceleration_rate",
+                field: "deceleration_rate".into(),
                 value: self.deceleration_rate,
-                reason: "must be finite",
+                reason: "must be finite".into(),
             });
         }
         
         if self.min_velocity_threshold < 0.0 {
             return Err(PredictorError::InvalidConfiguration {
-                field: "min_velocity_threshold",
+                field: "min_velocity_threshold".into(),
                 value: self.min_velocity_threshold,
-                reason: "cannot be negative",
+                reason: "cannot be negative".into(),
             });
         }
         
         if !self.min_velocity_threshold.is_finite() {
             return Err(PredictorError::InvalidConfiguration {
-                field: "min_velocity_threshold",
+                field: "min_velocity_threshold".into(),
                 value: self.min_velocity_threshold,
-                reason: "must be finite",
+                reason: "must be finite".into(),
             });
         }
         
         if self.min_gesture_time_ms < 0.0 {
             return Err(PredictorError::InvalidConfiguration {
-                field: "min_gesture_time_ms",
+                field: "min_gesture_time_ms".into(),
                 value: self.min_gesture_time_ms,
-                reason: "cannot be negative",
+                reason: "cannot be negative".into(),
             });
         }
         
         if !self.min_gesture_time_ms.is_finite() {
             return Err(PredictorError::InvalidConfiguration {
-                field: "min_gesture_time_ms",
+                field: "min_gesture_time_ms".into(),
                 value: self.min_gesture_time_ms,
-                reason: "must be finite",
+                reason: "must be finite".into(),
             });
         }
         
@@ -94,7 +123,7 @@ impl PhysicsConfig {
         velocity_x: f64,
         velocity_y: f64,
     ) -> Result<(f64, f64, f64)> {
-        let speed = (velocity_x * velocity_x + velocity_y * velocity_y).sqrt();
+        let speed = math::sqrt(velocity_x * velocity_x + velocity_y * velocity_y);
         
         if speed < self.min_velocity_threshold {
             return Err(PredictorError::VelocityTooLow {
@@ -141,7 +170,7 @@ mod tests {
         };
         assert!(matches!(
             config.validate(),
-            Err(PredictorError::InvalidConfiguration { field: "deceleration_rate", .. })
+            Err(PredictorError::InvalidConfiguration { field, .. }) if field == "deceleration_rate"
         ));
 
         // Invalid velocity threshold
@@ -151,7 +180,7 @@ mod tests {
         };
         assert!(matches!(
             config.validate(),
-            Err(PredictorError::InvalidConfiguration { field: "min_velocity_threshold", .. })
+            Err(PredictorError::InvalidConfiguration { field, .. }) if field == "min_velocity_threshold"
         ));
 
         // Infinite value
@@ -161,7 +190,7 @@ mod tests {
         };
         assert!(matches!(
             config.validate(),
-            Err(PredictorError::InvalidConfiguration { field: "deceleration_rate", .. })
+            Err(PredictorError::InvalidConfiguration { field, .. }) if field == "deceleration_rate"
         ));
     }
 
@@ -189,4 +218,23 @@ mod tests {
         let result = config.calculate_stopping_distance(10.0, 10.0);
         assert!(matches!(result, Err(PredictorError::VelocityTooLow { .. })));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_validates() {
+        let config: PhysicsConfig = serde_json::from_str(
+            r#"{"deceleration_rate": 2000.0, "min_velocity_threshold": 40.0, "min_gesture_time_ms": 20.0}"#,
+        )
+        .unwrap();
+        assert_eq!(config.deceleration_rate, 2000.0);
+
+        let json = serde_json::to_string(&config).unwrap();
+        let round_trip: PhysicsConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip.min_gesture_time_ms, 20.0);
+
+        let invalid = serde_json::from_str::<PhysicsConfig>(
+            r#"{"deceleration_rate": -1.0, "min_velocity_threshold": 40.0, "min_gesture_time_ms": 20.0}"#,
+        );
+        assert!(invalid.unwrap_err().to_string().contains("deceleration_rate"));
+    }
 }
\ No newline at end of file
@@ -1,35 +1,70 @@
-use std::collections::VecDeque;
+use alloc::collections::VecDeque;
 
+use crate::buffer::{RingBuffer, SampleBuffer};
+use crate::cancellation::{CancellationConfig, CancellationLatch, CancellationReason};
+use crate::config::{ConfidenceConfig, EstimatorConfig, PredictorConfig};
 use crate::error::{PredictorError, Result};
+use crate::events::{EventThresholds, PredictionEvent, PredictionEvents};
+use crate::filter::{FilterStats, Filtered, InputFilter, InputFilterConfig};
+use crate::math;
 use crate::physics::PhysicsConfig;
-use crate::types::{Point2D, Prediction, Timestamp, TouchPoint, Velocity2D};
+use crate::resample::{resample_into, ResampleConfig};
+use crate::types::{
+    DismissalPrediction, DuplicateTimestampPolicy, GestureState, Point2D, Prediction, Timestamp,
+    TouchPoint, Transform2D, Velocity2D,
+};
 
 /// Minimum number of touch points needed to calculate velocity
-const MIN_BUFFER_SIZE: usize = 2;
+pub(crate) const MIN_BUFFER_SIZE: usize = 2;
 
 /// Maximum number of touch points to keep in memory to prevent unbounded growth
-const MAX_BUFFER_SIZE: usize = 100;
+pub(crate) const MAX_BUFFER_SIZE: usize = 100;
 
 /// Default buffer size that balances memory usage with prediction accuracy
-const DEFAULT_BUFFER_SIZE: usize = 10;
-
-/// Factor by which speed must decrease between samples to be considered decelerating
-/// 0.9 = speed must be less than 90% of previous speed (10% decrease required)
-const DECELERATION_FACTOR: f64 = 0.9;
-
-/// Speed in pixels/second that represents maximum confidence
-/// Based on typical fast swipe speeds on mobile devices
-const SPEED_CONFIDENCE_SCALE: f64 = 500.0;
-
-/// Duration in milliseconds above minimum that represents maximum confidence
-/// 100ms above minimum gives full duration confidence
-const DURATION_CONFIDENCE_SCALE: f64 = 100.0;
+pub(crate) const DEFAULT_BUFFER_SIZE: usize = 10;
+
+/// Fraction of the dismissal threshold over which the dismissal probability
+/// ramps from ~27% to ~73% around the threshold
+const DISMISSAL_SOFTNESS_RATIO: f64 = 0.2;
+
+/// Samples received so far at the timestamp of the latest buffered one
+#[derive(Debug, Clone, Copy)]
+struct Burst {
+    /// Timestamp of the buffered sample before the burst, if any
+    previous: Option<Timestamp>,
+    /// Sum of the raw positions, for `DuplicateTimestampPolicy::Average`
+    position_sum: Point2D,
+    count: u32,
+}
 
-pub struct GesturePredictor {
-    touch_buffer: VecDeque<TouchPoint>,
+/// Predicts where a swipe comes to rest from its touch samples.
+///
+/// Samples are kept in a `VecDeque` sized at creation by default; see
+/// `FixedGesturePredictor` for an inline buffer that never allocates.
+pub struct GesturePredictor<B = VecDeque<TouchPoint>> {
+    touch_buffer: B,
     buffer_size: usize,
     physics_config: PhysicsConfig,
+    estimator: EstimatorConfig,
+    confidence: ConfidenceConfig,
     gesture_start_time: Option<Timestamp>,
+    rejected_samples: u64,
+    state: GestureState,
+    release_time: Option<Timestamp>,
+    events: Option<PredictionEvents>,
+    origin: Option<Point2D>,
+    peak_displacement: Point2D,
+    cancellation_config: CancellationConfig,
+    cancellation: CancellationLatch,
+    input_filter: Option<InputFilter>,
+    duplicate_timestamp_policy: DuplicateTimestampPolicy,
+    burst: Option<Burst>,
+    resampling: Option<ResampleConfig>,
+    /// `touch_buffer` at the `resampling` rate, kept in step with it
+    resampled: B,
+    /// Maps input coordinates into the space samples are analysed in
+    local_from_screen: Transform2D,
+    screen_from_local: Transform2D,
 }
 
 impl GesturePredictor {
@@ -47,21 +82,351 @@ impl GesturePredictor {
         Ok(Self::with_buffer_size_unchecked(physics_config, buffer_size))
     }
 
+    pub fn with_config(config: PredictorConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self::with_config_unchecked(&config))
+    }
+
+    pub(crate) fn with_config_unchecked(config: &PredictorConfig) -> Self {
+        let mut predictor = Self::with_buffer_size_unchecked(config.physics, config.buffer_size);
+        predictor.set_config_unchecked(config);
+        predictor
+    }
+
     fn with_buffer_size_unchecked(physics_config: PhysicsConfig, buffer_size: usize) -> Self {
         let buffer_size = buffer_size.clamp(MIN_BUFFER_SIZE, MAX_BUFFER_SIZE);
+        Self::from_buffers(
+            physics_config,
+            buffer_size,
+            VecDeque::with_capacity(buffer_size),
+            VecDeque::new(),
+        )
+    }
+}
+
+/// A `GesturePredictor` holding up to `N` samples inline, for real-time loops
+/// that must not allocate. Adding samples, predicting and detecting
+/// cancellation never allocate, including with resampling and the input
+/// filter enabled; enabling events allocates their queue once.
+pub type FixedGesturePredictor<const N: usize> = GesturePredictor<RingBuffer<N>>;
+
+impl<const N: usize> GesturePredictor<RingBuffer<N>> {
+    pub fn fixed(physics_config: PhysicsConfig) -> Result<Self> {
+        const { assert!(N >= MIN_BUFFER_SIZE, "a predictor needs room for 2 samples") };
+        physics_config.validate()?;
+        Ok(Self::from_buffers(physics_config, N, RingBuffer::new(), RingBuffer::new()))
+    }
+}
+
+impl<B: SampleBuffer> GesturePredictor<B> {
+    fn from_buffers(physics_config: PhysicsConfig, buffer_size: usize, touch_buffer: B, resampled: B) -> Self {
         Self {
-            touch_buffer: VecDeque::with_capacity(buffer_size),
+            touch_buffer,
             buffer_size,
             physics_config,
+            estimator: EstimatorConfig::default(),
+            confidence: ConfidenceConfig::default(),
             gesture_start_time: None,
+            rejected_samples: 0,
+            state: GestureState::Idle,
+            release_time: None,
+            events: None,
+            origin: None,
+            peak_displacement: Point2D::new(0.0, 0.0),
+            cancellation_config: CancellationConfig::default(),
+            cancellation: CancellationLatch::default(),
+            input_filter: None,
+            duplicate_timestamp_policy: DuplicateTimestampPolicy::default(),
+            burst: None,
+            resampling: None,
+            resampled,
+            local_from_screen: Transform2D::IDENTITY,
+            screen_from_local: Transform2D::IDENTITY,
+        }
+    }
+
+    /// Apply a validated config's physics, estimator and confidence settings.
+    /// The buffer size is fixed at creation, so it isn't changed.
+    pub(crate) fn set_config_unchecked(&mut self, config: &PredictorConfig) {
+        self.physics_config = config.physics;
+        self.estimator = config.estimator;
+        self.confidence = config.confidence;
+    }
+
+    /// Replace the physics configuration, keeping any buffered touch points
+    pub fn set_physics_config(&mut self, physics_config: PhysicsConfig) -> Result<()> {
+        physics_config.validate()?;
+        self.set_physics_config_unchecked(physics_config);
+        Ok(())
+    }
+
+    pub(crate) fn set_physics_config_unchecked(&mut self, physics_config: PhysicsConfig) {
+        self.physics_config = physics_config;
+    }
+
+    pub fn physics_config(&self) -> PhysicsConfig {
+        self.physics_config
+    }
+
+    pub fn set_estimator_config(&mut self, config: EstimatorConfig) -> Result<()> {
+        config.validate()?;
+        self.estimator = config;
+        Ok(())
+    }
+
+    pub fn estimator_config(&self) -> EstimatorConfig {
+        self.estimator
+    }
+
+    pub fn set_confidence_config(&mut self, config: ConfidenceConfig) -> Result<()> {
+        config.validate()?;
+        self.confidence = config;
+        Ok(())
+    }
+
+    pub fn confidence_config(&self) -> ConfidenceConfig {
+        self.confidence
+    }
+
+    /// Start a new gesture at touch-down from any state, discarding the
+    /// previous gesture even if it was never ended.
+    pub fn begin(&mut self, x: f64, y: f64, timestamp_ms: f64) -> Result<()> {
+        self.reset();
+        self.add_touch_point(x, y, timestamp_ms)
+    }
+
+    /// Add a sample to the active gesture
+    pub fn update(&mut self, x: f64, y: f64, timestamp_ms: f64) -> Result<()> {
+        if self.state != GestureState::Active {
+            self.rejected_samples += 1;
+            return Err(PredictorError::InvalidStateTransition {
+                from: self.state,
+                event: "update".into(),
+            });
+        }
+        self.add_touch_point(x, y, timestamp_ms)
+    }
+
+    /// Mark the touch as released at `release_time_ms`.
+    /// `predict` keeps returning the final prediction until the next `begin` or `reset`.
+    pub fn end(&mut self, release_time_ms: f64) -> Result<()> {
+        self.require_active("end")?;
+
+        let release_time = Timestamp::new(release_time_ms);
+        if !release_time.is_valid() {
+            return Err(PredictorError::InvalidTimestamp {
+                timestamp: release_time_ms,
+                reason: "must be non-negative and finite".into(),
+            });
+        }
+        if let Some(last) = self.touch_buffer.back() {
+            if release_time < last.timestamp {
+                return Err(PredictorError::TimestampOutOfOrder {
+                    previous: last.timestamp.as_millis(),
+                    current: release_time_ms,
+                });
+            }
+        }
+
+        self.release_time = Some(release_time);
+        self.state = GestureState::Ended;
+        Ok(())
+    }
+
+    /// Mark the touch as cancelled by the system; `predict` fails until the next `begin` or `reset`
+    pub fn cancel(&mut self) -> Result<()> {
+        self.require_active("cancel")?;
+        self.state = GestureState::Cancelled;
+        Ok(())
+    }
+
+    pub fn state(&self) -> GestureState {
+        self.state
+    }
+
+    fn require_active(&self, event: &'static str) -> Result<()> {
+        if self.state == GestureState::Active {
+            Ok(())
+        } else {
+            Err(PredictorError::InvalidStateTransition {
+                from: self.state,
+                event: event.into(),
+            })
         }
     }
 
+    /// Add a sample without explicit lifecycle calls.
+    /// The first sample after creation or `reset` implicitly begins a gesture;
+    /// samples after `end` or `cancel` are rejected.
     pub fn add_touch_point(&mut self, x: f64, y: f64, timestamp_ms: f64) -> Result<()> {
-        let touch_point = TouchPoint::new(x, y, timestamp_ms)
+        match self.push_touch_point(x, y, timestamp_ms) {
+            Ok(buffered) => {
+                if buffered {
+                    self.state = GestureState::Active;
+                    self.update_cancellation();
+                    self.observe_prediction();
+                }
+                Ok(())
+            }
+            Err(e) => {
+                self.rejected_samples += 1;
+                Err(e)
+            }
+        }
+    }
+
+    /// Filter samples before they enter the buffer, or pass them through
+    /// unchanged with `None`. Samples the filter drops are still `Ok` for the
+    /// caller and show up in `filter_stats` rather than `rejected_sample_count`.
+    /// Replacing the filter restarts its stats.
+    pub fn set_input_filter(&mut self, config: Option<InputFilterConfig>) -> Result<()> {
+        self.input_filter = config.map(InputFilter::new).transpose()?;
+        Ok(())
+    }
+
+    pub fn input_filter(&self) -> Option<InputFilterConfig> {
+        self.input_filter.as_ref().map(InputFilter::config)
+    }
+
+    /// Samples dropped or merged by the input filter. Not cleared by `reset`.
+    pub fn filter_stats(&self) -> FilterStats {
+        self.input_filter
+            .as_ref()
+            .map(InputFilter::stats)
+            .unwrap_or_default()
+    }
+
+    /// Choose how samples sharing the previous sample's timestamp are buffered.
+    /// Takes effect from the next duplicate.
+    pub fn set_duplicate_timestamp_policy(&mut self, policy: DuplicateTimestampPolicy) {
+        self.duplicate_timestamp_policy = policy;
+    }
+
+    pub fn duplicate_timestamp_policy(&self) -> DuplicateTimestampPolicy {
+        self.duplicate_timestamp_policy
+    }
+
+    /// Resample the buffered samples to a uniform rate before velocity,
+    /// straightness and deceleration analysis, or analyse them as they
+    /// arrived with `None`. The resampled window holds at most `buffer_size`
+    /// points, so it spans the same time whatever the input rate.
+    pub fn set_resampling(&mut self, config: Option<ResampleConfig>) -> Result<()> {
+        if let Some(config) = &config {
+            config.validate()?;
+        }
+        self.resampling = config;
+        self.update_resampled();
+        Ok(())
+    }
+
+    pub fn resampling(&self) -> Option<ResampleConfig> {
+        self.resampling
+    }
+
+    fn update_resampled(&mut self) {
+        match &self.resampling {
+            Some(config) => resample_into(
+                self.touch_buffer.make_contiguous(),
+                config,
+                self.buffer_size,
+                &mut self.resampled,
+            ),
+            None => self.resampled.clear(),
+        }
+    }
+
+    /// Samples to analyse: the resampled points when resampling is enabled
+    /// and spans at least two of them, otherwise the raw buffer
+    fn samples(&self) -> &B {
+        if self.resampled.len() >= MIN_BUFFER_SIZE {
+            &self.resampled
+        } else {
+            &self.touch_buffer
+        }
+    }
+
+    /// Analyse gestures in a transformed view's own space, e.g. along the axes
+    /// of a rotated card or in the units of a zoomed canvas.
+    ///
+    /// Samples are mapped through `local_from_screen` as they arrive, and
+    /// outputs are mapped back to screen space: predicted positions, those in
+    /// prediction-changed events, and dismissal distances, so event and
+    /// dismissal thresholds are in screen units too. Physics, input filter and
+    /// cancellation thresholds apply to the local samples. Buffered samples
+    /// aren't remapped, so change the transform between gestures.
+    pub fn set_transform(&mut self, local_from_screen: Transform2D) -> Result<()> {
+        let screen_from_local = local_from_screen
+            .inverse()
+            .ok_or(PredictorError::InvalidConfiguration {
+                field: "transform".into(),
+                value: local_from_screen.determinant(),
+                reason: "must be finite and invertible".into(),
+            })?;
+
+        self.local_from_screen = local_from_screen;
+        self.screen_from_local = screen_from_local;
+        Ok(())
+    }
+
+    /// The screen-to-local transform applied to incoming samples
+    pub fn transform(&self) -> Transform2D {
+        self.local_from_screen
+    }
+
+    /// Queue prediction-changed events once predictions cross `thresholds`,
+    /// or stop with `None`. Events are only computed while enabled.
+    pub fn set_event_thresholds(&mut self, thresholds: Option<EventThresholds>) -> Result<()> {
+        self.events = thresholds.map(PredictionEvents::new).transpose()?;
+        Ok(())
+    }
+
+    /// Thresholds events are emitted at, or `None` while events are disabled
+    pub fn event_thresholds(&self) -> Option<&EventThresholds> {
+        self.events.as_ref().map(PredictionEvents::thresholds)
+    }
+
+    /// Take the oldest queued prediction-changed event
+    pub fn poll_event(&mut self) -> Option<PredictionEvent> {
+        self.events.as_mut()?.poll()
+    }
+
+    fn observe_prediction(&mut self) {
+        if self.events.is_none() {
+            return;
+        }
+
+        let (Ok(prediction), Some(last)) = (self.predict(), self.touch_buffer.back().copied()) else {
+            return;
+        };
+        let current = self.screen_from_local.apply(last.position);
+        if let Some(events) = self.events.as_mut() {
+            events.observe(prediction, current, last.timestamp);
+        }
+    }
+
+    /// Validate a sample and buffer it, returning whether the buffer changed
+    fn push_touch_point(&mut self, x: f64, y: f64, timestamp_ms: f64) -> Result<bool> {
+        if matches!(self.state, GestureState::Ended | GestureState::Cancelled) {
+            return Err(PredictorError::InvalidStateTransition {
+                from: self.state,
+                event: "add_touch_point".into(),
+            });
+        }
+
+        if !x.is_finite() || !y.is_finite() {
+            return Err(PredictorError::InvalidCoordinate { x, y });
+        }
+
+        // A huge but finite transform can still overflow
+        let local = self.local_from_screen.apply(Point2D::new(x, y));
+        if !local.x.is_finite() || !local.y.is_finite() {
+            return Err(PredictorError::InvalidCoordinate { x: local.x, y: local.y });
+        }
+
+        let touch_point = TouchPoint::new(local.x, local.y, timestamp_ms)
             .ok_or(PredictorError::InvalidTimestamp {
                 timestamp: timestamp_ms,
-                reason: "must be non-negative and finite",
+                reason: "must be non-negative and finite".into(),
             })?;
 
         // Check timestamp ordering
@@ -74,21 +439,137 @@ impl GesturePredictor {
             }
         }
 
+        let previous = self.touch_buffer.back().map(|last| last.timestamp);
+        let duplicate = previous == Some(touch_point.timestamp);
+        let resolved = if duplicate {
+            self.resolve_duplicate(touch_point)?
+        } else {
+            None
+        };
+
+        let filtered = match (self.input_filter.as_mut(), resolved) {
+            (Some(filter), Some(Filtered::ReplaceLast(point))) => {
+                Filtered::ReplaceLast(filter.merge(point))
+            }
+            (Some(filter), Some(Filtered::Append(point))) => filter.append(point),
+            (Some(filter), _) => filter.process(touch_point),
+            (None, Some(resolved)) => resolved,
+            (None, None) => Filtered::Append(touch_point),
+        };
+        let raw_position = touch_point.position;
+        let touch_point = match filtered {
+            Filtered::Append(point) => point,
+            Filtered::ReplaceLast(point) => {
+                self.touch_buffer.pop_back();
+                if self.touch_buffer.is_empty() {
+                    // The first sample was replaced, so the gesture starts here
+                    self.origin = None;
+                }
+                point
+            }
+            Filtered::Dropped => return Ok(false),
+        };
+
+        // The sample is certain to be buffered now, so it can join the burst
+        if duplicate {
+            let appended = matches!(filtered, Filtered::Append(_));
+            self.join_burst(raw_position, touch_point.timestamp, appended);
+        } else {
+            self.burst = Some(Burst {
+                previous,
+                position_sum: raw_position,
+                count: 1,
+            });
+        }
+
         // Set gesture start time
         if self.gesture_start_time.is_none() {
             self.gesture_start_time = Some(touch_point.timestamp);
         }
 
+        // Track how far the gesture has reached from where it started
+        let origin = *self.origin.get_or_insert(touch_point.position);
+        let displacement = touch_point.position - origin;
+        if displacement.magnitude() > self.peak_displacement.magnitude() {
+            self.peak_displacement = displacement;
+        }
+
         // Maintain buffer size
         if self.touch_buffer.len() >= self.buffer_size {
             self.touch_buffer.pop_front();
         }
 
         self.touch_buffer.push_back(touch_point);
-        Ok(())
+        if self.resampling.is_some() {
+            self.update_resampled();
+        }
+        Ok(true)
+    }
+
+    /// Apply the duplicate timestamp policy to a sample sharing the latest
+    /// buffered timestamp: `ReplaceLast` to merge it, `Append` to buffer it
+    /// after the burst, or `None` to leave it to the input filter
+    fn resolve_duplicate(&self, mut touch_point: TouchPoint) -> Result<Option<Filtered>> {
+        let resolved = match (self.duplicate_timestamp_policy, self.burst) {
+            (DuplicateTimestampPolicy::Keep, _) => return Ok(None),
+            (DuplicateTimestampPolicy::Reject, _) => {
+                return Err(PredictorError::InvalidTimestamp {
+                    timestamp: touch_point.timestamp.as_millis(),
+                    reason: "duplicates the previous sample's timestamp".into(),
+                });
+            }
+            (DuplicateTimestampPolicy::Average, Some(burst)) => {
+                touch_point.position =
+                    (burst.position_sum + touch_point.position) / (burst.count + 1) as f64;
+                Filtered::ReplaceLast(touch_point)
+            }
+            (DuplicateTimestampPolicy::Interpolate, Some(Burst { previous: Some(_), .. })) => {
+                Filtered::Append(touch_point)
+            }
+            _ => Filtered::ReplaceLast(touch_point),
+        };
+        Ok(Some(resolved))
     }
 
+    /// Count a duplicate about to be buffered into the current burst. Under
+    /// `DuplicateTimestampPolicy::Interpolate` the burst samples already
+    /// buffered take evenly spaced slots after the burst's previous
+    /// timestamp, the new one keeping the shared timestamp in the last slot.
+    fn join_burst(&mut self, position: Point2D, timestamp: Timestamp, appended: bool) {
+        let Some(burst) = self.burst.as_mut() else {
+            return;
+        };
+        burst.count += 1;
+        burst.position_sum = burst.position_sum + position;
+
+        if !appended || self.duplicate_timestamp_policy != DuplicateTimestampPolicy::Interpolate {
+            return;
+        }
+        let Some(previous) = burst.previous else {
+            return;
+        };
+        let count = burst.count as usize;
+        let span = timestamp - previous;
+        for (back, point) in self.touch_buffer.iter_mut().rev().take(count - 1).enumerate() {
+            let slot = count - 1 - back;
+            point.timestamp = Timestamp::new(previous.as_millis() + span * slot as f64 / count as f64);
+        }
+    }
+
+    /// Predict where the gesture comes to rest, in screen space
     pub fn predict(&self) -> Result<Prediction> {
+        let prediction = self.predict_local()?;
+        Ok(Prediction::new(
+            self.screen_from_local.apply(prediction.position),
+            prediction.confidence,
+        ))
+    }
+
+    fn predict_local(&self) -> Result<Prediction> {
+        if self.state == GestureState::Cancelled {
+            return Err(PredictorError::GestureCancelled);
+        }
+
         // Check minimum data requirements
         if self.touch_buffer.len() < 2 {
             return Err(PredictorError::InsufficientData {
@@ -123,6 +604,16 @@ impl GesturePredictor {
             actual: 0,
         })?;
 
+        // A finger that rested before lifting doesn't fling
+        if let Some(release) = self.release_time {
+            if release - current_point.timestamp > self.estimator.release_stop_ms {
+                return Err(PredictorError::VelocityTooLow {
+                    velocity: 0.0,
+                    minimum: self.physics_config.min_velocity_threshold,
+                });
+            }
+        }
+
         // Calculate stopping distance
         let (distance_x, distance_y, _) = self.physics_config
             .calculate_stopping_distance(velocity.x, velocity.y)?;
@@ -150,10 +641,11 @@ impl GesturePredictor {
     }
 
     fn calculate_weighted_velocity(&self) -> Result<Velocity2D> {
-        if self.touch_buffer.len() < 2 {
+        let samples = self.samples();
+        if samples.len() < 2 {
             return Err(PredictorError::InsufficientData {
                 required: 2,
-                actual: self.touch_buffer.len(),
+                actual: samples.len(),
             });
         }
 
@@ -161,10 +653,10 @@ impl GesturePredictor {
         let mut total_velocity_y = 0.0;
         let mut total_weight = 0.0;
 
-        let n = self.touch_buffer.len();
+        let n = samples.len();
         let mut prev_point: Option<&TouchPoint> = None;
 
-        for (i, curr) in self.touch_buffer.iter().enumerate() {
+        for (i, curr) in samples.iter().enumerate() {
             if let Some(prev) = prev_point {
                 if let Some(dt) = curr.timestamp.duration_since(&prev.timestamp) {
                     if dt > 0.0 {
@@ -174,7 +666,7 @@ impl GesturePredictor {
                             dt,
                         ) {
                             // Weight more recent velocities higher
-                            let weight = ((i as f64) / (n as f64)).powi(2);
+                            let weight = math::powf((i as f64) / (n as f64), self.estimator.recency_exponent);
 
                             total_velocity_x += velocity.x * weight;
                             total_velocity_y += velocity.y * weight;
@@ -193,19 +685,19 @@ impl GesturePredictor {
             ))
         } else {
             Err(PredictorError::NumericalError {
-                operation: "velocity calculation",
-                details: "no valid velocity measurements",
+                operation: "velocity calculation".into(),
+                details: "no valid velocity measurements".into(),
             })
         }
     }
 
     fn calculate_confidence(&self, speed: f64, gesture_duration: f64) -> f64 {
         // Speed confidence (0 to 1)
-        let speed_confidence = (speed / SPEED_CONFIDENCE_SCALE).min(1.0);
+        let speed_confidence = (speed / self.confidence.speed_scale).min(1.0);
 
         // Duration confidence (0 to 1)
         let duration_confidence = ((gesture_duration - self.physics_config.min_gesture_time_ms)
-            / DURATION_CONFIDENCE_SCALE)
+            / self.confidence.duration_scale)
             .clamp(0.0, 1.0);
 
         // Straightness score (0 to 1)
@@ -213,7 +705,7 @@ impl GesturePredictor {
 
         // Deceleration penalty
         let deceleration_penalty = if self.is_gesture_decelerating() {
-            0.5
+            self.confidence.deceleration_penalty
         } else {
             1.0
         };
@@ -223,15 +715,16 @@ impl GesturePredictor {
     }
 
     fn calculate_straightness_score(&self) -> f64 {
-        if self.touch_buffer.len() < 3 {
+        let samples = self.samples();
+        if samples.len() < 3 {
             return 1.0;
         }
 
-        let first = match self.touch_buffer.front() {
+        let first = match samples.front() {
             Some(point) => &point.position,
             None => return 1.0,
         };
-        let last = match self.touch_buffer.back() {
+        let last = match samples.back() {
             Some(point) => &point.position,
             None => return 1.0,
         };
@@ -245,7 +738,7 @@ impl GesturePredictor {
         let mut path_distance = 0.0;
         let mut prev_pos: Option<&Point2D> = None;
 
-        for point in &self.touch_buffer {
+        for point in samples.iter() {
             if let Some(prev) = prev_pos {
                 path_distance += prev.distance_to(&point.position);
             }
@@ -256,33 +749,36 @@ impl GesturePredictor {
     }
 
     fn is_gesture_decelerating(&self) -> bool {
-        if self.touch_buffer.len() < 4 {
+        let samples = self.samples();
+        if samples.len() < 4 {
             return false;
         }
 
-        let n = self.touch_buffer.len();
+        let n = samples.len();
         let start_idx = n.saturating_sub(4);
 
-        let mut recent_speeds = Vec::with_capacity(3);
+        let mut speeds = [0.0; 3];
+        let mut count = 0;
         let mut prev_point: Option<&TouchPoint> = None;
 
-        for point in self.touch_buffer.iter().skip(start_idx) {
+        for point in samples.iter().skip(start_idx) {
             if let Some(prev) = prev_point {
                 if let Some(dt) = point.timestamp.duration_since(&prev.timestamp) {
                     if dt > 0.0 {
                         let distance = prev.position.distance_to(&point.position);
-                        let speed = distance / dt * 1000.0; // Convert to pixels/second
-                        recent_speeds.push(speed);
+                        speeds[count] = distance / dt * 1000.0; // Convert to points/second
+                        count += 1;
                     }
                 }
             }
             prev_point = Some(point);
         }
 
+        let recent_speeds = &speeds[..count];
         if recent_speeds.len() >= 2 {
             recent_speeds
                 .windows(2)
-                .all(|w| w[1] < w[0] * DECELERATION_FACTOR)
+                .all(|w| w[1] < w[0] * self.confidence.deceleration_factor)
         } else {
             false
         }
@@ -291,35 +787,154 @@ impl GesturePredictor {
     pub fn reset(&mut self) {
         self.touch_buffer.clear();
         self.gesture_start_time = None;
+        self.state = GestureState::Idle;
+        self.release_time = None;
+        self.origin = None;
+        self.peak_displacement = Point2D::new(0.0, 0.0);
+        self.cancellation = CancellationLatch::default();
+        if let Some(events) = self.events.as_mut() {
+            events.clear();
+        }
+        if let Some(filter) = self.input_filter.as_mut() {
+            filter.clear();
+        }
+        self.burst = None;
+        self.resampled.clear();
     }
 
+    /// Predict whether a swipe-to-dismiss gesture ends past `threshold` points from
+    /// its first point, or returns toward it.
+    ///
+    /// Distances are measured along the gesture's dominant direction (from the
+    /// first point to the furthest point reached), so dragging back toward the
+    /// start lowers both distances even before the finger reverses past it.
+    /// The projection uses the same physics as `predict`; a gesture too slow to
+    /// fling is projected to rest where it is.
+    pub fn predict_dismissal(&self, threshold: f64) -> Result<DismissalPrediction> {
+        if !threshold.is_finite() || threshold <= 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "threshold".into(),
+                value: threshold,
+                reason: "must be positive and finite".into(),
+            });
+        }
+
+        let (origin, current) = match (self.origin, self.touch_buffer.back()) {
+            (Some(origin), Some(current)) => (origin, current.position),
+            _ => {
+                return Err(PredictorError::InsufficientData {
+                    required: 2,
+                    actual: self.touch_buffer.len(),
+                })
+            }
+        };
+
+        let (resting, confidence) = match self.predict_local() {
+            Ok(prediction) => (prediction.position, prediction.confidence),
+            // No fling: the element rests where the finger is
+            Err(PredictorError::VelocityTooLow { .. }) => (current, 1.0),
+            Err(e) => return Err(e),
+        };
+
+        // Measured on screen, like predicted positions
+        let origin = self.screen_from_local.apply(origin);
+        let current = self.screen_from_local.apply(current);
+        let resting = self.screen_from_local.apply(resting);
+        let axis = match self.screen_from_local.apply_vector(self.peak_displacement).normalized() {
+            Some(axis) => axis,
+            // Never left the origin
+            None => Point2D::new(0.0, 0.0),
+        };
+        let current_distance = (current - origin).dot(&axis);
+        let projected_distance = (resting - origin).dot(&axis);
+
+        // Logistic around the threshold, pulled toward 50% when the prediction is uncertain
+        let softness = threshold * DISMISSAL_SOFTNESS_RATIO;
+        let certain = 1.0 / (1.0 + math::exp(-(projected_distance - threshold) / softness));
+        let probability = 0.5 + (certain - 0.5) * confidence;
+
+        Ok(DismissalPrediction {
+            will_dismiss: projected_distance >= threshold,
+            probability: probability.clamp(0.0, 1.0),
+            current_distance,
+            projected_distance,
+        })
+    }
+
+    /// Whether the gesture appears to be cancelled; see `cancellation` for why
     pub fn detect_cancellation(&self) -> bool {
-        if self.touch_buffer.len() < 3 {
-            return false;
+        self.cancellation.verdict().is_some()
+    }
+
+    /// Why the gesture appears to be cancelled, if it does.
+    /// Re-evaluated on every accepted sample and debounced by
+    /// `CancellationConfig::hysteresis_samples`.
+    pub fn cancellation(&self) -> Option<CancellationReason> {
+        self.cancellation.verdict()
+    }
+
+    pub fn set_cancellation_config(&mut self, config: CancellationConfig) -> Result<()> {
+        config.validate()?;
+        self.cancellation_config = config;
+        Ok(())
+    }
+
+    pub fn cancellation_config(&self) -> CancellationConfig {
+        self.cancellation_config
+    }
+
+    fn update_cancellation(&mut self) {
+        let raw = self.raw_cancellation_reason();
+        self.cancellation
+            .update(raw, self.cancellation_config.hysteresis_samples);
+    }
+
+    /// Cancellation verdict for the current sample alone, before hysteresis
+    fn raw_cancellation_reason(&self) -> Option<CancellationReason> {
+        let config = &self.cancellation_config;
+        let min_displacement = config.min_displacement.max(f64::EPSILON);
+        let origin = self.origin?;
+        let current = self.touch_buffer.back()?.position;
+
+        // A gesture that hasn't gone anywhere can't be abandoned
+        let peak = self.peak_displacement;
+        if peak.magnitude() < min_displacement {
+            return None;
         }
 
-        // Check for direction reversal using the last 3 points
-        let mut recent_points = self.touch_buffer.iter().rev().take(3);
-        if let (Some(p3), Some(p2), Some(p1)) = (recent_points.next(), recent_points.next(), recent_points.next()) {
-            let v1 = p2.position - p1.position;
-            let v2 = p3.position - p2.position;
+        if (current - origin).dot(&peak) < 0.0 {
+            return Some(CancellationReason::ReturnedPastOrigin);
+        }
 
-            // Dot product < 0 means angle > 90 degrees
-            let dot_product = v1.x * v2.x + v1.y * v2.y;
-            if dot_product < 0.0 {
-                return true;
+        // Compare the latest motion longer than the jitter threshold with the
+        // gesture's dominant direction
+        if let Some(recent) = self.recent_motion(min_displacement) {
+            let cos_angle = recent.dot(&peak) / (recent.magnitude() * peak.magnitude());
+            if cos_angle < math::cos(config.reversal_angle_deg.to_radians()) {
+                return Some(CancellationReason::DirectionReversal);
             }
         }
 
-        // Check for deceleration below threshold
-        if self.is_gesture_decelerating() {
-            if let Ok(velocity) = self.calculate_weighted_velocity() {
-                let speed = velocity.speed();
-                return speed < self.physics_config.min_velocity_threshold * 0.5;
-            }
+        let speed = self.calculate_weighted_velocity().ok()?.speed();
+        if speed < config.stop_speed {
+            return Some(CancellationReason::Stopped);
+        }
+        if self.is_gesture_decelerating()
+            && speed < self.physics_config.min_velocity_threshold * config.slow_velocity_ratio
+        {
+            return Some(CancellationReason::SlowedBelowThreshold);
         }
 
-        false
+        None
+    }
+
+    /// Vector from the most recent buffered point at least `min_length` points
+    /// away to the latest point
+    fn recent_motion(&self, min_length: f64) -> Option<Point2D> {
+        let mut points = self.touch_buffer.iter().rev().map(|point| point.position);
+        let latest = points.next()?;
+        let earlier = points.find(|point| point.distance_to(&latest) >= min_length)?;
+        Some(latest - earlier)
     }
 
     pub fn buffer_size(&self) -> usize {
@@ -330,14 +945,23 @@ impl GesturePredictor {
         self.touch_buffer.len()
     }
 
+    /// Whether a gesture is in progress: begun (or started by
+    /// `add_touch_point`) and not yet ended or cancelled
     pub fn is_active(&self) -> bool {
-        !self.touch_buffer.is_empty()
+        self.state == GestureState::Active
+    }
+
+    /// Number of samples rejected by `add_touch_point` since creation.
+    /// Not cleared by `reset`, so it covers every gesture this predictor has seen.
+    pub fn rejected_sample_count(&self) -> u64 {
+        self.rejected_samples
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::Preset;
 
     #[test]
     fn test_basic_prediction() {
@@ -384,6 +1008,454 @@ mod tests {
         assert!(predictor.add_touch_point(30.0, 0.0, 5.0).is_err());
     }
 
+    #[test]
+    fn test_rejected_sample_count() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+
+        assert!(predictor.add_touch_point(0.0, 0.0, 0.0).is_ok());
+        assert!(matches!(
+            predictor.add_touch_point(f64::NAN, 0.0, 10.0),
+            Err(PredictorError::InvalidCoordinate { .. })
+        ));
+        assert!(matches!(
+            predictor.add_touch_point(0.0, f64::INFINITY, 10.0),
+            Err(PredictorError::InvalidCoordinate { .. })
+        ));
+        assert!(predictor.add_touch_point(10.0, 0.0, 20.0).is_ok());
+        assert!(predictor.add_touch_point(20.0, 0.0, 5.0).is_err());
+
+        assert_eq!(predictor.point_count(), 2);
+        assert_eq!(predictor.rejected_sample_count(), 3);
+
+        // The counter survives reset
+        predictor.reset();
+        assert_eq!(predictor.rejected_sample_count(), 3);
+    }
+
+    #[test]
+    fn test_input_filter() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+        predictor.set_input_filter(Some(InputFilterConfig::default())).unwrap();
+
+        for i in 0..6 {
+            let x = i as f64 * 20.0;
+            assert!(predictor.add_touch_point(x, 0.0, i as f64 * 20.0).is_ok());
+            // Coalesced duplicate of the same frame
+            assert!(predictor.add_touch_point(x + 1.0, 0.0, i as f64 * 20.0).is_ok());
+        }
+        let clean = predictor.predict().unwrap();
+
+        // A glitched sample is dropped before it reaches the buffer
+        assert!(predictor.add_touch_point(2000.0, 900.0, 120.0).is_ok());
+        assert_eq!(predictor.point_count(), 6);
+        let filtered = predictor.predict().unwrap();
+        assert_eq!(filtered.position, clean.position);
+
+        let stats = predictor.filter_stats();
+        assert_eq!(stats.spikes_rejected, 1);
+        assert_eq!(stats.duplicates_merged, 6);
+        assert_eq!(predictor.rejected_sample_count(), 0);
+
+        // Stats survive reset
+        predictor.reset();
+        assert_eq!(predictor.filter_stats(), stats);
+
+        let invalid = InputFilterConfig {
+            max_acceleration: Some(-1.0),
+            ..Default::default()
+        };
+        assert!(predictor.set_input_filter(Some(invalid)).is_err());
+        assert!(predictor.input_filter().is_some());
+        predictor.set_input_filter(None).unwrap();
+        assert_eq!(predictor.filter_stats(), FilterStats::default());
+    }
+
+    /// Feed 6 frames 16ms apart, each a burst of 3 coalesced samples sharing
+    /// the frame's timestamp and moving at 1000 px/s overall
+    fn feed_bursts(predictor: &mut GesturePredictor) -> usize {
+        let mut accepted = 0;
+        for frame in 0..6 {
+            let t = frame as f64 * 16.0;
+            for k in 0..3 {
+                let x = t - 10.0 + k as f64 * 5.0;
+                if predictor.add_touch_point(x, 0.0, t).is_ok() {
+                    accepted += 1;
+                }
+            }
+        }
+        accepted
+    }
+
+    #[test]
+    fn test_duplicate_timestamp_policies() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+        assert_eq!(predictor.duplicate_timestamp_policy(), DuplicateTimestampPolicy::Keep);
+
+        // Keep buffers every sample as delivered
+        assert_eq!(feed_bursts(&mut predictor), 18);
+        assert_eq!(predictor.point_count(), predictor.buffer_size());
+
+        predictor.reset();
+        predictor.set_duplicate_timestamp_policy(DuplicateTimestampPolicy::Merge);
+        // Merge keeps the latest sample of each burst
+        assert_eq!(feed_bursts(&mut predictor), 18);
+        assert_eq!(predictor.point_count(), 6);
+        assert!(predictor.touch_buffer.iter().all(|p| p.position.x == p.timestamp.as_millis()));
+        let velocity = predictor.calculate_weighted_velocity().unwrap();
+        assert!((velocity.x - 1000.0).abs() < 1e-6);
+
+        predictor.reset();
+        predictor.set_duplicate_timestamp_policy(DuplicateTimestampPolicy::Average);
+        feed_bursts(&mut predictor);
+        assert_eq!(predictor.point_count(), 6);
+        assert!(predictor
+            .touch_buffer
+            .iter()
+            .all(|p| p.position.x == p.timestamp.as_millis() - 5.0));
+
+        predictor.reset();
+        predictor.set_duplicate_timestamp_policy(DuplicateTimestampPolicy::Interpolate);
+        feed_bursts(&mut predictor);
+        // The first burst has no earlier timestamp to spread over and is merged
+        assert_eq!(predictor.point_count(), predictor.buffer_size());
+        let timestamps: Vec<f64> = predictor.touch_buffer.iter().map(|p| p.timestamp.as_millis()).collect();
+        assert!(timestamps.windows(2).all(|w| w[1] > w[0]));
+        assert_eq!(*timestamps.last().unwrap(), 80.0);
+        assert!((timestamps[timestamps.len() - 2] - (64.0 + 32.0 / 3.0)).abs() < 1e-9);
+        let velocity = predictor.calculate_weighted_velocity().unwrap();
+        assert!((velocity.x - 1000.0).abs() < 200.0);
+
+        predictor.reset();
+        predictor.set_duplicate_timestamp_policy(DuplicateTimestampPolicy::Reject);
+        assert_eq!(feed_bursts(&mut predictor), 6);
+        assert_eq!(predictor.rejected_sample_count(), 12);
+        assert!(matches!(
+            predictor.add_touch_point(100.0, 0.0, 80.0),
+            Err(PredictorError::InvalidTimestamp { .. })
+        ));
+    }
+
+    #[test]
+    fn test_zero_duration_burst() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+        predictor.set_duplicate_timestamp_policy(DuplicateTimestampPolicy::Merge);
+
+        // A whole burst at one instant is a single sample, not a gesture
+        for i in 0..5 {
+            assert!(predictor.add_touch_point(i as f64 * 30.0, 0.0, 0.0).is_ok());
+        }
+        assert!(matches!(
+            predictor.predict(),
+            Err(PredictorError::InsufficientData { required: 2, actual: 1 })
+        ));
+        assert!(predictor.predict_dismissal(100.0).is_err());
+    }
+
+    #[test]
+    fn test_interpolated_bursts_with_spike_rejection() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+        predictor.set_input_filter(Some(InputFilterConfig::default())).unwrap();
+        predictor.set_duplicate_timestamp_policy(DuplicateTimestampPolicy::Interpolate);
+
+        for i in 0..4 {
+            predictor.add_touch_point(i as f64 * 16.0, 0.0, i as f64 * 16.0).unwrap();
+        }
+        // A glitch opens the next frame and is dropped, so the frame's real
+        // samples start a burst after 48ms rather than joining the glitch
+        predictor.add_touch_point(900.0, 0.0, 64.0).unwrap();
+        predictor.add_touch_point(60.0, 0.0, 64.0).unwrap();
+        predictor.add_touch_point(64.0, 0.0, 64.0).unwrap();
+
+        let stats = predictor.filter_stats();
+        assert_eq!(stats.spikes_rejected, 1);
+        assert_eq!(stats.duplicates_merged, 0);
+        let timestamps: Vec<f64> = predictor.touch_buffer.iter().map(|p| p.timestamp.as_millis()).collect();
+        assert_eq!(timestamps, [0.0, 16.0, 32.0, 48.0, 56.0, 64.0]);
+        let burst = predictor.burst.unwrap();
+        assert_eq!(burst.count, 2);
+        assert_eq!(burst.position_sum.x, 124.0);
+    }
+
+    /// Predict an accelerating swipe, x = 1.5t + 0.002t² px with t in ms,
+    /// sampled every `interval_ms`
+    fn predict_accelerating_swipe(interval_ms: f64, resampling: Option<ResampleConfig>) -> Prediction {
+        let mut predictor = GesturePredictor::new(PhysicsConfig::default()).unwrap();
+        predictor.set_resampling(resampling).unwrap();
+        for i in 0..=(200.0 / interval_ms) as usize {
+            let t = i as f64 * interval_ms;
+            predictor.add_touch_point(1.5 * t + 0.002 * t * t, 0.0, t).unwrap();
+        }
+        predictor.predict().unwrap()
+    }
+
+    #[test]
+    fn test_resampling_is_rate_independent() {
+        let spread = |resampling| {
+            let at_60hz = predict_accelerating_swipe(1000.0 / 60.0, resampling);
+            let at_120hz = predict_accelerating_swipe(1000.0 / 120.0, resampling);
+            (at_60hz.position.x - at_120hz.position.x).abs()
+        };
+
+        let raw = spread(None);
+        let resampled = spread(Some(ResampleConfig::default()));
+        assert!(resampled < raw / 5.0, "resampled {resampled} vs raw {raw}");
+    }
+
+    #[test]
+    fn test_resampling_config() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+
+        let invalid = ResampleConfig {
+            rate_hz: -60.0,
+            ..Default::default()
+        };
+        assert!(predictor.set_resampling(Some(invalid)).is_err());
+        assert!(predictor.resampling().is_none());
+
+        // Too short to resample to two points, so the raw samples are used
+        predictor.set_resampling(Some(ResampleConfig::default())).unwrap();
+        predictor.add_touch_point(0.0, 0.0, 0.0).unwrap();
+        predictor.add_touch_point(5.0, 0.0, 5.0).unwrap();
+        assert_eq!(predictor.samples().len(), 2);
+        assert!(predictor.calculate_weighted_velocity().is_ok());
+
+        predictor.reset();
+        assert!(predictor.resampled.is_empty());
+    }
+
+    #[test]
+    fn test_with_config() {
+        let config = PredictorConfig {
+            buffer_size: 4,
+            confidence: ConfidenceConfig {
+                speed_scale: 100_000.0,
+                ..Default::default()
+            },
+            ..Preset::Android.into()
+        };
+        let mut predictor = GesturePredictor::with_config(config).unwrap();
+        let mut default = GesturePredictor::new(PhysicsConfig::default()).unwrap();
+        assert_eq!(predictor.buffer_size(), 4);
+        assert_eq!(predictor.physics_config().deceleration_rate, 1800.0);
+
+        for i in 0..8 {
+            predictor.add_touch_point(i as f64 * 20.0, 0.0, i as f64 * 16.0).unwrap();
+            default.add_touch_point(i as f64 * 20.0, 0.0, i as f64 * 16.0).unwrap();
+        }
+        // A much higher speed scale leaves the same swipe far less confident
+        assert!(predictor.predict().unwrap().confidence < default.predict().unwrap().confidence / 10.0);
+
+        let invalid = PredictorConfig {
+            buffer_size: 500,
+            ..Default::default()
+        };
+        assert!(GesturePredictor::with_config(invalid).is_err());
+        let invalid = EstimatorConfig {
+            recency_exponent: f64::NAN,
+            ..Default::default()
+        };
+        assert!(predictor.set_estimator_config(invalid).is_err());
+        assert_eq!(predictor.estimator_config(), EstimatorConfig::default());
+    }
+
+    #[test]
+    fn test_fixed_matches_default() {
+        let config = PhysicsConfig::default();
+        let mut fixed = FixedGesturePredictor::<5>::fixed(config).unwrap();
+        let mut default = GesturePredictor::with_buffer_size(config, 5).unwrap();
+        assert_eq!(fixed.buffer_size(), 5);
+
+        // A curved swipe long enough to wrap the ring buffer
+        for i in 0..12 {
+            let t = i as f64 * 16.0;
+            let (x, y) = (t * 2.0, t * t * 0.01);
+            fixed.add_touch_point(x, y, t).unwrap();
+            default.add_touch_point(x, y, t).unwrap();
+        }
+        assert_eq!(fixed.point_count(), 5);
+        let (a, b) = (fixed.predict().unwrap(), default.predict().unwrap());
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.confidence, b.confidence);
+        assert_eq!(fixed.detect_cancellation(), default.detect_cancellation());
+    }
+
+    #[test]
+    fn test_transform() {
+        let config = PhysicsConfig::default();
+        let mut untransformed = GesturePredictor::new(config).unwrap();
+        for i in 0..8 {
+            untransformed.add_touch_point(i as f64 * 20.0, 0.0, i as f64 * 16.0).unwrap();
+        }
+        let expected = untransformed.predict().unwrap();
+
+        // A card rotated 90° and zoomed 2x, offset on screen: the same swipe in
+        // local space arrives on screen as a half-length vertical swipe
+        let local_from_screen = Transform2D::translation(-50.0, -80.0)
+            .then(&Transform2D::rotation(-std::f64::consts::FRAC_PI_2))
+            .then(&Transform2D::scale(2.0, 2.0));
+        let screen_from_local = local_from_screen.inverse().unwrap();
+
+        let mut predictor = GesturePredictor::new(config).unwrap();
+        predictor.set_transform(local_from_screen).unwrap();
+        for i in 0..8 {
+            let screen = screen_from_local.apply(Point2D::new(i as f64 * 20.0, 0.0));
+            predictor.add_touch_point(screen.x, screen.y, i as f64 * 16.0).unwrap();
+        }
+
+        let prediction = predictor.predict().unwrap();
+        let expected_on_screen = screen_from_local.apply(expected.position);
+        assert!(prediction.position.distance_to(&expected_on_screen) < 1e-9);
+        assert!((prediction.confidence - expected.confidence).abs() < 1e-9);
+
+        // Dismissal is measured along the card, in screen units: half the
+        // local distance, so half the threshold gives the same verdict
+        let dismissal = predictor.predict_dismissal(100.0).unwrap();
+        let expected_dismissal = untransformed.predict_dismissal(200.0).unwrap();
+        assert!((dismissal.projected_distance * 2.0 - expected_dismissal.projected_distance).abs() < 1e-9);
+        assert!((dismissal.current_distance * 2.0 - expected_dismissal.current_distance).abs() < 1e-9);
+        assert!((dismissal.probability - expected_dismissal.probability).abs() < 1e-9);
+        assert_eq!(dismissal.will_dismiss, expected_dismissal.will_dismiss);
+
+        // A finite sample that overflows the transform is rejected, not buffered
+        let point_count = predictor.point_count();
+        predictor.set_transform(Transform2D::scale(1e200, 1.0)).unwrap();
+        assert!(matches!(
+            predictor.add_touch_point(1e150, 0.0, 200.0),
+            Err(PredictorError::InvalidCoordinate { .. })
+        ));
+        assert_eq!(predictor.point_count(), point_count);
+        predictor.set_transform(local_from_screen).unwrap();
+
+        assert!(predictor.set_transform(Transform2D::scale(0.0, 1.0)).is_err());
+        assert_eq!(predictor.transform(), local_from_screen);
+    }
+
+    #[test]
+    fn test_gesture_lifecycle() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+        assert_eq!(predictor.state(), GestureState::Idle);
+        assert!(!predictor.is_active());
+
+        // Only begin is valid while idle
+        assert!(matches!(
+            predictor.update(0.0, 0.0, 0.0),
+            Err(PredictorError::InvalidStateTransition { from: GestureState::Idle, event }) if event == "update"
+        ));
+        assert!(predictor.end(0.0).is_err());
+        assert!(predictor.cancel().is_err());
+
+        predictor.begin(0.0, 0.0, 0.0).unwrap();
+        assert_eq!(predictor.state(), GestureState::Active);
+        assert!(predictor.is_active());
+        for i in 1..6 {
+            predictor.update(i as f64 * 20.0, 0.0, i as f64 * 20.0).unwrap();
+        }
+
+        // Release can't precede the last sample
+        assert!(matches!(predictor.end(50.0), Err(PredictorError::TimestampOutOfOrder { .. })));
+        predictor.end(110.0).unwrap();
+        assert_eq!(predictor.state(), GestureState::Ended);
+        assert!(!predictor.is_active());
+        assert!(predictor.predict().is_ok());
+        assert!(predictor.update(120.0, 0.0, 120.0).is_err());
+        assert!(predictor.add_touch_point(120.0, 0.0, 120.0).is_err());
+
+        // Begin auto-resets the previous gesture
+        predictor.begin(500.0, 500.0, 1000.0).unwrap();
+        assert_eq!(predictor.point_count(), 1);
+        predictor.update(520.0, 500.0, 1020.0).unwrap();
+        predictor.cancel().unwrap();
+        assert_eq!(predictor.state(), GestureState::Cancelled);
+        assert!(!predictor.is_active());
+        assert!(matches!(predictor.predict(), Err(PredictorError::GestureCancelled)));
+
+        predictor.reset();
+        assert_eq!(predictor.state(), GestureState::Idle);
+    }
+
+    #[test]
+    fn test_begin_while_active_starts_new_gesture() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+
+        // A touch-down whose release was never delivered
+        predictor.begin(0.0, 0.0, 0.0).unwrap();
+        for i in 1..6 {
+            predictor.update(i as f64 * 20.0, 0.0, i as f64 * 20.0).unwrap();
+        }
+
+        // Earlier timestamps are fine, the old samples are gone
+        predictor.begin(300.0, 300.0, 50.0).unwrap();
+        assert_eq!(predictor.state(), GestureState::Active);
+        assert_eq!(predictor.point_count(), 1);
+        assert_eq!(predictor.rejected_sample_count(), 0);
+    }
+
+    #[test]
+    fn test_release_after_pause_predicts_no_fling() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+
+        predictor.begin(0.0, 0.0, 0.0).unwrap();
+        for i in 1..6 {
+            predictor.update(i as f64 * 20.0, 0.0, i as f64 * 20.0).unwrap();
+        }
+        predictor.end(100.0 + EstimatorConfig::default().release_stop_ms + 1.0).unwrap();
+
+        assert!(matches!(predictor.predict(), Err(PredictorError::VelocityTooLow { .. })));
+    }
+
+    #[test]
+    fn test_add_touch_point_begins_implicitly() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+
+        predictor.add_touch_point(0.0, 0.0, 0.0).unwrap();
+        assert_eq!(predictor.state(), GestureState::Active);
+        predictor.update(10.0, 0.0, 10.0).unwrap();
+    }
+
+    #[test]
+    fn test_prediction_events() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+        predictor.set_event_thresholds(Some(EventThresholds::default())).unwrap();
+
+        // Steady motion emits far fewer events than samples
+        predictor.begin(0.0, 0.0, 0.0).unwrap();
+        for i in 1..20 {
+            predictor.update(i as f64 * 20.0, 0.0, i as f64 * 16.0).unwrap();
+        }
+        let mut events = Vec::new();
+        while let Some(event) = predictor.poll_event() {
+            events.push(event);
+        }
+        assert!(!events.is_empty() && events.len() < 19);
+        assert!(events[0].changes.first);
+        assert!(events[1..].iter().all(|event| !event.changes.first));
+
+        // Reversing the swipe flips the predicted direction
+        for i in 1..10 {
+            predictor.update(380.0 - i as f64 * 40.0, 0.0, 304.0 + i as f64 * 16.0).unwrap();
+        }
+        let mut flipped = false;
+        while let Some(event) = predictor.poll_event() {
+            flipped |= event.changes.direction_flip;
+        }
+        assert!(flipped);
+
+        predictor.set_event_thresholds(None).unwrap();
+        predictor.update(0.0, 0.0, 500.0).unwrap();
+        assert!(predictor.poll_event().is_none());
+    }
+
     #[test]
     fn test_gesture_cancellation() {
         let config = PhysicsConfig::default();
@@ -393,13 +1465,126 @@ mod tests {
         let _ = predictor.add_touch_point(0.0, 0.0, 0.0);
         let _ = predictor.add_touch_point(10.0, 0.0, 10.0);
         let _ = predictor.add_touch_point(20.0, 0.0, 20.0);
+        let _ = predictor.add_touch_point(30.0, 0.0, 30.0);
 
         assert!(!predictor.detect_cancellation());
 
-        // Reverse direction
-        let _ = predictor.add_touch_point(15.0, 0.0, 30.0);
+        // A jittery step back below the minimum displacement is ignored
+        let _ = predictor.add_touch_point(27.0, 0.0, 40.0);
+        assert!(!predictor.detect_cancellation());
+
+        // Reverse direction; it takes two samples to confirm
+        let _ = predictor.add_touch_point(15.0, 0.0, 50.0);
+        assert!(!predictor.detect_cancellation());
+        let _ = predictor.add_touch_point(5.0, 0.0, 60.0);
 
         assert!(predictor.detect_cancellation());
+        assert_eq!(predictor.cancellation(), Some(CancellationReason::DirectionReversal));
+
+        // Behind the starting point
+        let _ = predictor.add_touch_point(-10.0, 0.0, 70.0);
+        assert_eq!(predictor.cancellation(), Some(CancellationReason::ReturnedPastOrigin));
+    }
+
+    #[test]
+    fn test_cancellation_stopped_and_recovery() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+
+        for i in 0..6 {
+            let _ = predictor.add_touch_point(i as f64 * 20.0, 0.0, i as f64 * 20.0);
+        }
+        assert_eq!(predictor.cancellation(), None);
+
+        // Finger rests in place
+        for i in 6..16 {
+            let _ = predictor.add_touch_point(100.0, 0.0, i as f64 * 20.0);
+        }
+        assert_eq!(predictor.cancellation(), Some(CancellationReason::Stopped));
+
+        // Moving again clears the verdict once the motion is sustained
+        for i in 16..26 {
+            let _ = predictor.add_touch_point(100.0 + (i - 15) as f64 * 20.0, 0.0, i as f64 * 20.0);
+        }
+        assert_eq!(predictor.cancellation(), None);
+
+        predictor.reset();
+        assert_eq!(predictor.cancellation(), None);
+    }
+
+    #[test]
+    fn test_predict_dismissal() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+        assert!(predictor.predict_dismissal(200.0).is_err());
+
+        // Fast swipe to the right: flung past the threshold
+        for i in 0..8 {
+            predictor.add_touch_point(i as f64 * 20.0, 0.0, i as f64 * 16.0).unwrap();
+        }
+        let flung = predictor.predict_dismissal(200.0).unwrap();
+        assert!(flung.will_dismiss);
+        assert!(flung.probability > 0.5);
+        assert_eq!(flung.current_distance, 140.0);
+        assert!(flung.projected_distance > 200.0);
+
+        // Dragging back toward the start: returns
+        for i in 1..8 {
+            predictor
+                .add_touch_point(140.0 - i as f64 * 15.0, 0.0, 112.0 + i as f64 * 16.0)
+                .unwrap();
+        }
+        let returning = predictor.predict_dismissal(200.0).unwrap();
+        assert!(!returning.will_dismiss);
+        assert!(returning.probability < 0.5);
+        assert!(returning.projected_distance < returning.current_distance);
+
+        assert!(predictor.predict_dismissal(0.0).is_err());
+    }
+
+    #[test]
+    fn test_predict_dismissal_at_rest() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+
+        // Dragged past the threshold slowly and held: stays dismissed
+        for i in 0..30 {
+            predictor.add_touch_point(i as f64 * 10.0, 0.0, i as f64 * 100.0).unwrap();
+        }
+        for i in 30..40 {
+            predictor.add_touch_point(290.0, 0.0, i as f64 * 100.0).unwrap();
+        }
+        let held = predictor.predict_dismissal(200.0).unwrap();
+        assert_eq!(held.projected_distance, held.current_distance);
+        assert!(held.will_dismiss);
+        assert!(held.probability > 0.9);
+    }
+
+    #[test]
+    fn test_cancellation_config() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+
+        let invalid = CancellationConfig {
+            min_displacement: -1.0,
+            ..Default::default()
+        };
+        assert!(predictor.set_cancellation_config(invalid).is_err());
+
+        // Without hysteresis or a jitter threshold a single step back is a reversal
+        let twitchy = CancellationConfig {
+            min_displacement: 0.0,
+            hysteresis_samples: 1,
+            ..Default::default()
+        };
+        predictor.set_cancellation_config(twitchy).unwrap();
+        assert_eq!(predictor.cancellation_config(), twitchy);
+
+        let _ = predictor.add_touch_point(0.0, 0.0, 0.0);
+        let _ = predictor.add_touch_point(10.0, 0.0, 10.0);
+        let _ = predictor.add_touch_point(20.0, 0.0, 20.0);
+        let _ = predictor.add_touch_point(17.0, 0.0, 30.0);
+        assert_eq!(predictor.cancellation(), Some(CancellationReason::DirectionReversal));
     }
 
     #[test]
@@ -416,6 +1601,36 @@ mod tests {
         assert_eq!(predictor.buffer_size(), 20);
     }
 
+    #[test]
+    fn test_set_physics_config() {
+        let config = PhysicsConfig::default();
+        let mut predictor = GesturePredictor::new(config).unwrap();
+
+        for i in 0..6 {
+            let _ = predictor.add_touch_point(i as f64 * 20.0, 0.0, i as f64 * 20.0);
+        }
+        let before = predictor.predict().unwrap();
+
+        // Invalid config is rejected and leaves the predictor untouched
+        let invalid = PhysicsConfig {
+            deceleration_rate: 0.0,
+            ..Default::default()
+        };
+        assert!(predictor.set_physics_config(invalid).is_err());
+        assert_eq!(predictor.physics_config().deceleration_rate, config.deceleration_rate);
+
+        // Lower deceleration keeps the buffer and predicts further
+        let slower = PhysicsConfig {
+            deceleration_rate: 750.0,
+            ..Default::default()
+        };
+        predictor.set_physics_config(slower).unwrap();
+        assert_eq!(predictor.point_count(), 6);
+
+        let after = predictor.predict().unwrap();
+        assert!(after.position.x > before.position.x);
+    }
+
     #[test]
     fn test_reset() {
         let config = PhysicsConfig::default();
@@ -0,0 +1,521 @@
+//! Python module for offline analysis and tuning, so recorded gestures are
+//! evaluated with the production predictor rather than a reimplementation.
+//! Build with `maturin build --release` (see pyproject.toml).
+//!
+//! Sample arrays are accepted as any sequence of floats, including NumPy
+//! arrays, and failures raise `PredictorError(message, code)` with the same
+//! code the native bridges report.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::batch::{self, GestureBatch};
+use crate::config::{PredictorConfig, Preset};
+use crate::error::PredictorError;
+use crate::physics::PhysicsConfig;
+use crate::predictor::GesturePredictor;
+use crate::types::{Point2D, Prediction};
+
+mod exceptions {
+    pyo3::create_exception!(
+        swipe_predictor,
+        PredictorError,
+        pyo3::exceptions::PyException,
+        "Raised with `(message, code)` when the predictor rejects a call"
+    );
+}
+
+fn to_py_err(error: PredictorError) -> PyErr {
+    exceptions::PredictorError::new_err((error.to_string(), error.code()))
+}
+
+/// Physics parameters, in points and milliseconds
+#[pyclass(
+    name = "PhysicsConfig",
+    module = "swipe_predictor",
+    frozen,
+    get_all,
+    from_py_object
+)]
+#[derive(Debug, Clone, Copy)]
+pub struct PyPhysicsConfig {
+    deceleration_rate: f64,
+    min_velocity_threshold: f64,
+    min_gesture_time_ms: f64,
+}
+
+#[pymethods]
+impl PyPhysicsConfig {
+    /// Unset parameters take their defaults
+    #[new]
+    #[pyo3(signature = (deceleration_rate=None, min_velocity_threshold=None, min_gesture_time_ms=None))]
+    fn new(
+        deceleration_rate: Option<f64>,
+        min_velocity_threshold: Option<f64>,
+        min_gesture_time_ms: Option<f64>,
+    ) -> PyResult<Self> {
+        let default = PhysicsConfig::default();
+        let config = PhysicsConfig::new(
+            deceleration_rate.unwrap_or(default.deceleration_rate),
+            min_velocity_threshold.unwrap_or(default.min_velocity_threshold),
+            min_gesture_time_ms.unwrap_or(default.min_gesture_time_ms),
+        )
+        .map_err(to_py_err)?;
+        Ok(config.into())
+    }
+
+    /// One of "ios", "android", "ios_fast" or "custom"
+    #[staticmethod]
+    fn preset(name: &str) -> PyResult<Self> {
+        let preset = match name {
+            "ios" => Preset::Ios,
+            "android" => Preset::Android,
+            "ios_fast" => Preset::IosFast,
+            "custom" => Preset::Custom,
+            _ => return Err(PyValueError::new_err(format!("unknown preset {name:?}"))),
+        };
+        Ok(preset.physics().into())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PhysicsConfig(deceleration_rate={}, min_velocity_threshold={}, min_gesture_time_ms={})",
+            self.deceleration_rate, self.min_velocity_threshold, self.min_gesture_time_ms
+        )
+    }
+}
+
+impl From<PhysicsConfig> for PyPhysicsConfig {
+    fn from(config: PhysicsConfig) -> Self {
+        Self {
+            deceleration_rate: config.deceleration_rate,
+            min_velocity_threshold: config.min_velocity_threshold,
+            min_gesture_time_ms: config.min_gesture_time_ms,
+        }
+    }
+}
+
+impl From<PyPhysicsConfig> for PhysicsConfig {
+    fn from(config: PyPhysicsConfig) -> Self {
+        Self {
+            deceleration_rate: config.deceleration_rate,
+            min_velocity_threshold: config.min_velocity_threshold,
+            min_gesture_time_ms: config.min_gesture_time_ms,
+        }
+    }
+}
+
+/// Where the gesture is predicted to come to rest
+#[pyclass(
+    name = "Prediction",
+    module = "swipe_predictor",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone, Copy)]
+pub struct PyPrediction {
+    x: f64,
+    y: f64,
+    confidence: f64,
+}
+
+#[pymethods]
+impl PyPrediction {
+    fn __repr__(&self) -> String {
+        format!(
+            "Prediction(x={}, y={}, confidence={})",
+            self.x, self.y, self.confidence
+        )
+    }
+}
+
+impl From<Prediction> for PyPrediction {
+    fn from(prediction: Prediction) -> Self {
+        Self {
+            x: prediction.position.x,
+            y: prediction.position.y,
+            confidence: prediction.confidence,
+        }
+    }
+}
+
+fn new_predictor(
+    physics: Option<PyPhysicsConfig>,
+    buffer_size: Option<usize>,
+) -> PyResult<GesturePredictor> {
+    let physics = physics.map(PhysicsConfig::from).unwrap_or_default();
+    match buffer_size {
+        Some(buffer_size) => GesturePredictor::with_buffer_size(physics, buffer_size),
+        None => GesturePredictor::new(physics),
+    }
+    .map_err(to_py_err)
+}
+
+/// `GesturePredictor`, fed one sample at a time
+#[pyclass(name = "GesturePredictor", module = "swipe_predictor")]
+pub struct PyGesturePredictor {
+    inner: GesturePredictor,
+}
+
+#[pymethods]
+impl PyGesturePredictor {
+    #[new]
+    #[pyo3(signature = (physics=None, buffer_size=None))]
+    fn new(physics: Option<PyPhysicsConfig>, buffer_size: Option<usize>) -> PyResult<Self> {
+        Ok(Self {
+            inner: new_predictor(physics, buffer_size)?,
+        })
+    }
+
+    #[getter]
+    fn physics(&self) -> PyPhysicsConfig {
+        self.inner.physics_config().into()
+    }
+
+    /// Start a new gesture from any state, discarding the previous one
+    fn begin(&mut self, x: f64, y: f64, timestamp_ms: f64) -> PyResult<()> {
+        self.inner.begin(x, y, timestamp_ms).map_err(to_py_err)
+    }
+
+    fn update(&mut self, x: f64, y: f64, timestamp_ms: f64) -> PyResult<()> {
+        self.inner.update(x, y, timestamp_ms).map_err(to_py_err)
+    }
+
+    fn end(&mut self, release_time_ms: f64) -> PyResult<()> {
+        self.inner.end(release_time_ms).map_err(to_py_err)
+    }
+
+    fn cancel(&mut self) -> PyResult<()> {
+        self.inner.cancel().map_err(to_py_err)
+    }
+
+    fn add_touch_point(&mut self, x: f64, y: f64, timestamp_ms: f64) -> PyResult<()> {
+        self.inner
+            .add_touch_point(x, y, timestamp_ms)
+            .map_err(to_py_err)
+    }
+
+    fn predict(&self) -> PyResult<PyPrediction> {
+        self.inner.predict().map(Into::into).map_err(to_py_err)
+    }
+
+    fn detect_cancellation(&self) -> bool {
+        self.inner.detect_cancellation()
+    }
+
+    /// "idle", "active", "ended" or "cancelled"
+    #[getter]
+    fn state(&self) -> String {
+        self.inner.state().to_string()
+    }
+
+    #[getter]
+    fn point_count(&self) -> usize {
+        self.inner.point_count()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+/// Prediction after each sample of a recorded gesture. Samples the predictor
+/// rejects are skipped, as the native bridges do.
+fn trajectory(
+    predictor: &mut GesturePredictor,
+    xs: &[f64],
+    ys: &[f64],
+    ts: &[f64],
+) -> Vec<Option<Prediction>> {
+    xs.iter()
+        .zip(ys)
+        .zip(ts)
+        .map(|((&x, &y), &t)| {
+            let _ = predictor.add_touch_point(x, y, t);
+            predictor.predict().ok()
+        })
+        .collect()
+}
+
+fn check_lengths(xs: &[f64], ys: &[f64], ts: &[f64]) -> PyResult<()> {
+    if xs.len() != ys.len() || xs.len() != ts.len() {
+        return Err(PyValueError::new_err(format!(
+            "xs, ys and ts must have the same length, got {}, {} and {}",
+            xs.len(),
+            ys.len(),
+            ts.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Predict after every sample of one recorded gesture, to see how the
+/// prediction evolves; `predict_gestures` batches many gestures instead.
+///
+/// Returns `(xs, ys, confidences)`, NaN where there is no prediction yet.
+#[pyfunction]
+#[pyo3(signature = (xs, ys, ts, physics=None, buffer_size=None))]
+fn predict_trajectory(
+    py: Python<'_>,
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    ts: Vec<f64>,
+    physics: Option<PyPhysicsConfig>,
+    buffer_size: Option<usize>,
+) -> PyResult<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+    check_lengths(&xs, &ys, &ts)?;
+    let mut predictor = new_predictor(physics, buffer_size)?;
+    let predictions = py.detach(|| trajectory(&mut predictor, &xs, &ys, &ts));
+
+    let mut columns = (
+        Vec::with_capacity(predictions.len()),
+        Vec::with_capacity(predictions.len()),
+        Vec::with_capacity(predictions.len()),
+    );
+    for prediction in predictions {
+        let (x, y, confidence) = prediction.map_or((f64::NAN, f64::NAN, f64::NAN), |p| {
+            (p.position.x, p.position.y, p.confidence)
+        });
+        columns.0.push(x);
+        columns.1.push(y);
+        columns.2.push(confidence);
+    }
+    Ok(columns)
+}
+
+/// Predict for many recorded gestures at once. Gesture `i`'s samples are
+/// `xs[offsets[i]:offsets[i + 1]]`, and likewise for `ys` and `ts`.
+///
+/// Returns `(xs, ys, confidences, status)` with a row per gesture and a column
+/// per cut time (see `batch::predict_batch`), NaN and a non-zero
+/// `PredictorError` code where there is no prediction.
+#[pyfunction]
+#[pyo3(signature = (offsets, xs, ys, ts, cut_times_ms=None, physics=None, buffer_size=None))]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn predict_gestures(
+    py: Python<'_>,
+    offsets: Vec<usize>,
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    ts: Vec<f64>,
+    cut_times_ms: Option<Vec<f64>>,
+    physics: Option<PyPhysicsConfig>,
+    buffer_size: Option<usize>,
+) -> PyResult<(Vec<f64>, Vec<f64>, Vec<f64>, Vec<i32>)> {
+    let gestures = GestureBatch::new(&offsets, &xs, &ys, &ts).map_err(to_py_err)?;
+    let default = PredictorConfig::default();
+    let config = PredictorConfig {
+        physics: physics.map(PhysicsConfig::from).unwrap_or(default.physics),
+        buffer_size: buffer_size.unwrap_or(default.buffer_size),
+        ..default
+    };
+    let cut_times_ms = cut_times_ms.unwrap_or_default();
+
+    #[cfg(feature = "rayon")]
+    let predict = batch::par_predict_batch;
+    #[cfg(not(feature = "rayon"))]
+    let predict = batch::predict_batch;
+    let predictions = py
+        .detach(|| predict(&gestures, &config, &cut_times_ms))
+        .map_err(to_py_err)?;
+    Ok((
+        predictions.xs,
+        predictions.ys,
+        predictions.confidences,
+        predictions.status,
+    ))
+}
+
+/// How close a recorded gesture's predictions came to where it actually stopped
+#[pyclass(
+    name = "Evaluation",
+    module = "swipe_predictor",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone)]
+pub struct PyEvaluation {
+    /// Distance from each sample's prediction to the resting position, NaN
+    /// where there was no prediction
+    errors: Vec<f64>,
+    /// Error of the prediction at the last sample
+    final_error: f64,
+    /// Mean error over the samples that had a prediction
+    mean_error: f64,
+    /// Fraction of samples that had a prediction
+    coverage: f64,
+}
+
+impl PyEvaluation {
+    fn new(predictions: &[Option<Prediction>], rest: Point2D) -> Self {
+        let errors: Vec<f64> = predictions
+            .iter()
+            .map(|prediction| prediction.map_or(f64::NAN, |p| p.position.distance_to(&rest)))
+            .collect();
+        let predicted: Vec<f64> = errors.iter().copied().filter(|e| !e.is_nan()).collect();
+
+        Self {
+            final_error: errors.last().copied().unwrap_or(f64::NAN),
+            mean_error: if predicted.is_empty() {
+                f64::NAN
+            } else {
+                predicted.iter().sum::<f64>() / predicted.len() as f64
+            },
+            coverage: if errors.is_empty() {
+                0.0
+            } else {
+                predicted.len() as f64 / errors.len() as f64
+            },
+            errors,
+        }
+    }
+}
+
+#[pymethods]
+impl PyEvaluation {
+    fn __repr__(&self) -> String {
+        format!(
+            "Evaluation(final_error={}, mean_error={}, coverage={})",
+            self.final_error, self.mean_error, self.coverage
+        )
+    }
+}
+
+/// Replay a recorded gesture and measure its predictions against the
+/// position `(rest_x, rest_y)` where the content actually came to rest
+#[pyfunction]
+#[pyo3(signature = (xs, ys, ts, rest_x, rest_y, physics=None, buffer_size=None))]
+#[allow(clippy::too_many_arguments)]
+fn evaluate(
+    py: Python<'_>,
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    ts: Vec<f64>,
+    rest_x: f64,
+    rest_y: f64,
+    physics: Option<PyPhysicsConfig>,
+    buffer_size: Option<usize>,
+) -> PyResult<PyEvaluation> {
+    check_lengths(&xs, &ys, &ts)?;
+    let mut predictor = new_predictor(physics, buffer_size)?;
+    let predictions = py.detach(|| trajectory(&mut predictor, &xs, &ys, &ts));
+    Ok(PyEvaluation::new(
+        &predictions,
+        Point2D::new(rest_x, rest_y),
+    ))
+}
+
+#[pymodule]
+#[pyo3(name = "swipe_predictor")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyPhysicsConfig>()?;
+    m.add_class::<PyPrediction>()?;
+    m.add_class::<PyGesturePredictor>()?;
+    m.add_class::<PyEvaluation>()?;
+    m.add_function(wrap_pyfunction!(predict_trajectory, m)?)?;
+    m.add_function(wrap_pyfunction!(predict_gestures, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate, m)?)?;
+    m.add(
+        "PredictorError",
+        m.py().get_type::<exceptions::PredictorError>(),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trajectory_matches_predictor() {
+        let xs: Vec<f64> = (0..8).map(|i| i as f64 * 20.0).collect();
+        let ys = vec![0.0; 8];
+        let ts: Vec<f64> = (0..8).map(|i| i as f64 * 16.0).collect();
+
+        let mut predictor = GesturePredictor::new(PhysicsConfig::default()).unwrap();
+        let predictions = trajectory(&mut predictor, &xs, &ys, &ts);
+        assert!(predictions[0].is_none());
+        let last = predictions.last().unwrap().unwrap();
+        assert_eq!(last.position, predictor.predict().unwrap().position);
+
+        let rest = Point2D::new(last.position.x + 30.0, 40.0);
+        let evaluation = PyEvaluation::new(&predictions, rest);
+        assert_eq!(evaluation.final_error, 50.0);
+        assert!(evaluation.errors[0].is_nan());
+        assert!(evaluation.coverage > 0.5 && evaluation.coverage < 1.0);
+        assert!(evaluation.mean_error.is_finite());
+    }
+
+    #[test]
+    fn test_module_functions() {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "swipe_predictor").unwrap();
+            python_module(&module).unwrap();
+
+            let xs: Vec<f64> = (0..8).map(|i| i as f64 * 20.0).collect();
+            let ts: Vec<f64> = (0..8).map(|i| i as f64 * 16.0).collect();
+            let (px, _, confidences): (Vec<f64>, Vec<f64>, Vec<f64>) = module
+                .getattr("predict_trajectory")
+                .unwrap()
+                .call1((xs.clone(), vec![0.0; 8], ts.clone()))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(px[0].is_nan());
+            assert!(px[7] > xs[7] && confidences[7] > 0.0);
+
+            let error = module
+                .getattr("predict_trajectory")
+                .unwrap()
+                .call1((xs.clone(), vec![0.0; 7], ts.clone()))
+                .unwrap_err();
+            assert!(error.is_instance_of::<PyValueError>(py));
+
+            // A moving gesture and a single tap, predicted at release
+            let gesture_xs: Vec<f64> = xs.iter().copied().chain([5.0]).collect();
+            let gesture_ts: Vec<f64> = ts.iter().copied().chain([0.0]).collect();
+            let (gx, _, gconfidences, status): (Vec<f64>, Vec<f64>, Vec<f64>, Vec<i32>) = module
+                .getattr("predict_gestures")
+                .unwrap()
+                .call1((vec![0, 8, 9], gesture_xs.clone(), vec![0.0; 9], gesture_ts.clone()))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(status.len(), 2);
+            assert_eq!(status[0], 0);
+            assert_eq!(gx[0], px[7]);
+            assert_eq!(gconfidences[0], confidences[7]);
+            assert_ne!(status[1], 0);
+            assert!(gx[1].is_nan());
+
+            let error = module
+                .getattr("predict_gestures")
+                .unwrap()
+                .call1((vec![0, 8, 9], gesture_xs, vec![0.0; 9], gesture_ts, vec![-1.0]))
+                .unwrap_err();
+            assert!(error.is_instance_of::<exceptions::PredictorError>(py));
+
+            let evaluation = module
+                .getattr("evaluate")
+                .unwrap()
+                .call1((xs.clone(), vec![0.0; 8], ts.clone(), px[7] + 30.0, 40.0))
+                .unwrap();
+            let final_error: f64 = evaluation.getattr("final_error").unwrap().extract().unwrap();
+            let coverage: f64 = evaluation.getattr("coverage").unwrap().extract().unwrap();
+            let errors: Vec<f64> = evaluation.getattr("errors").unwrap().extract().unwrap();
+            assert_eq!(final_error, 50.0);
+            assert!(coverage > 0.5 && coverage < 1.0);
+            assert!(errors[0].is_nan());
+
+            let error = module
+                .getattr("evaluate")
+                .unwrap()
+                .call1((xs, vec![0.0; 8], &ts[..7], 0.0, 0.0))
+                .unwrap_err();
+            assert!(error.is_instance_of::<PyValueError>(py));
+        });
+    }
+}
@@ -0,0 +1,369 @@
+// Bridge registry - maps generational i32 IDs to FFI contexts and predictors
+// for the iOS and Android bridges, which can't hand raw pointers to JS
+use crate::error::{PredictorError, Result};
+use crate::ffi::{SwipePredictorContext, SwipePredictorHandle};
+use crate::handle::{HandleId, HandleMap, MAX_SLOTS};
+use crate::physics::PhysicsConfig;
+use crate::types::DisplayScale;
+
+// Wrapper to make the raw pointer Send + Sync
+struct ContextPtr(*mut SwipePredictorContext);
+unsafe impl Send for ContextPtr {}
+unsafe impl Sync for ContextPtr {}
+
+// Wrapper for handle pointers to make them Send + Sync
+struct HandlePtr(*mut SwipePredictorHandle);
+unsafe impl Send for HandlePtr {}
+unsafe impl Sync for HandlePtr {}
+
+struct PredictorEntry {
+    handle: HandlePtr,
+    context_id: HandleId,
+}
+
+/// Contexts and predictors owned by a bridge.
+///
+/// Each context has its own physics configuration, so separate root views or
+/// JS runtimes can each create one. Predictor IDs are global to the registry
+/// and remember which context they belong to. The legacy single-context API
+/// is served by a "default" context.
+pub(crate) struct BridgeRegistry {
+    contexts: HandleMap<ContextPtr>,
+    predictors: HandleMap<PredictorEntry>,
+    default_context: Option<HandleId>,
+}
+
+impl BridgeRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            contexts: HandleMap::new(),
+            predictors: HandleMap::new(),
+            default_context: None,
+        }
+    }
+
+    pub(crate) fn create_context(&mut self, physics_config: PhysicsConfig) -> Result<HandleId> {
+        physics_config.validate()?;
+
+        let ctx = crate::ffi::swipe_predictor_context_create(
+            physics_config.deceleration_rate,
+            physics_config.min_velocity_threshold,
+            physics_config.min_gesture_time_ms,
+        );
+        if ctx.is_null() {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "physics_config".into(),
+                value: physics_config.deceleration_rate,
+                reason: "context creation failed".into(),
+            });
+        }
+
+        match self.contexts.insert(ContextPtr(ctx)) {
+            Some(id) => Ok(id),
+            None => {
+                crate::ffi::swipe_predictor_context_destroy(ctx);
+                Err(PredictorError::HandleLimitReached {
+                    limit: MAX_SLOTS as usize,
+                })
+            }
+        }
+    }
+
+    /// Destroy a context together with every predictor created in it
+    pub(crate) fn destroy_context(&mut self, context_id: HandleId) -> Result<()> {
+        let ctx = self.contexts.remove(context_id)?;
+
+        let orphans: Vec<HandleId> = self
+            .predictors
+            .iter()
+            .filter(|(_, entry)| entry.context_id == context_id)
+            .map(|(id, _)| id)
+            .collect();
+        for id in orphans {
+            if let Ok(entry) = self.predictors.remove(id) {
+                crate::ffi::swipe_predictor_destroy(entry.handle.0);
+            }
+        }
+
+        crate::ffi::swipe_predictor_context_destroy(ctx.0);
+
+        if self.default_context == Some(context_id) {
+            self.default_context = None;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn context_ids(&self) -> Vec<HandleId> {
+        self.contexts.iter().map(|(id, _)| id).collect()
+    }
+
+    pub(crate) fn update_context_config(
+        &mut self,
+        context_id: HandleId,
+        physics_config: PhysicsConfig,
+        apply_to_existing: bool,
+    ) -> Result<()> {
+        physics_config.validate()?;
+        let ctx = self.contexts.get(context_id)?;
+
+        let result = crate::ffi::swipe_predictor_context_update_config(
+            ctx.0,
+            physics_config.deceleration_rate,
+            physics_config.min_velocity_threshold,
+            physics_config.min_gesture_time_ms,
+            i32::from(apply_to_existing),
+        );
+        if result != 1 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "physics_config".into(),
+                value: physics_config.deceleration_rate,
+                reason: "context update failed".into(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Set the display density of a context, in physical pixels per point.
+    /// Fails while any of its predictors has samples buffered.
+    pub(crate) fn set_display_scale(&mut self, context_id: HandleId, pixels_per_point: f64) -> Result<()> {
+        DisplayScale::new(pixels_per_point)?;
+        let ctx = self.contexts.get(context_id)?;
+
+        let result = crate::ffi::swipe_predictor_context_set_display_scale(ctx.0, pixels_per_point);
+        if result != 0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "display_scale".into(),
+                value: pixels_per_point,
+                reason: "can't change while samples are buffered".into(),
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn create_predictor(&mut self, context_id: HandleId) -> Result<HandleId> {
+        let ctx = self.contexts.get(context_id)?;
+
+        let handle = crate::ffi::swipe_predictor_create_in_context(ctx.0);
+        if handle.is_null() {
+            return Err(PredictorError::HandleLimitReached {
+                limit: crate::ffi::MAX_PREDICTORS,
+            });
+        }
+
+        let entry = PredictorEntry {
+            handle: HandlePtr(handle),
+            context_id,
+        };
+        match self.predictors.insert(entry) {
+            Some(id) => Ok(id),
+            None => {
+                crate::ffi::swipe_predictor_destroy(handle);
+                Err(PredictorError::HandleLimitReached {
+                    limit: MAX_SLOTS as usize,
+                })
+            }
+        }
+    }
+
+    pub(crate) fn predictor(&self, predictor_id: HandleId) -> Result<*mut SwipePredictorHandle> {
+        self.predictors.get(predictor_id).map(|entry| entry.handle.0)
+    }
+
+    pub(crate) fn remove_predictor(&mut self, predictor_id: HandleId) -> Result<()> {
+        let entry = self.predictors.remove(predictor_id)?;
+        crate::ffi::swipe_predictor_destroy(entry.handle.0);
+        Ok(())
+    }
+
+    pub(crate) fn default_context(&self) -> Option<HandleId> {
+        self.default_context
+    }
+
+    /// Replace the default context used by the legacy API.
+    /// The previous default context and its predictors are destroyed; other
+    /// contexts are untouched. If the new context can't be created, nothing
+    /// changes.
+    pub(crate) fn reset_default_context(&mut self, physics_config: PhysicsConfig) -> Result<HandleId> {
+        let id = self.create_context(physics_config)?;
+
+        if let Some(old) = self.default_context.replace(id) {
+            let _ = self.destroy_context(old);
+        }
+        Ok(id)
+    }
+
+    /// Destroy every predictor and context
+    pub(crate) fn clear(&mut self) {
+        for entry in self.predictors.drain() {
+            crate::ffi::swipe_predictor_destroy(entry.handle.0);
+        }
+        for ctx in self.contexts.drain() {
+            crate::ffi::swipe_predictor_context_destroy(ctx.0);
+        }
+        self.default_context = None;
+    }
+}
+
+impl Drop for BridgeRegistry {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_and_predict(registry: &BridgeRegistry, predictor_id: HandleId) -> f64 {
+        let handle = registry.predictor(predictor_id).unwrap();
+        for i in 0..6 {
+            crate::ffi::swipe_predictor_add_point(handle, i as f64 * 20.0, 0.0, i as f64 * 20.0);
+        }
+
+        let mut x = 0.0;
+        let mut y = 0.0;
+        let mut conf = 0.0;
+        let result = crate::ffi::swipe_predictor_get_prediction(handle, &mut x, &mut y, &mut conf);
+        assert_eq!(result, 1);
+        x
+    }
+
+    #[test]
+    fn test_independent_contexts() {
+        let mut registry = BridgeRegistry::new();
+        let fast = registry.create_context(PhysicsConfig::default()).unwrap();
+        let slow = registry
+            .create_context(PhysicsConfig::new(750.0, 50.0, 30.0).unwrap())
+            .unwrap();
+        assert_eq!(registry.context_ids().len(), 2);
+
+        let p_fast = registry.create_predictor(fast).unwrap();
+        let p_slow = registry.create_predictor(slow).unwrap();
+
+        // Each context keeps its own configuration
+        assert!(feed_and_predict(&registry, p_slow) > feed_and_predict(&registry, p_fast));
+    }
+
+    #[test]
+    fn test_destroy_context_releases_its_predictors() {
+        let mut registry = BridgeRegistry::new();
+        let a = registry.create_context(PhysicsConfig::default()).unwrap();
+        let b = registry.create_context(PhysicsConfig::default()).unwrap();
+        let pa = registry.create_predictor(a).unwrap();
+        let pb = registry.create_predictor(b).unwrap();
+
+        registry.destroy_context(a).unwrap();
+
+        assert_eq!(registry.context_ids(), vec![b]);
+        assert!(matches!(registry.predictor(pa), Err(PredictorError::StaleHandle { .. })));
+        assert!(registry.predictor(pb).is_ok());
+        assert!(matches!(
+            registry.create_predictor(a),
+            Err(PredictorError::StaleHandle { .. })
+        ));
+        assert!(matches!(
+            registry.destroy_context(a),
+            Err(PredictorError::StaleHandle { .. })
+        ));
+    }
+
+    #[test]
+    fn test_reset_default_context_keeps_other_contexts() {
+        let mut registry = BridgeRegistry::new();
+        let other = registry.create_context(PhysicsConfig::default()).unwrap();
+        let p_other = registry.create_predictor(other).unwrap();
+
+        let first = registry.reset_default_context(PhysicsConfig::default()).unwrap();
+        let p_default = registry.create_predictor(first).unwrap();
+
+        let second = registry.reset_default_context(PhysicsConfig::default()).unwrap();
+        assert_eq!(registry.default_context(), Some(second));
+        assert!(matches!(
+            registry.predictor(p_default),
+            Err(PredictorError::StaleHandle { .. })
+        ));
+        assert!(registry.predictor(p_other).is_ok());
+
+        // Invalid config leaves the current default in place
+        let invalid = PhysicsConfig {
+            deceleration_rate: -1.0,
+            ..Default::default()
+        };
+        assert!(registry.reset_default_context(invalid).is_err());
+        assert_eq!(registry.default_context(), Some(second));
+    }
+
+    #[test]
+    fn test_reset_default_context_keeps_default_when_registry_full() {
+        let mut registry = BridgeRegistry::new();
+        let default = registry.reset_default_context(PhysicsConfig::default()).unwrap();
+        let p_default = registry.create_predictor(default).unwrap();
+        while registry.create_context(PhysicsConfig::default()).is_ok() {}
+
+        assert!(matches!(
+            registry.reset_default_context(PhysicsConfig::default()),
+            Err(PredictorError::HandleLimitReached { .. })
+        ));
+        assert_eq!(registry.default_context(), Some(default));
+        assert!(registry.predictor(p_default).is_ok());
+    }
+
+    #[test]
+    fn test_update_context_config() {
+        let mut registry = BridgeRegistry::new();
+        let ctx = registry.create_context(PhysicsConfig::default()).unwrap();
+        let before = {
+            let p = registry.create_predictor(ctx).unwrap();
+            feed_and_predict(&registry, p)
+        };
+
+        let slower = PhysicsConfig::new(750.0, 50.0, 30.0).unwrap();
+        registry.update_context_config(ctx, slower, false).unwrap();
+
+        let p = registry.create_predictor(ctx).unwrap();
+        assert!(feed_and_predict(&registry, p) > before);
+
+        let invalid = PhysicsConfig {
+            deceleration_rate: 0.0,
+            ..Default::default()
+        };
+        assert!(registry.update_context_config(ctx, invalid, true).is_err());
+    }
+
+    #[test]
+    fn test_set_display_scale() {
+        let mut registry = BridgeRegistry::new();
+        let ctx = registry.create_context(PhysicsConfig::default()).unwrap();
+        let p = registry.create_predictor(ctx).unwrap();
+        let in_points = feed_and_predict(&registry, p);
+
+        // Buffered samples keep the scale they were converted at
+        assert!(registry.set_display_scale(ctx, 2.0).is_err());
+        crate::ffi::swipe_predictor_reset(registry.predictor(p).unwrap());
+        registry.set_display_scale(ctx, 2.0).unwrap();
+
+        // The same pixel swipe covers half the points, and is reported in pixels
+        let in_pixels = feed_and_predict(&registry, p);
+        assert!(in_pixels < in_points);
+        assert!(registry.set_display_scale(ctx, f64::NAN).is_err());
+
+        registry.destroy_context(ctx).unwrap();
+        assert!(matches!(
+            registry.set_display_scale(ctx, 2.0),
+            Err(PredictorError::StaleHandle { .. })
+        ));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut registry = BridgeRegistry::new();
+        let ctx = registry.reset_default_context(PhysicsConfig::default()).unwrap();
+        let p = registry.create_predictor(ctx).unwrap();
+
+        registry.clear();
+
+        assert!(registry.context_ids().is_empty());
+        assert_eq!(registry.default_context(), None);
+        assert!(registry.predictor(p).is_err());
+    }
+}
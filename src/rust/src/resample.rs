@@ -0,0 +1,284 @@
+use alloc::collections::VecDeque;
+
+use crate::buffer::SampleBuffer;
+use crate::error::{PredictorError, Result};
+use crate::types::{Point2D, Timestamp, TouchPoint};
+
+/// Highest supported output rate, well above any touch digitizer
+const MAX_RATE_HZ: f64 = 1000.0;
+
+/// How positions between two samples are estimated
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpolation {
+    #[default]
+    Linear = 0,
+    /// Cubic through the neighbouring samples, with tangents taken over the
+    /// actual time between them so irregular spacing doesn't distort speed
+    CatmullRom = 1,
+}
+
+impl TryFrom<i32> for Interpolation {
+    type Error = PredictorError;
+
+    fn try_from(value: i32) -> core::result::Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Linear),
+            1 => Ok(Self::CatmullRom),
+            _ => Err(PredictorError::InvalidConfiguration {
+                field: "interpolation".into(),
+                value: value as f64,
+                reason: "unknown interpolation".into(),
+            }),
+        }
+    }
+}
+
+/// Uniform rate that touch samples are resampled to before analysis
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedResampleConfig"))]
+pub struct ResampleConfig {
+    /// Output rate in Hz
+    pub rate_hz: f64,
+    pub interpolation: Interpolation,
+}
+
+impl Default for ResampleConfig {
+    fn default() -> Self {
+        Self {
+            rate_hz: 120.0,
+            interpolation: Interpolation::Linear,
+        }
+    }
+}
+
+/// A deserialized `ResampleConfig` that hasn't been validated yet
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedResampleConfig {
+    rate_hz: f64,
+    interpolation: Interpolation,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedResampleConfig> for ResampleConfig {
+    type Error = PredictorError;
+
+    fn try_from(config: UncheckedResampleConfig) -> Result<Self> {
+        let config = Self {
+            rate_hz: config.rate_hz,
+            interpolation: config.interpolation,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl ResampleConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !(self.rate_hz > 0.0 && self.rate_hz <= MAX_RATE_HZ) {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "rate_hz".into(),
+                value: self.rate_hz,
+                reason: "must be in (0, 1000]".into(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn period_ms(&self) -> f64 {
+        1000.0 / self.rate_hz
+    }
+}
+
+/// Resample `samples` (in timestamp order) to points spaced uniformly at the
+/// configured rate, ending exactly at the latest sample.
+///
+/// Only the most recent `max_points` are produced, so the resampled window
+/// covers the same span of time whatever rate the input arrived at.
+pub fn resample(
+    samples: &[TouchPoint],
+    config: &ResampleConfig,
+    max_points: usize,
+) -> VecDeque<TouchPoint> {
+    let mut resampled = VecDeque::with_capacity(max_points);
+    resample_into(samples, config, max_points, &mut resampled);
+    resampled
+}
+
+/// Like `resample`, replacing the contents of `resampled` so a buffer with
+/// room for `max_points` is reused without allocating
+pub fn resample_into<B: SampleBuffer>(
+    samples: &[TouchPoint],
+    config: &ResampleConfig,
+    max_points: usize,
+    resampled: &mut B,
+) {
+    resampled.clear();
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return;
+    };
+
+    let period = config.period_ms();
+    let time = |k: usize| last.timestamp.as_millis() - k as f64 * period;
+    let mut count = 0;
+    while count < max_points && time(count) >= first.timestamp.as_millis() {
+        count += 1;
+    }
+
+    // Oldest first, walking forward to the segment containing each time
+    let last_segment = samples.len().saturating_sub(2);
+    let mut segment = 0;
+    for k in (0..count).rev() {
+        let t = time(k);
+        while segment < last_segment && samples[segment + 1].timestamp.as_millis() <= t {
+            segment += 1;
+        }
+
+        let position = match config.interpolation {
+            Interpolation::Linear => linear(samples, segment, t),
+            Interpolation::CatmullRom => catmull_rom(samples, segment, t),
+        };
+        resampled.push_back(TouchPoint {
+            position,
+            timestamp: Timestamp::new(t),
+        });
+    }
+}
+
+fn linear(samples: &[TouchPoint], i: usize, t: f64) -> Point2D {
+    let start = samples[i];
+    let Some(end) = samples.get(i + 1) else {
+        return start.position;
+    };
+
+    let span = end.timestamp - start.timestamp;
+    if span <= 0.0 {
+        return end.position;
+    }
+    let s = (t - start.timestamp.as_millis()) / span;
+    start.position + (end.position - start.position) * s
+}
+
+fn catmull_rom(samples: &[TouchPoint], i: usize, t: f64) -> Point2D {
+    let start = samples[i];
+    let Some(end) = samples.get(i + 1) else {
+        return start.position;
+    };
+
+    let span = end.timestamp - start.timestamp;
+    if span <= 0.0 {
+        return end.position;
+    }
+    let s = (t - start.timestamp.as_millis()) / span;
+
+    // Cubic Hermite basis
+    let s2 = s * s;
+    let s3 = s2 * s;
+    let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+    let h10 = s3 - 2.0 * s2 + s;
+    let h01 = -2.0 * s3 + 3.0 * s2;
+    let h11 = s3 - s2;
+
+    start.position * h00
+        + tangent(samples, i) * (h10 * span)
+        + end.position * h01
+        + tangent(samples, i + 1) * (h11 * span)
+}
+
+/// Velocity in points/ms at sample `j`, from its neighbours (one-sided at the ends)
+fn tangent(samples: &[TouchPoint], j: usize) -> Point2D {
+    let before = samples[j.saturating_sub(1)];
+    let after = samples[(j + 1).min(samples.len() - 1)];
+
+    let span = after.timestamp - before.timestamp;
+    if span > 0.0 {
+        (after.position - before.position) / span
+    } else {
+        Point2D::new(0.0, 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(points: &[(f64, f64)]) -> Vec<TouchPoint> {
+        points
+            .iter()
+            .map(|&(x, t)| TouchPoint::new(x, 0.0, t).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_uniform_output_from_irregular_input() {
+        // 1 px/ms, arriving with a dropped frame and a late event
+        let input = samples(&[(0.0, 0.0), (16.0, 16.0), (50.0, 50.0), (58.0, 58.0), (70.0, 70.0)]);
+
+        for interpolation in [Interpolation::Linear, Interpolation::CatmullRom] {
+            let config = ResampleConfig {
+                rate_hz: 100.0,
+                interpolation,
+            };
+            let resampled = resample(&input, &config, 100);
+
+            assert_eq!(resampled.len(), 8);
+            assert_eq!(resampled.back().unwrap().timestamp.as_millis(), 70.0);
+            for point in &resampled {
+                assert!((point.position.x - point.timestamp.as_millis()).abs() < 1e-9);
+            }
+            for pair in resampled.iter().collect::<Vec<_>>().windows(2) {
+                assert!((pair[1].timestamp - pair[0].timestamp - 10.0).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_catmull_rom_follows_curvature() {
+        // x = t²/100, sampled every 20ms
+        let input: Vec<_> = (0..6)
+            .map(|i| {
+                let t = i as f64 * 20.0;
+                TouchPoint::new(t * t / 100.0, 0.0, t).unwrap()
+            })
+            .collect();
+        let config = ResampleConfig {
+            rate_hz: 200.0,
+            interpolation: Interpolation::CatmullRom,
+        };
+
+        // Between samples a chord overshoots a convex curve; the cubic stays closer
+        let error = |interpolation| {
+            let config = ResampleConfig { interpolation, ..config };
+            resample(&input, &config, 100)
+                .iter()
+                .map(|p| (p.position.x - p.timestamp.as_millis().powi(2) / 100.0).abs())
+                .fold(0.0, f64::max)
+        };
+        assert!(error(Interpolation::CatmullRom) < error(Interpolation::Linear));
+    }
+
+    #[test]
+    fn test_window_is_capped() {
+        let input = samples(&[(0.0, 0.0), (100.0, 100.0)]);
+        let resampled = resample(&input, &ResampleConfig::default(), 4);
+        assert_eq!(resampled.len(), 4);
+        assert_eq!(resampled.back().unwrap().position.x, 100.0);
+
+        assert!(resample(&[], &ResampleConfig::default(), 4).is_empty());
+        assert!(ResampleConfig { rate_hz: 0.0, ..Default::default() }.validate().is_err());
+        assert!(Interpolation::try_from(2).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_validates() {
+        let config: ResampleConfig =
+            serde_json::from_str(r#"{"rate_hz":240.0,"interpolation":"CatmullRom"}"#).unwrap();
+        assert_eq!(config.interpolation, Interpolation::CatmullRom);
+        assert!(serde_json::from_str::<ResampleConfig>(r#"{"rate_hz":0.0,"interpolation":"Linear"}"#).is_err());
+    }
+}
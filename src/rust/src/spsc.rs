@@ -0,0 +1,223 @@
+use std::sync::atomic::{fence, AtomicI32, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::physics::PhysicsConfig;
+use crate::predictor::GesturePredictor;
+use crate::types::{Point2D, Prediction};
+
+/// Latest prediction published by a `SampleWriter`
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PredictionSnapshot {
+    /// Number of snapshots published so far; changes whenever a new one is written
+    pub sequence: u64,
+    /// 0 when `prediction` is set, otherwise the `PredictorError::code` explaining
+    /// why there is none
+    pub status: i32,
+    pub prediction: Option<Prediction>,
+}
+
+/// Seqlock holding the latest snapshot.
+///
+/// The writer bumps `seq` to an odd value, stores the fields and bumps it back
+/// to even. Readers retry if `seq` was odd or changed while they read, so a
+/// reader only ever spins for the duration of a single store and neither side
+/// takes a lock. Fields are atomics, so a torn read is discarded rather than UB.
+struct SnapshotCell {
+    seq: AtomicU64,
+    status: AtomicI32,
+    x: AtomicU64,
+    y: AtomicU64,
+    confidence: AtomicU64,
+}
+
+impl SnapshotCell {
+    fn new(status: i32) -> Self {
+        Self {
+            seq: AtomicU64::new(0),
+            status: AtomicI32::new(status),
+            x: AtomicU64::new(0),
+            y: AtomicU64::new(0),
+            confidence: AtomicU64::new(0),
+        }
+    }
+
+    /// Must only be called from the single writer
+    fn write(&self, status: i32, x: f64, y: f64, confidence: f64) {
+        let seq = self.seq.load(Ordering::Relaxed);
+        self.seq.store(seq.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        self.status.store(status, Ordering::Relaxed);
+        self.x.store(x.to_bits(), Ordering::Relaxed);
+        self.y.store(y.to_bits(), Ordering::Relaxed);
+        self.confidence.store(confidence.to_bits(), Ordering::Relaxed);
+
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+    }
+
+    fn read(&self) -> (u64, i32, f64, f64, f64) {
+        loop {
+            let before = self.seq.load(Ordering::Acquire);
+            if before & 1 == 0 {
+                let status = self.status.load(Ordering::Relaxed);
+                let x = f64::from_bits(self.x.load(Ordering::Relaxed));
+                let y = f64::from_bits(self.y.load(Ordering::Relaxed));
+                let confidence = f64::from_bits(self.confidence.load(Ordering::Relaxed));
+
+                fence(Ordering::Acquire);
+                if self.seq.load(Ordering::Relaxed) == before {
+                    return (before / 2, status, x, y, confidence);
+                }
+            }
+            std::hint::spin_loop();
+        }
+    }
+}
+
+/// Create a single-producer/single-consumer predictor.
+///
+/// The `SampleWriter` owns the predictor and republishes the prediction after
+/// every sample; the `PredictionReader` reads the latest one. Neither side
+/// blocks the other, so the reader is safe to poll from a UI thread (e.g. a
+/// Reanimated worklet) while another thread feeds touch samples.
+pub fn split(physics_config: PhysicsConfig) -> Result<(SampleWriter, PredictionReader)> {
+    let predictor = GesturePredictor::new(physics_config)?;
+    Ok(split_predictor(predictor))
+}
+
+/// Like `split`, but reuses an existing predictor and publishes its current prediction
+pub fn split_predictor(predictor: GesturePredictor) -> (SampleWriter, PredictionReader) {
+    let cell = Arc::new(SnapshotCell::new(0));
+    let mut writer = SampleWriter {
+        predictor,
+        cell: Arc::clone(&cell),
+    };
+    writer.publish();
+    (writer, PredictionReader { cell })
+}
+
+/// Producer half of an SPSC predictor. Not `Clone`, so there is only ever one writer.
+pub struct SampleWriter {
+    predictor: GesturePredictor,
+    cell: Arc<SnapshotCell>,
+}
+
+impl SampleWriter {
+    /// Add a sample and publish the resulting prediction.
+    /// Rejected samples leave the published snapshot unchanged.
+    pub fn add_touch_point(&mut self, x: f64, y: f64, timestamp_ms: f64) -> Result<()> {
+        self.predictor.add_touch_point(x, y, timestamp_ms)?;
+        self.publish();
+        Ok(())
+    }
+
+    /// Reset the predictor and publish an empty snapshot
+    pub fn reset(&mut self) {
+        self.predictor.reset();
+        self.publish();
+    }
+
+    /// The predictor, for read-only queries such as `detect_cancellation`
+    pub fn predictor(&self) -> &GesturePredictor {
+        &self.predictor
+    }
+
+    fn publish(&mut self) {
+        match self.predictor.predict() {
+            Ok(prediction) => self.cell.write(
+                0,
+                prediction.position.x,
+                prediction.position.y,
+                prediction.confidence,
+            ),
+            Err(e) => self.cell.write(e.code(), 0.0, 0.0, 0.0),
+        }
+    }
+}
+
+/// Consumer half of an SPSC predictor
+pub struct PredictionReader {
+    cell: Arc<SnapshotCell>,
+}
+
+impl PredictionReader {
+    /// Read the latest snapshot without blocking the writer
+    pub fn latest(&self) -> PredictionSnapshot {
+        let (sequence, status, x, y, confidence) = self.cell.read();
+        let prediction = if status == 0 {
+            Some(Prediction::new(Point2D::new(x, y), confidence))
+        } else {
+            None
+        };
+
+        PredictionSnapshot {
+            sequence,
+            status,
+            prediction,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::PredictorError;
+
+    #[test]
+    fn test_snapshot_follows_writer() {
+        let (mut writer, reader) = split(PhysicsConfig::default()).unwrap();
+
+        let initial = reader.latest();
+        assert!(initial.prediction.is_none());
+        let too_few = PredictorError::InsufficientData { required: 2, actual: 0 };
+        assert_eq!(initial.status, too_few.code());
+
+        for i in 0..6 {
+            writer
+                .add_touch_point(i as f64 * 20.0, 0.0, i as f64 * 20.0)
+                .unwrap();
+        }
+
+        let snapshot = reader.latest();
+        assert_eq!(snapshot.status, 0);
+        assert!(snapshot.sequence > initial.sequence);
+        let expected = writer.predictor().predict().unwrap();
+        assert_eq!(snapshot.prediction.unwrap().position, expected.position);
+
+        // A rejected sample doesn't publish
+        assert!(writer.add_touch_point(0.0, 0.0, 0.0).is_err());
+        assert_eq!(reader.latest().sequence, snapshot.sequence);
+
+        writer.reset();
+        let after_reset = reader.latest();
+        assert!(after_reset.prediction.is_none());
+        assert!(after_reset.sequence > snapshot.sequence);
+    }
+
+    #[test]
+    fn test_reads_are_never_torn() {
+        let cell = Arc::new(SnapshotCell::new(0));
+        let reader_cell = Arc::clone(&cell);
+
+        let reader = std::thread::spawn(move || {
+            let mut last = 0;
+            while last < 10_000 {
+                let (sequence, status, x, y, confidence) = reader_cell.read();
+                assert!(sequence >= last);
+                // Every write stores the same value in all fields
+                assert_eq!(x, y);
+                assert_eq!(x, confidence);
+                assert_eq!(status as f64, x);
+                last = sequence;
+            }
+        });
+
+        for i in 1..=10_000 {
+            let v = (i % 1000) as f64;
+            cell.write(v as i32, v, v, v);
+        }
+        reader.join().unwrap();
+    }
+}
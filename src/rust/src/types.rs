@@ -1,6 +1,11 @@
-use std::ops::{Add, Sub, Mul, Div};
+use core::fmt;
+use core::ops::{Add, Sub, Mul, Div};
+
+use crate::error::PredictorError;
+use crate::math;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point2D {
     pub x: f64,
     pub y: f64,
@@ -14,11 +19,27 @@ impl Point2D {
     pub fn distance_to(&self, other: &Point2D) -> f64 {
         let dx = other.x - self.x;
         let dy = other.y - self.y;
-        (dx * dx + dy * dy).sqrt()
+        math::sqrt(dx * dx + dy * dy)
     }
 
     pub fn magnitude(&self) -> f64 {
-        (self.x * self.x + self.y * self.y).sqrt()
+        math::sqrt(self.x * self.x + self.y * self.y)
+    }
+
+    pub fn dot(&self, other: &Point2D) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn normalized(&self) -> Option<Self> {
+        let magnitude = self.magnitude();
+        if magnitude > f64::EPSILON {
+            Some(Self {
+                x: self.x / magnitude,
+                y: self.y / magnitude,
+            })
+        } else {
+            None
+        }
     }
 }
 
@@ -67,8 +88,25 @@ impl Div<f64> for Point2D {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "f64"))]
 pub struct Timestamp(pub f64); // milliseconds
 
+impl TryFrom<f64> for Timestamp {
+    type Error = PredictorError;
+
+    fn try_from(millis: f64) -> Result<Self, Self::Error> {
+        let timestamp = Self::new(millis);
+        if !timestamp.is_valid() {
+            return Err(PredictorError::InvalidTimestamp {
+                timestamp: millis,
+                reason: "must be non-negative and finite".into(),
+            });
+        }
+        Ok(timestamp)
+    }
+}
+
 impl Timestamp {
     pub fn new(millis: f64) -> Self {
         Self(millis)
@@ -104,9 +142,10 @@ impl Sub for Timestamp {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Velocity2D {
-    pub x: f64, // pixels per second
-    pub y: f64, // pixels per second
+    pub x: f64, // points per second
+    pub y: f64, // points per second
 }
 
 impl Velocity2D {
@@ -130,7 +169,7 @@ impl Velocity2D {
     }
 
     pub fn speed(&self) -> f64 {
-        (self.x * self.x + self.y * self.y).sqrt()
+        math::sqrt(self.x * self.x + self.y * self.y)
     }
 
     pub fn normalized(&self) -> Option<Self> {
@@ -157,12 +196,205 @@ impl Mul<f64> for Velocity2D {
     }
 }
 
+/// Physical pixels per density-independent point (an iOS point or Android dp):
+/// 1 on a @1x / mdpi display, 3 on a @3x / xxhdpi one.
+///
+/// Predictors work in points so that one `PhysicsConfig` behaves the same on
+/// every device; pixel coordinates are converted at the boundary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "f64"))]
+pub struct DisplayScale(f64);
+
+impl Default for DisplayScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl TryFrom<f64> for DisplayScale {
+    type Error = PredictorError;
+
+    fn try_from(pixels_per_point: f64) -> Result<Self, Self::Error> {
+        Self::new(pixels_per_point)
+    }
+}
+
+impl DisplayScale {
+    pub fn new(pixels_per_point: f64) -> Result<Self, PredictorError> {
+        if !pixels_per_point.is_finite() || pixels_per_point <= 0.0 {
+            return Err(PredictorError::InvalidConfiguration {
+                field: "display_scale".into(),
+                value: pixels_per_point,
+                reason: "must be positive and finite".into(),
+            });
+        }
+        Ok(Self(pixels_per_point))
+    }
+
+    pub fn pixels_per_point(&self) -> f64 {
+        self.0
+    }
+
+    pub fn to_points(&self, pixels: Point2D) -> Point2D {
+        pixels / self.0
+    }
+
+    pub fn to_pixels(&self, points: Point2D) -> Point2D {
+        points * self.0
+    }
+
+    pub fn distance_to_points(&self, pixels: f64) -> f64 {
+        pixels / self.0
+    }
+
+    pub fn distance_to_pixels(&self, points: f64) -> f64 {
+        points * self.0
+    }
+
+    pub fn velocity_to_points(&self, pixels_per_second: Velocity2D) -> Velocity2D {
+        pixels_per_second * (1.0 / self.0)
+    }
+
+    pub fn velocity_to_pixels(&self, points_per_second: Velocity2D) -> Velocity2D {
+        points_per_second * self.0
+    }
+}
+
+/// 2D affine transform mapping (x, y) to (a·x + c·y + tx, b·x + d·y + ty),
+/// the same layout as `CGAffineTransform` and CSS `matrix()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform2D {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub tx: f64,
+    pub ty: f64,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform2D {
+    pub const IDENTITY: Self = Self {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        tx: 0.0,
+        ty: 0.0,
+    };
+
+    pub fn translation(tx: f64, ty: f64) -> Self {
+        Self { tx, ty, ..Self::IDENTITY }
+    }
+
+    pub fn scale(sx: f64, sy: f64) -> Self {
+        Self { a: sx, d: sy, ..Self::IDENTITY }
+    }
+
+    /// Counter-clockwise rotation in a y-up space, which is clockwise on
+    /// screen where y points down
+    pub fn rotation(radians: f64) -> Self {
+        let (sin, cos) = math::sin_cos(radians);
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// The transform that applies `self` and then `next`
+    pub fn then(&self, next: &Transform2D) -> Self {
+        Self {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            tx: next.a * self.tx + next.c * self.ty + next.tx,
+            ty: next.b * self.tx + next.d * self.ty + next.ty,
+        }
+    }
+
+    pub fn apply(&self, point: Point2D) -> Point2D {
+        self.apply_vector(point) + Point2D::new(self.tx, self.ty)
+    }
+
+    /// Apply to a displacement, ignoring the translation
+    pub fn apply_vector(&self, vector: Point2D) -> Point2D {
+        Point2D::new(
+            self.a * vector.x + self.c * vector.y,
+            self.b * vector.x + self.d * vector.y,
+        )
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// The inverse transform, or `None` if this one collapses the plane onto
+    /// a line or point or isn't finite
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if !det.is_finite() || det.abs() < f64::EPSILON || !self.tx.is_finite() || !self.ty.is_finite() {
+            return None;
+        }
+
+        let a = self.d / det;
+        let b = -self.b / det;
+        let c = -self.c / det;
+        let d = self.a / det;
+        Some(Self {
+            a,
+            b,
+            c,
+            d,
+            tx: -(a * self.tx + c * self.ty),
+            ty: -(b * self.tx + d * self.ty),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedTouchPoint"))]
 pub struct TouchPoint {
     pub position: Point2D,
     pub timestamp: Timestamp,
 }
 
+/// A deserialized `TouchPoint` whose position hasn't been validated yet; the
+/// timestamp is checked by `Timestamp`'s own deserialization
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedTouchPoint {
+    position: Point2D,
+    timestamp: Timestamp,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedTouchPoint> for TouchPoint {
+    type Error = PredictorError;
+
+    fn try_from(point: UncheckedTouchPoint) -> Result<Self, Self::Error> {
+        let Point2D { x, y } = point.position;
+        if !x.is_finite() || !y.is_finite() {
+            return Err(PredictorError::InvalidCoordinate { x, y });
+        }
+        Ok(Self {
+            position: point.position,
+            timestamp: point.timestamp,
+        })
+    }
+}
+
 impl TouchPoint {
     pub fn new(x: f64, y: f64, timestamp_ms: f64) -> Option<Self> {
         let timestamp = Timestamp::new(timestamp_ms);
@@ -178,6 +410,7 @@ impl TouchPoint {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Prediction {
     pub position: Point2D,
     pub confidence: f64, // 0.0 to 1.0
@@ -192,6 +425,88 @@ impl Prediction {
     }
 }
 
+/// Whether a dragged element will be dismissed or return to where it started
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DismissalPrediction {
+    /// The projected resting position is at or past the dismissal threshold
+    pub will_dismiss: bool,
+    /// Probability (0.0 to 1.0) that the gesture ends past the threshold
+    pub probability: f64,
+    /// Current distance from the origin along the gesture's dominant direction
+    pub current_distance: f64,
+    /// Projected resting distance from the origin along the same direction
+    pub projected_distance: f64,
+}
+
+/// Lifecycle of the gesture a predictor is tracking
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub enum GestureState {
+    /// No gesture yet, or the predictor was reset
+    Idle = 0,
+    /// Touch is down and samples are arriving
+    Active = 1,
+    /// The touch was released; the final prediction is available
+    Ended = 2,
+    /// The system cancelled the touch; no prediction is made
+    Cancelled = 3,
+}
+
+impl fmt::Display for GestureState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GestureState::Idle => "idle",
+            GestureState::Active => "active",
+            GestureState::Ended => "ended",
+            GestureState::Cancelled => "cancelled",
+        };
+        f.write_str(name)
+    }
+}
+
+/// What to do with a sample whose timestamp equals the previous one's, as
+/// when the platform delivers a burst of coalesced events at once
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DuplicateTimestampPolicy {
+    /// Keep only the latest position
+    Merge = 0,
+    /// Keep the mean position of the burst
+    Average = 1,
+    /// Keep every sample, spreading the burst evenly over the time since the
+    /// previous distinct timestamp. Merges a burst at the very first timestamp.
+    Interpolate = 2,
+    /// Reject the sample with `InvalidTimestamp`
+    Reject = 3,
+    /// Buffer the sample as delivered, unless the input filter merges it.
+    /// Velocity estimates skip pairs with no time between them.
+    #[default]
+    Keep = 4,
+}
+
+impl TryFrom<i32> for DuplicateTimestampPolicy {
+    type Error = PredictorError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Merge),
+            1 => Ok(Self::Average),
+            2 => Ok(Self::Interpolate),
+            3 => Ok(Self::Reject),
+            4 => Ok(Self::Keep),
+            _ => Err(PredictorError::InvalidConfiguration {
+                field: "duplicate_timestamp_policy".into(),
+                value: value as f64,
+                reason: "unknown policy".into(),
+            }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,6 +528,52 @@ mod tests {
         assert_eq!(p4.y, 8.0);
     }
 
+    #[test]
+    fn test_display_scale_conversions() {
+        let scale = DisplayScale::new(3.0).unwrap();
+
+        let pixels = Point2D::new(300.0, 150.0);
+        let points = scale.to_points(pixels);
+        assert_eq!(points, Point2D::new(100.0, 50.0));
+        assert_eq!(scale.to_pixels(points), pixels);
+
+        assert_eq!(scale.distance_to_points(30.0), 10.0);
+        assert_eq!(scale.distance_to_pixels(10.0), 30.0);
+        let velocity = scale.velocity_to_points(Velocity2D::new(1500.0, 0.0));
+        assert_eq!(velocity, Velocity2D::new(500.0, 0.0));
+        assert_eq!(scale.velocity_to_pixels(velocity), Velocity2D::new(1500.0, 0.0));
+
+        assert_eq!(DisplayScale::default().pixels_per_point(), 1.0);
+        assert!(DisplayScale::new(0.0).is_err());
+        assert!(DisplayScale::new(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_transform2d() {
+        let p = Point2D::new(10.0, 5.0);
+        assert_eq!(Transform2D::IDENTITY.apply(p), p);
+
+        let moved = Transform2D::translation(3.0, -2.0);
+        assert_eq!(moved.apply(p), Point2D::new(13.0, 3.0));
+        assert_eq!(moved.apply_vector(p), p);
+
+        let rotated = Transform2D::rotation(std::f64::consts::FRAC_PI_2).apply(Point2D::new(1.0, 0.0));
+        assert!((rotated.x - 0.0).abs() < 1e-12 && (rotated.y - 1.0).abs() < 1e-12);
+
+        // Scale, then rotate, then translate
+        let transform = Transform2D::scale(2.0, 0.5)
+            .then(&Transform2D::rotation(0.3))
+            .then(&Transform2D::translation(40.0, -7.0));
+        let expected = Transform2D::translation(40.0, -7.0)
+            .apply(Transform2D::rotation(0.3).apply(Transform2D::scale(2.0, 0.5).apply(p)));
+        assert!(transform.apply(p).distance_to(&expected) < 1e-9);
+
+        let inverse = transform.inverse().unwrap();
+        assert!(inverse.apply(transform.apply(p)).distance_to(&p) < 1e-9);
+        assert!(Transform2D::scale(1.0, 0.0).inverse().is_none());
+        assert!(Transform2D::translation(f64::NAN, 0.0).inverse().is_none());
+    }
+
     #[test]
     fn test_timestamp_validation() {
         let t1 = Timestamp::new(100.0);
@@ -235,4 +596,45 @@ mod tests {
         assert_eq!(v.y, 0.0);
         assert_eq!(v.speed(), 1000.0);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let point = TouchPoint::new(12.5, -3.0, 16.0).unwrap();
+        let json = serde_json::to_string(&point).unwrap();
+        assert_eq!(json, r#"{"position":{"x":12.5,"y":-3.0},"timestamp":16.0}"#);
+        let decoded: TouchPoint = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.position, point.position);
+        assert_eq!(decoded.timestamp, point.timestamp);
+
+        let state: GestureState = serde_json::from_str(r#""Active""#).unwrap();
+        assert_eq!(state, GestureState::Active);
+
+        assert_eq!(serde_json::from_str::<DisplayScale>("3.0").unwrap().pixels_per_point(), 3.0);
+        assert!(serde_json::from_str::<DisplayScale>("0.0").is_err());
+
+        assert!(serde_json::from_str::<Timestamp>("-1.0").is_err());
+        assert!(serde_json::from_str::<TouchPoint>(
+            r#"{"position":{"x":0.0,"y":0.0},"timestamp":-16.0}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<TouchPoint>(
+            r#"{"position":{"x":1e999,"y":0.0},"timestamp":16.0}"#
+        )
+        .is_err());
+
+        let error = PredictorError::InsufficientData { required: 2, actual: 1 };
+        assert_eq!(
+            serde_json::to_string(&error).unwrap(),
+            r#"{"InsufficientData":{"required":2,"actual":1}}"#
+        );
+
+        // Errors logged by one process can be read back by another
+        let error = crate::physics::PhysicsConfig::new(-1.0, 50.0, 30.0).unwrap_err();
+        let json = serde_json::to_string(&error).unwrap();
+        let decoded: PredictorError = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, error);
+        assert_eq!(decoded.code(), error.code());
+        assert_eq!(decoded.to_string(), error.to_string());
+    }
 }
\ No newline at end of file
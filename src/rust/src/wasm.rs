@@ -0,0 +1,329 @@
+//! JavaScript bindings for React Native Web and Electron, where there is no
+//! native module. Built by build-wasm.sh with `cargo rustc --crate-type cdylib
+//! --no-default-features --features wasm` followed by `wasm-bindgen --target web`.
+//!
+//! Failures throw an `Error` named `PredictorError` whose `code` is the
+//! `PredictorError::code`, the same status the native bridges report.
+
+use wasm_bindgen::prelude::*;
+
+use crate::cancellation::CancellationReason;
+use crate::config::Preset;
+use crate::error::PredictorError;
+use crate::physics::PhysicsConfig;
+use crate::predictor::GesturePredictor;
+use crate::types::{DismissalPrediction, GestureState, Prediction};
+
+fn to_js_error(error: PredictorError) -> JsValue {
+    let js_error = js_sys::Error::new(&error.to_string());
+    js_error.set_name("PredictorError");
+    // Setting a property on a fresh `Error` can't fail
+    let _ = js_sys::Reflect::set(
+        &js_error,
+        &JsValue::from_str("code"),
+        &JsValue::from(error.code()),
+    );
+    js_error.into()
+}
+
+/// Physics parameters, in points and milliseconds like `PhysicsConfig`
+#[wasm_bindgen(js_name = PhysicsConfig)]
+#[derive(Debug, Clone, Copy)]
+pub struct WasmPhysicsConfig {
+    /// Deceleration rate in points/second²
+    #[wasm_bindgen(js_name = decelerationRate)]
+    pub deceleration_rate: f64,
+    /// Minimum velocity threshold in points/second
+    #[wasm_bindgen(js_name = minVelocityThreshold)]
+    pub min_velocity_threshold: f64,
+    /// Minimum gesture time in milliseconds
+    #[wasm_bindgen(js_name = minGestureTimeMs)]
+    pub min_gesture_time_ms: f64,
+}
+
+#[wasm_bindgen(js_class = PhysicsConfig)]
+impl WasmPhysicsConfig {
+    /// The default physics
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        PhysicsConfig::default().into()
+    }
+
+    #[wasm_bindgen(js_name = fromPreset)]
+    pub fn from_preset(preset: Preset) -> Self {
+        preset.physics().into()
+    }
+}
+
+impl Default for WasmPhysicsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<PhysicsConfig> for WasmPhysicsConfig {
+    fn from(config: PhysicsConfig) -> Self {
+        Self {
+            deceleration_rate: config.deceleration_rate,
+            min_velocity_threshold: config.min_velocity_threshold,
+            min_gesture_time_ms: config.min_gesture_time_ms,
+        }
+    }
+}
+
+impl From<WasmPhysicsConfig> for PhysicsConfig {
+    fn from(config: WasmPhysicsConfig) -> Self {
+        Self {
+            deceleration_rate: config.deceleration_rate,
+            min_velocity_threshold: config.min_velocity_threshold,
+            min_gesture_time_ms: config.min_gesture_time_ms,
+        }
+    }
+}
+
+/// Where the gesture is predicted to come to rest
+#[wasm_bindgen(js_name = Prediction)]
+#[derive(Debug, Clone, Copy)]
+pub struct WasmPrediction {
+    pub x: f64,
+    pub y: f64,
+    /// 0.0 to 1.0
+    pub confidence: f64,
+}
+
+impl From<Prediction> for WasmPrediction {
+    fn from(prediction: Prediction) -> Self {
+        Self {
+            x: prediction.position.x,
+            y: prediction.position.y,
+            confidence: prediction.confidence,
+        }
+    }
+}
+
+/// Whether a dragged element will be dismissed or return to where it started
+#[wasm_bindgen(js_name = DismissalPrediction)]
+#[derive(Debug, Clone, Copy)]
+pub struct WasmDismissalPrediction {
+    #[wasm_bindgen(js_name = willDismiss)]
+    pub will_dismiss: bool,
+    /// Probability (0.0 to 1.0) that the gesture ends past the threshold
+    pub probability: f64,
+    #[wasm_bindgen(js_name = currentDistance)]
+    pub current_distance: f64,
+    #[wasm_bindgen(js_name = projectedDistance)]
+    pub projected_distance: f64,
+}
+
+impl From<DismissalPrediction> for WasmDismissalPrediction {
+    fn from(dismissal: DismissalPrediction) -> Self {
+        Self {
+            will_dismiss: dismissal.will_dismiss,
+            probability: dismissal.probability,
+            current_distance: dismissal.current_distance,
+            projected_distance: dismissal.projected_distance,
+        }
+    }
+}
+
+/// `GesturePredictor` for JavaScript. Timestamps are in milliseconds, e.g.
+/// from `PointerEvent.timeStamp`.
+#[wasm_bindgen(js_name = GesturePredictor)]
+pub struct WasmGesturePredictor {
+    inner: GesturePredictor,
+}
+
+#[wasm_bindgen(js_class = GesturePredictor)]
+impl WasmGesturePredictor {
+    /// A predictor with the default physics
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            inner: GesturePredictor::new(PhysicsConfig::default())
+                .expect("default physics is valid"),
+        }
+    }
+
+    /// A predictor with the given physics, keeping up to `bufferSize` samples
+    #[wasm_bindgen(js_name = withPhysics)]
+    pub fn with_physics(
+        physics: &WasmPhysicsConfig,
+        #[wasm_bindgen(js_name = bufferSize)] buffer_size: Option<usize>,
+    ) -> Result<WasmGesturePredictor, JsValue> {
+        let physics = PhysicsConfig::from(*physics);
+        let inner = match buffer_size {
+            Some(buffer_size) => GesturePredictor::with_buffer_size(physics, buffer_size),
+            None => GesturePredictor::new(physics),
+        };
+        inner.map(|inner| Self { inner }).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = setPhysics)]
+    pub fn set_physics(&mut self, physics: &WasmPhysicsConfig) -> Result<(), JsValue> {
+        self.inner
+            .set_physics_config((*physics).into())
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn physics(&self) -> WasmPhysicsConfig {
+        self.inner.physics_config().into()
+    }
+
+    /// Start a new gesture at touch-down from any state, discarding the
+    /// previous one even if it was never ended
+    pub fn begin(
+        &mut self,
+        x: f64,
+        y: f64,
+        #[wasm_bindgen(js_name = timestampMs)] timestamp_ms: f64,
+    ) -> Result<(), JsValue> {
+        self.inner.begin(x, y, timestamp_ms).map_err(to_js_error)
+    }
+
+    /// Add a sample to the active gesture
+    pub fn update(
+        &mut self,
+        x: f64,
+        y: f64,
+        #[wasm_bindgen(js_name = timestampMs)] timestamp_ms: f64,
+    ) -> Result<(), JsValue> {
+        self.inner.update(x, y, timestamp_ms).map_err(to_js_error)
+    }
+
+    /// Mark the touch as released at `releaseTimeMs`
+    pub fn end(
+        &mut self,
+        #[wasm_bindgen(js_name = releaseTimeMs)] release_time_ms: f64,
+    ) -> Result<(), JsValue> {
+        self.inner.end(release_time_ms).map_err(to_js_error)
+    }
+
+    /// Mark the touch as cancelled by the system
+    pub fn cancel(&mut self) -> Result<(), JsValue> {
+        self.inner.cancel().map_err(to_js_error)
+    }
+
+    /// Add a sample without tracking begin/end, starting a gesture if needed
+    #[wasm_bindgen(js_name = addTouchPoint)]
+    pub fn add_touch_point(
+        &mut self,
+        x: f64,
+        y: f64,
+        #[wasm_bindgen(js_name = timestampMs)] timestamp_ms: f64,
+    ) -> Result<(), JsValue> {
+        self.inner
+            .add_touch_point(x, y, timestamp_ms)
+            .map_err(to_js_error)
+    }
+
+    pub fn predict(&self) -> Result<WasmPrediction, JsValue> {
+        self.inner.predict().map(Into::into).map_err(to_js_error)
+    }
+
+    /// Predict whether the gesture ends past `threshold` points from where it started
+    #[wasm_bindgen(js_name = predictDismissal)]
+    pub fn predict_dismissal(&self, threshold: f64) -> Result<WasmDismissalPrediction, JsValue> {
+        self.inner
+            .predict_dismissal(threshold)
+            .map(Into::into)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = detectCancellation)]
+    pub fn detect_cancellation(&self) -> bool {
+        self.inner.detect_cancellation()
+    }
+
+    /// Why the gesture looks abandoned, if it does
+    #[wasm_bindgen(getter)]
+    pub fn cancellation(&self) -> Option<CancellationReason> {
+        self.inner.cancellation()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> GestureState {
+        self.inner.state()
+    }
+
+    #[wasm_bindgen(getter, js_name = pointCount)]
+    pub fn point_count(&self) -> usize {
+        self.inner.point_count()
+    }
+
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+impl Default for WasmGesturePredictor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn swipe(predictor: &mut WasmGesturePredictor) {
+        for i in 0..8 {
+            predictor
+                .add_touch_point(i as f64 * 20.0, 0.0, i as f64 * 16.0)
+                .unwrap();
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_matches_native_predictor() {
+        let mut predictor = WasmGesturePredictor::new();
+        let mut native = GesturePredictor::new(PhysicsConfig::default()).unwrap();
+        swipe(&mut predictor);
+        for i in 0..8 {
+            native
+                .add_touch_point(i as f64 * 20.0, 0.0, i as f64 * 16.0)
+                .unwrap();
+        }
+
+        let prediction = predictor.predict().unwrap();
+        let expected = native.predict().unwrap();
+        assert_eq!(prediction.x, expected.position.x);
+        assert_eq!(prediction.y, expected.position.y);
+        assert_eq!(prediction.confidence, expected.confidence);
+        assert_eq!(predictor.point_count(), 8);
+        assert_eq!(predictor.state(), GestureState::Active);
+        assert!(predictor.predict_dismissal(100.0).unwrap().will_dismiss);
+
+        // Faster deceleration stops the same swipe sooner
+        let fast = WasmPhysicsConfig::from_preset(Preset::IosFast);
+        let mut fast = WasmGesturePredictor::with_physics(&fast, Some(20)).unwrap();
+        swipe(&mut fast);
+        assert!(fast.predict().unwrap().x < prediction.x);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_errors_carry_code() {
+        let predictor = WasmGesturePredictor::new();
+        let error = predictor
+            .predict()
+            .unwrap_err()
+            .dyn_into::<js_sys::Error>()
+            .unwrap();
+        assert_eq!(error.name(), "PredictorError");
+        let code = js_sys::Reflect::get(&error, &JsValue::from_str("code")).unwrap();
+        let expected = PredictorError::InsufficientData {
+            required: 2,
+            actual: 0,
+        }
+        .code();
+        assert_eq!(code.as_f64(), Some(expected as f64));
+
+        let invalid = WasmPhysicsConfig {
+            deceleration_rate: -1.0,
+            ..WasmPhysicsConfig::new()
+        };
+        assert!(WasmGesturePredictor::with_physics(&invalid, None).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use swipe_predictor::*;
+
+/// Counts allocations made on the current thread, so tests running in
+/// parallel don't see each other's
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn test_fixed_predictor_does_not_allocate() {
+    let mut predictor = FixedGesturePredictor::<16>::fixed(PhysicsConfig::default()).unwrap();
+    predictor
+        .set_input_filter(Some(InputFilterConfig {
+            max_acceleration: Some(1_000_000.0),
+            merge_duplicates: true,
+            one_euro: Some(OneEuroConfig::default()),
+        }))
+        .unwrap();
+    predictor
+        .set_resampling(Some(ResampleConfig {
+            rate_hz: 120.0,
+            interpolation: Interpolation::CatmullRom,
+        }))
+        .unwrap();
+    predictor.set_event_thresholds(Some(EventThresholds::default())).unwrap();
+
+    let before = allocations();
+    // Enough samples to wrap the ring buffer several times
+    for i in 0..64 {
+        let t = i as f64 * 16.0;
+        predictor.add_touch_point(t * 1.5, 200.0 + t * 0.2, t).unwrap();
+        // Errors for too short a gesture don't allocate either
+        let prediction = predictor.predict();
+        let dismissal = predictor.predict_dismissal(300.0);
+        if i >= 2 {
+            assert!(prediction.unwrap().position.x > t * 1.5);
+            assert!(dismissal.is_ok());
+        }
+        predictor.detect_cancellation();
+        while predictor.poll_event().is_some() {}
+    }
+    predictor.end(64.0 * 16.0).unwrap();
+    predictor.predict().unwrap();
+    predictor.reset();
+
+    assert_eq!(allocations() - before, 0);
+}
+
+#[test]
+fn test_allocations_are_counted() {
+    let before = allocations();
+    let predictor = GesturePredictor::new(PhysicsConfig::default()).unwrap();
+    assert!(allocations() > before);
+    drop(predictor);
+}
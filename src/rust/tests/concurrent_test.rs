@@ -35,4 +35,34 @@ fn test_predictor_thread_safety() {
         assert!(pred.position.y > 0.0);
         assert!(pred.confidence > 0.0);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_spsc_writer_and_reader_on_separate_threads() {
+    let (mut writer, reader) = swipe_predictor::spsc::split(PhysicsConfig::default()).unwrap();
+
+    let producer = thread::spawn(move || {
+        for i in 0..500 {
+            writer.add_touch_point(i as f64 * 5.0, 0.0, i as f64 * 5.0).unwrap();
+        }
+        writer
+    });
+
+    // Poll like a UI thread would while samples arrive
+    let mut last_sequence = 0;
+    while !producer.is_finished() {
+        let snapshot = reader.latest();
+        assert!(snapshot.sequence >= last_sequence);
+        if let Some(prediction) = snapshot.prediction {
+            assert!(prediction.position.x.is_finite());
+            assert!(prediction.confidence >= 0.0 && prediction.confidence <= 1.0);
+        }
+        last_sequence = snapshot.sequence;
+    }
+
+    let writer = producer.join().unwrap();
+    let final_snapshot = reader.latest();
+    let expected = writer.predictor().predict().unwrap();
+    assert_eq!(final_snapshot.prediction.unwrap().position, expected.position);
+}